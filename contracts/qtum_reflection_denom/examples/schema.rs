@@ -0,0 +1,28 @@
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+use cosmwasm_schema::{export_schema, export_schema_with_title, remove_schemas, schema_for};
+
+use cosmwasm_std::Addr;
+use cw_ownable::Ownership;
+use qtum_reflection_denom::msg::{
+    ContractVersionResponse, DenomResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, PairConfig,
+    QueryMsg, TaxRatesResponse,
+};
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
+    export_schema(&schema_for!(DenomResponse), &out_dir);
+    export_schema(&schema_for!(TaxRatesResponse), &out_dir);
+    export_schema(&schema_for!(PairConfig), &out_dir);
+    export_schema(&schema_for!(ContractVersionResponse), &out_dir);
+    export_schema_with_title(&schema_for!(Ownership<Addr>), &out_dir, "OwnershipResponse");
+}
@@ -0,0 +1,605 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    coin, to_json_binary, Addr, BankMsg, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Response, StdError, StdResult, Storage, Uint128, WasmMsg,
+};
+use cw0::{must_pay, one_coin};
+use cw2::{get_contract_version, set_contract_version};
+use cw20_base::ContractError;
+use cw_storage_plus::{Item, Map};
+use dojoswap::asset::{Asset, AssetInfo};
+use semver::Version;
+
+use crate::msg::{
+    ContractVersionResponse, DenomResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, PairConfig,
+    QueryMsg, TaxRatesResponse,
+};
+use crate::tokenfactory;
+
+// version info for migration info
+const CONTRACT_NAME: &str = "qtum:reflection-denom";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// token-factory denom created at instantiate, administered by the contract
+pub const DENOM: Item<String> = Item::new("denom");
+pub const BUY_TAX_RATE: Item<Decimal> = Item::new("buy_tax_rate");
+pub const SELL_TAX_RATE: Item<Decimal> = Item::new("sell_tax_rate");
+pub const TRANSFER_TAX_RATE: Item<Decimal> = Item::new("transfer_tax_rate");
+// share of every tax that is burned, the rest goes to the treasury
+pub const BURN_RATE: Item<Decimal> = Item::new("burn_rate");
+pub const TREASURY: Item<Addr> = Item::new("treasury");
+pub const PAIRLIST: Map<&Addr, PairConfig> = Map::new("pairlist");
+// addresses that never pay taxes, e.g. routers, lockers, partner contracts
+pub const TAX_EXEMPT: Map<&Addr, bool> = Map::new("tax_exempt");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(info.sender.as_str()))?;
+
+    let denom = tokenfactory::full_denom(&env.contract.address, &msg.subdenom);
+    DENOM.save(deps.storage, &denom)?;
+    BUY_TAX_RATE.save(deps.storage, &Decimal::zero())?;
+    SELL_TAX_RATE.save(deps.storage, &Decimal::zero())?;
+    TRANSFER_TAX_RATE.save(deps.storage, &Decimal::zero())?;
+    BURN_RATE.save(deps.storage, &Decimal::zero())?;
+    if let Some(treasury) = msg.treasury {
+        TREASURY.save(deps.storage, &deps.api.addr_validate(&treasury)?)?;
+    }
+
+    // The denom is minted to the contract, which distributes the initial balances
+    let mut messages = vec![tokenfactory::create_denom(
+        &env.contract.address,
+        &msg.subdenom,
+    )];
+    let total_supply = msg
+        .initial_balances
+        .iter()
+        .try_fold(Uint128::zero(), |total, row| total.checked_add(row.amount))
+        .map_err(StdError::from)?;
+    if !total_supply.is_zero() {
+        messages.push(tokenfactory::mint(
+            &env.contract.address,
+            coin(total_supply.u128(), &denom),
+        ));
+    }
+    for row in msg.initial_balances.iter() {
+        deps.api.addr_validate(&row.address)?;
+        if !row.amount.is_zero() {
+            messages.push(
+                BankMsg::Send {
+                    to_address: row.address.clone(),
+                    amount: vec![coin(row.amount.u128(), &denom)],
+                }
+                .into(),
+            );
+        }
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "instantiate")
+        .add_attribute("denom", denom))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Transfer { recipient } => execute_transfer(deps, env, info, recipient),
+        ExecuteMsg::Sell { pair, max_spread } => execute_sell(deps, env, info, pair, max_spread),
+        ExecuteMsg::Buy { pair, max_spread } => execute_buy(deps, env, info, pair, max_spread),
+        ExecuteMsg::FinishBuy {
+            recipient,
+            pair,
+            balance_before,
+        } => finish_buy(deps, env, info, recipient, pair, balance_before),
+        ExecuteMsg::Mint { recipient, amount } => execute_mint(deps, env, info, recipient, amount),
+        ExecuteMsg::Burn {} => execute_burn(deps, env, info),
+        ExecuteMsg::SetTaxRates {
+            buy,
+            sell,
+            transfer,
+        } => set_tax_rates(deps, info, buy, sell, transfer),
+        ExecuteMsg::SetBurnRate { rate } => set_burn_rate(deps, info, rate),
+        ExecuteMsg::SetPair {
+            contract,
+            enable,
+            tax_rate,
+        } => set_pair(deps, info, contract, enable, tax_rate),
+        ExecuteMsg::SetTaxExempt { address, exempt } => set_tax_exempt(deps, info, address, exempt),
+        ExecuteMsg::SetTreasury { contract } => set_treasury(deps, info, contract),
+        ExecuteMsg::UpdateOwnership(action) => update_ownership(deps, env, info, action),
+    }
+}
+
+/// Sends the attached denom to `recipient` minus the transfer tax
+pub fn execute_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let denom = DENOM.load(deps.storage)?;
+    let amount = must_pay(&info, &denom).map_err(|err| StdError::generic_err(err.to_string()))?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    let rate =
+        if is_tax_exempt(deps.storage, &info.sender)? || is_tax_exempt(deps.storage, &recipient)? {
+            Decimal::zero()
+        } else {
+            TRANSFER_TAX_RATE.load(deps.storage)?
+        };
+    let (received, mut messages) = take_tax(deps.storage, &env, &denom, amount, rate)?;
+    if !received.is_zero() {
+        messages.push(
+            BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![coin(received.u128(), &denom)],
+            }
+            .into(),
+        );
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "transfer")
+        .add_attribute("from", info.sender)
+        .add_attribute("to", recipient)
+        .add_attribute("amount", received)
+        .add_attribute("tax", amount - received))
+}
+
+/// Swaps the attached denom minus the sell tax on a listed pair, for the sender
+pub fn execute_sell(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pair: String,
+    max_spread: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let denom = DENOM.load(deps.storage)?;
+    let amount = must_pay(&info, &denom).map_err(|err| StdError::generic_err(err.to_string()))?;
+    let pair = deps.api.addr_validate(&pair)?;
+    let config = load_pair(deps.storage, &pair)?;
+
+    let rate = if is_tax_exempt(deps.storage, &info.sender)? {
+        Decimal::zero()
+    } else {
+        config
+            .tax_rate
+            .map_or_else(|| SELL_TAX_RATE.load(deps.storage), Ok)?
+    };
+    let (offer_amount, mut messages) = take_tax(deps.storage, &env, &denom, amount, rate)?;
+    messages.push(
+        WasmMsg::Execute {
+            contract_addr: pair.to_string(),
+            msg: to_json_binary(&dojoswap::pair::ExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: denom.clone(),
+                    },
+                    amount: offer_amount,
+                },
+                belief_price: None,
+                max_spread,
+                to: Some(info.sender.to_string()),
+                deadline: None,
+            })?,
+            funds: vec![coin(offer_amount.u128(), &denom)],
+        }
+        .into(),
+    );
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "sell")
+        .add_attribute("pair", pair)
+        .add_attribute("amount", offer_amount)
+        .add_attribute("tax", amount - offer_amount))
+}
+
+/// Swaps the attached coin into the denom on a listed pair, the callback taxes what was bought
+pub fn execute_buy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pair: String,
+    max_spread: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let denom = DENOM.load(deps.storage)?;
+    let offer = one_coin(&info).map_err(|err| StdError::generic_err(err.to_string()))?;
+    let pair = deps.api.addr_validate(&pair)?;
+    load_pair(deps.storage, &pair)?;
+    let balance_before = deps
+        .querier
+        .query_balance(env.contract.address.clone(), &denom)?;
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: pair.to_string(),
+            msg: to_json_binary(&dojoswap::pair::ExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: offer.denom.clone(),
+                    },
+                    amount: offer.amount,
+                },
+                belief_price: None,
+                max_spread,
+                to: None,
+                deadline: None,
+            })?,
+            funds: vec![offer],
+        })
+        // Runs after the swap so only the denom bought is taxed
+        .add_message(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_json_binary(&ExecuteMsg::FinishBuy {
+                recipient: info.sender.to_string(),
+                pair: pair.to_string(),
+                balance_before: balance_before.amount,
+            })?,
+            funds: vec![],
+        })
+        .add_attribute("action", "buy")
+        .add_attribute("pair", pair))
+}
+
+/// Internal callback of `execute_buy`.
+/// Sends the denom bought since `balance_before` to `recipient` minus the buy tax
+pub fn finish_buy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    pair: String,
+    balance_before: Uint128,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let denom = DENOM.load(deps.storage)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let config = load_pair(deps.storage, &deps.api.addr_validate(&pair)?)?;
+    let balance = deps
+        .querier
+        .query_balance(env.contract.address.clone(), &denom)?;
+    let amount = balance.amount.saturating_sub(balance_before);
+
+    let rate = if is_tax_exempt(deps.storage, &recipient)? {
+        Decimal::zero()
+    } else {
+        config
+            .tax_rate
+            .map_or_else(|| BUY_TAX_RATE.load(deps.storage), Ok)?
+    };
+    let (received, mut messages) = take_tax(deps.storage, &env, &denom, amount, rate)?;
+    if !received.is_zero() {
+        messages.push(
+            BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: vec![coin(received.u128(), &denom)],
+            }
+            .into(),
+        );
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "finish_buy")
+        .add_attribute("to", recipient)
+        .add_attribute("amount", received)
+        .add_attribute("tax", amount - received))
+}
+
+/// Deducts the tax at `rate` from `amount` held by the contract. The burn share of the tax is
+/// burned and the rest is sent to the treasury, or burned too without treasury.
+/// Returns the amount left after tax and the messages settling the tax
+pub fn take_tax(
+    storage: &dyn Storage,
+    env: &Env,
+    denom: &str,
+    amount: Uint128,
+    rate: Decimal,
+) -> StdResult<(Uint128, Vec<CosmosMsg>)> {
+    let tax = amount * rate;
+    if tax.is_zero() {
+        return Ok((amount, vec![]));
+    }
+
+    let mut burn = tax * BURN_RATE.may_load(storage)?.unwrap_or_default();
+    let mut messages = vec![];
+    match TREASURY.may_load(storage)? {
+        Some(treasury) if burn < tax => messages.push(
+            BankMsg::Send {
+                to_address: treasury.to_string(),
+                amount: vec![coin((tax - burn).u128(), denom)],
+            }
+            .into(),
+        ),
+        _ => burn = tax,
+    }
+    if !burn.is_zero() {
+        messages.push(tokenfactory::burn(
+            &env.contract.address,
+            coin(burn.u128(), denom),
+        ));
+    }
+
+    Ok((amount - tax, messages))
+}
+
+pub fn load_pair(storage: &dyn Storage, pair: &Addr) -> StdResult<PairConfig> {
+    match PAIRLIST.may_load(storage, pair)? {
+        Some(config) if config.enabled => Ok(config),
+        _ => Err(StdError::generic_err("pair is not listed")),
+    }
+}
+
+pub fn is_tax_exempt(storage: &dyn Storage, address: &Addr) -> StdResult<bool> {
+    Ok(TAX_EXEMPT.may_load(storage, address)?.unwrap_or_default())
+}
+
+/// Mints `amount` to `recipient` without taxes
+pub fn execute_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let denom = DENOM.load(deps.storage)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    Ok(Response::new()
+        .add_message(tokenfactory::mint(
+            &env.contract.address,
+            coin(amount.u128(), &denom),
+        ))
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin(amount.u128(), denom)],
+        })
+        .add_attribute("action", "mint")
+        .add_attribute("to", recipient)
+        .add_attribute("amount", amount))
+}
+
+/// Burns the attached denom
+pub fn execute_burn(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let denom = DENOM.load(deps.storage)?;
+    let amount = must_pay(&info, &denom).map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    Ok(Response::new()
+        .add_message(tokenfactory::burn(
+            &env.contract.address,
+            coin(amount.u128(), denom),
+        ))
+        .add_attribute("action", "burn")
+        .add_attribute("from", info.sender)
+        .add_attribute("amount", amount))
+}
+
+/// Tax rates are numbers between 0 and 1, 0.05 refers to a 5% tax
+pub fn set_tax_rates(
+    deps: DepsMut,
+    info: MessageInfo,
+    buy: Decimal,
+    sell: Decimal,
+    transfer: Decimal,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    if buy > Decimal::one() || sell > Decimal::one() || transfer > Decimal::one() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "tax rates must be <= 1",
+        )));
+    }
+
+    BUY_TAX_RATE.save(deps.storage, &buy)?;
+    SELL_TAX_RATE.save(deps.storage, &sell)?;
+    TRANSFER_TAX_RATE.save(deps.storage, &transfer)?;
+    Ok(Response::new().add_attribute("action", "set_tax_rates"))
+}
+
+pub fn set_burn_rate(
+    deps: DepsMut,
+    info: MessageInfo,
+    rate: Decimal,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    if rate > Decimal::one() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "burn rate must be <= 1",
+        )));
+    }
+
+    BURN_RATE.save(deps.storage, &rate)?;
+    Ok(Response::new().add_attribute("action", "set_burn_rate"))
+}
+
+pub fn set_pair(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: String,
+    enable: bool,
+    tax_rate: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    if tax_rate.is_some_and(|rate| rate > Decimal::one()) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "tax rate must be <= 1",
+        )));
+    }
+
+    let pair = deps.api.addr_validate(&contract)?;
+    PAIRLIST.save(
+        deps.storage,
+        &pair,
+        &PairConfig {
+            enabled: enable,
+            tax_rate,
+        },
+    )?;
+    Ok(Response::new()
+        .add_attribute("action", "set_pair")
+        .add_attribute("pair", pair)
+        .add_attribute("enable", enable.to_string()))
+}
+
+pub fn set_tax_exempt(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    exempt: bool,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let address = deps.api.addr_validate(&address)?;
+    if exempt {
+        TAX_EXEMPT.save(deps.storage, &address, &true)?;
+    } else {
+        TAX_EXEMPT.remove(deps.storage, &address);
+    }
+    Ok(Response::new()
+        .add_attribute("action", "set_tax_exempt")
+        .add_attribute("address", address)
+        .add_attribute("exempt", exempt.to_string()))
+}
+
+pub fn set_treasury(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    match contract {
+        Some(contract) => {
+            let treasury = deps.api.addr_validate(&contract)?;
+            TREASURY.save(deps.storage, &treasury)?
+        }
+        None => TREASURY.remove(deps.storage),
+    }
+    Ok(Response::new().add_attribute("action", "set_treasury"))
+}
+
+/// Ensures only admins (the cw-ownable owner) can use this function
+pub fn ensure_admin(deps: &DepsMut, info: &MessageInfo) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)
+        .map_err(|err| ContractError::Std(StdError::generic_err(err.to_string())))?;
+
+    Ok(Response::default())
+}
+
+/// Proposes, accepts or renounces the ownership of the contract
+pub fn update_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: cw_ownable::Action,
+) -> Result<Response, ContractError> {
+    let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)
+        .map_err(|err| ContractError::Std(StdError::generic_err(err.to_string())))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_ownership")
+        .add_attributes(ownership.into_attributes()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Denom {} => to_json_binary(&DenomResponse {
+            denom: DENOM.load(deps.storage)?,
+        }),
+        QueryMsg::TaxRates {} => to_json_binary(&TaxRatesResponse {
+            buy: BUY_TAX_RATE.load(deps.storage)?,
+            sell: SELL_TAX_RATE.load(deps.storage)?,
+            transfer: TRANSFER_TAX_RATE.load(deps.storage)?,
+            burn_rate: BURN_RATE.load(deps.storage)?,
+        }),
+        QueryMsg::Pair { contract } => {
+            let pair = deps.api.addr_validate(&contract)?;
+            to_json_binary(&PAIRLIST.may_load(deps.storage, &pair)?)
+        }
+        QueryMsg::TaxExempt { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(&is_tax_exempt(deps.storage, &address)?)
+        }
+        QueryMsg::GetTreasury {} => to_json_binary(&TREASURY.may_load(deps.storage)?),
+        QueryMsg::Ownership {} => to_json_binary(&cw_ownable::get_ownership(deps.storage)?),
+        QueryMsg::ContractVersion {} => to_json_binary(&query_contract_version(deps.storage)?),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored_version = ensure_upgrade(deps.storage)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", stored_version.to_string())
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
+/// Returns the stored version, only upgrades of the reflection denom are accepted
+pub fn ensure_upgrade(storage: &dyn Storage) -> StdResult<Version> {
+    let stored = get_contract_version(storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(StdError::generic_err(format!(
+            "Cannot migrate from contract {}",
+            stored.contract
+        )));
+    }
+    let stored_version = parse_version(&stored.version)?;
+    if stored_version > parse_version(CONTRACT_VERSION)? {
+        return Err(StdError::generic_err(format!(
+            "Cannot downgrade from version {} to {}",
+            stored_version, CONTRACT_VERSION
+        )));
+    }
+    Ok(stored_version)
+}
+
+fn parse_version(version: &str) -> StdResult<Version> {
+    version
+        .parse()
+        .map_err(|err: semver::Error| StdError::generic_err(err.to_string()))
+}
+
+/// Returns the cw2 version with the package and the crate features the contract was built with
+pub fn query_contract_version(storage: &dyn Storage) -> StdResult<ContractVersionResponse> {
+    let version = get_contract_version(storage)?;
+    let features = [
+        ("backtraces", cfg!(feature = "backtraces")),
+        ("library", cfg!(feature = "library")),
+    ]
+    .iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(feature, _)| feature.to_string())
+    .collect();
+
+    Ok(ContractVersionResponse {
+        contract: version.contract,
+        version: version.version,
+        package: env!("CARGO_PKG_NAME").to_string(),
+        features,
+    })
+}
@@ -0,0 +1,3 @@
+pub mod contract;
+pub mod msg;
+pub mod tokenfactory;
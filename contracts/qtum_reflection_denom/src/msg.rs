@@ -0,0 +1,129 @@
+use cosmwasm_std::{Decimal, Uint128};
+use cw20::Cw20Coin;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct InstantiateMsg {
+    /// The contract creates and administers `factory/{contract}/{subdenom}`
+    pub subdenom: String,
+    /// Minted at instantiation, without taxes
+    pub initial_balances: Vec<Cw20Coin>,
+    /// Receives the taxes that are not burned
+    pub treasury: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Sends the attached denom to `recipient` minus the transfer tax.
+    /// The contract only routes the denom, it does not enforce taxes: plain bank sends and swaps
+    /// sent to the pairs directly are not taxed
+    Transfer { recipient: String },
+    /// Sells the attached denom on a listed pair, minus the sell tax. The output is sent to the
+    /// sender
+    Sell {
+        pair: String,
+        max_spread: Option<Decimal>,
+    },
+    /// Buys the denom on a listed pair with the attached coin. The denom bought is sent to the
+    /// sender minus the buy tax
+    Buy {
+        pair: String,
+        max_spread: Option<Decimal>,
+    },
+    /// Internal use
+    /// Taxes the denom bought since `balance_before` and sends the rest to `recipient`
+    FinishBuy {
+        recipient: String,
+        pair: String,
+        balance_before: Uint128,
+    },
+    /// Only with the admin role. Mints `amount` to `recipient` without taxes
+    Mint { recipient: String, amount: Uint128 },
+    /// Burns the attached denom
+    Burn {},
+    /// Only with the admin role. Sets the buy, sell and wallet-to-wallet tax rates
+    SetTaxRates {
+        buy: Decimal,
+        sell: Decimal,
+        transfer: Decimal,
+    },
+    /// Only with the admin role. Share of every tax that is burned, the rest goes to the treasury
+    SetBurnRate { rate: Decimal },
+    /// Only with the admin role. Lists a pair `Buy` and `Sell` swap on
+    SetPair {
+        contract: String,
+        enable: bool,
+        /// Overrides the buy and sell tax rates for this pair
+        tax_rate: Option<Decimal>,
+    },
+    /// Only with the admin role. Tax exempt addresses never pay taxes
+    SetTaxExempt { address: String, exempt: bool },
+    /// Only with the admin role. Sets the treasury that receives the taxes, `None` burns them
+    SetTreasury { contract: Option<String> },
+    /// Proposes, accepts or renounces the ownership of the contract. The owner is the admin role
+    UpdateOwnership(cw_ownable::Action),
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the token-factory denom managed by the contract.
+    /// Return type: DenomResponse.
+    Denom {},
+    /// Returns the tax rates and the share of taxes burned.
+    /// Return type: TaxRatesResponse.
+    TaxRates {},
+    /// Returns the config of a listed pair, null if unlisted.
+    /// Return type: Option<PairConfig>.
+    Pair { contract: String },
+    /// Returns whether the address is exempt from taxes.
+    /// Return type: bool.
+    TaxExempt { address: String },
+    /// Returns the treasury that receives the taxes, null if they are burned.
+    /// Return type: Option<Addr>.
+    GetTreasury {},
+    /// Returns the owner of the contract and any pending ownership transfer.
+    /// Return type: cw_ownable::Ownership<Addr>.
+    Ownership {},
+    /// Returns the cw2 name and version of the contract, with the package and features it was
+    /// built with.
+    /// Return type: ContractVersionResponse.
+    ContractVersion {},
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PairConfig {
+    pub enabled: bool,
+    /// Overrides the buy and sell tax rates for this pair
+    pub tax_rate: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct DenomResponse {
+    pub denom: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TaxRatesResponse {
+    pub buy: Decimal,
+    pub sell: Decimal,
+    pub transfer: Decimal,
+    pub burn_rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ContractVersionResponse {
+    /// cw2 contract name
+    pub contract: String,
+    /// cw2 contract version
+    pub version: String,
+    /// Cargo package the contract was built from, e.g. `qtum-reflection-token`
+    pub package: String,
+    /// Crate features enabled in the build
+    pub features: Vec<String>,
+}
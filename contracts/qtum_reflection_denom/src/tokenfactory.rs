@@ -0,0 +1,66 @@
+use cosmwasm_std::{Addr, Binary, Coin, CosmosMsg};
+
+// messages of the Injective token-factory module
+const TOKENFACTORY_PREFIX: &str = "/injective.tokenfactory.v1beta1";
+
+/// Denom created by `creator` with `subdenom`
+pub fn full_denom(creator: &Addr, subdenom: &str) -> String {
+    format!("factory/{}/{}", creator, subdenom)
+}
+
+/// Creates `factory/{sender}/{subdenom}`, `sender` becomes its admin
+pub fn create_denom(sender: &Addr, subdenom: &str) -> CosmosMsg {
+    let mut value = vec![];
+    encode_bytes(&mut value, 1, sender.as_bytes());
+    encode_bytes(&mut value, 2, subdenom.as_bytes());
+    stargate("MsgCreateDenom", value)
+}
+
+/// Mints `amount` to `sender`, which must be the denom admin
+pub fn mint(sender: &Addr, amount: Coin) -> CosmosMsg {
+    let mut value = vec![];
+    encode_bytes(&mut value, 1, sender.as_bytes());
+    encode_bytes(&mut value, 2, &encode_coin(&amount));
+    stargate("MsgMint", value)
+}
+
+/// Burns `amount` from the balance of `sender`, which must be the denom admin
+pub fn burn(sender: &Addr, amount: Coin) -> CosmosMsg {
+    let mut value = vec![];
+    encode_bytes(&mut value, 1, sender.as_bytes());
+    encode_bytes(&mut value, 2, &encode_coin(&amount));
+    stargate("MsgBurn", value)
+}
+
+fn stargate(msg: &str, value: Vec<u8>) -> CosmosMsg {
+    CosmosMsg::Stargate {
+        type_url: format!("{}.{}", TOKENFACTORY_PREFIX, msg),
+        value: Binary::from(value),
+    }
+}
+
+// The messages only have string and embedded message fields, so only length delimited fields are
+// encoded. Empty fields are left out as protobuf does
+fn encode_bytes(buf: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+    if bytes.is_empty() {
+        return;
+    }
+    encode_varint(buf, field << 3 | 2);
+    encode_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+fn encode_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn encode_coin(coin: &Coin) -> Vec<u8> {
+    let mut value = vec![];
+    encode_bytes(&mut value, 1, coin.denom.as_bytes());
+    encode_bytes(&mut value, 2, coin.amount.to_string().as_bytes());
+    value
+}
@@ -1,13 +1,24 @@
 use std::env::current_dir;
 use std::fs::create_dir_all;
 
-use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+use cosmwasm_schema::{export_schema, export_schema_with_title, remove_schemas, schema_for};
+use cosmwasm_std::Addr;
 
 use cw20::{
     AllAccountsResponse, AllAllowancesResponse, AllowanceResponse, BalanceResponse,
-    TokenInfoResponse,
+    DownloadLogoResponse, MarketingInfoResponse, MinterResponse, TokenInfoResponse,
+};
+use cw_ownable::Ownership;
+use qtum_reflection_token::msg::{
+    AccountTaxPaid, AddressListResponse, AddressStatusResponse, AllowanceLimitResponse,
+    AntiWhaleTax, AuditLogResponse, ContractVersionResponse, DynamicTaxResponse, ExecuteMsg,
+    ExportStateResponse, InstantiateMsg, LaunchProtection, LiquifyConfigResponse, LocksResponse,
+    MerkleStage, MigrateMsg, PairConfig, PendingRates, PendingReflectionResponse, PriceImpactTax,
+    QueryMsg, QueryTaxResponse, RateHistoryResponse, RatesResponse, SellAllowanceResponse,
+    SellLimit, SupplyCheckResponse, TaxDecayResponse, TaxMode, TaxRatesResponse, TaxSplitResponse,
+    TaxStats, TaxStrategyResponse, TaxTiersResponse, TreasuryResponse, VestingResponse,
+    VotingPowerResponse,
 };
-use cw20_base::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -18,9 +29,51 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
+
+    // cw20 queries
     export_schema(&schema_for!(AllowanceResponse), &out_dir);
     export_schema(&schema_for!(BalanceResponse), &out_dir);
     export_schema(&schema_for!(TokenInfoResponse), &out_dir);
     export_schema(&schema_for!(AllAllowancesResponse), &out_dir);
     export_schema(&schema_for!(AllAccountsResponse), &out_dir);
+    export_schema(&schema_for!(MinterResponse), &out_dir);
+    export_schema(&schema_for!(MarketingInfoResponse), &out_dir);
+    export_schema(&schema_for!(DownloadLogoResponse), &out_dir);
+    export_schema_with_title(&schema_for!(Ownership<Addr>), &out_dir, "OwnershipResponse");
+
+    // Reflection queries
+    export_schema(&schema_for!(AccountTaxPaid), &out_dir);
+    export_schema(&schema_for!(AddressListResponse), &out_dir);
+    export_schema(&schema_for!(AddressStatusResponse), &out_dir);
+    export_schema(&schema_for!(AllowanceLimitResponse), &out_dir);
+    export_schema(&schema_for!(AntiWhaleTax), &out_dir);
+    export_schema(&schema_for!(AuditLogResponse), &out_dir);
+    export_schema(&schema_for!(ContractVersionResponse), &out_dir);
+    export_schema(&schema_for!(DynamicTaxResponse), &out_dir);
+    export_schema(&schema_for!(ExportStateResponse), &out_dir);
+    export_schema(&schema_for!(LaunchProtection), &out_dir);
+    export_schema(&schema_for!(LiquifyConfigResponse), &out_dir);
+    export_schema(&schema_for!(LocksResponse), &out_dir);
+    export_schema(&schema_for!(MerkleStage), &out_dir);
+    export_schema(&schema_for!(PairConfig), &out_dir);
+    export_schema(&schema_for!(PendingRates), &out_dir);
+    export_schema(&schema_for!(PendingReflectionResponse), &out_dir);
+    export_schema(&schema_for!(PriceImpactTax), &out_dir);
+    export_schema(&schema_for!(QueryTaxResponse), &out_dir);
+    export_schema(&schema_for!(RateHistoryResponse), &out_dir);
+    export_schema(&schema_for!(RatesResponse), &out_dir);
+    export_schema(&schema_for!(SellAllowanceResponse), &out_dir);
+    export_schema(&schema_for!(SellLimit), &out_dir);
+    export_schema(&schema_for!(SupplyCheckResponse), &out_dir);
+    export_schema(&schema_for!(TaxDecayResponse), &out_dir);
+    export_schema(&schema_for!(TaxMode), &out_dir);
+    export_schema(&schema_for!(TaxRatesResponse), &out_dir);
+    export_schema(&schema_for!(TaxSplitResponse), &out_dir);
+    export_schema(&schema_for!(TaxStats), &out_dir);
+    export_schema(&schema_for!(TaxStrategyResponse), &out_dir);
+    export_schema(&schema_for!(TaxTiersResponse), &out_dir);
+    export_schema(&schema_for!(TreasuryResponse), &out_dir);
+    export_schema(&schema_for!(VestingResponse), &out_dir);
+    export_schema(&schema_for!(VotingPowerResponse), &out_dir);
 }
@@ -1,49 +1,282 @@
-use std::ops::{Mul, Sub};
+use std::convert::TryFrom;
+use std::ops::Mul;
 
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, to_json_binary, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, 
-    Response, StdError, StdResult, Storage,   Uint128, WasmMsg,
+    attr, coins, instantiate2_address, to_json_binary, to_json_vec, Addr, Api, Attribute, BankMsg,
+    Binary, CosmosMsg, Decimal, Deps, DepsMut, Env, Event, MessageInfo, Order, Reply, Response,
+    StdError, StdResult, Storage, SubMsg, SubMsgResult, Uint128, Uint256, WasmMsg,
 };
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
-use cw2::set_contract_version;
-use cw20::{Cw20ReceiveMsg, Logo, LogoInfo, MarketingInfoResponse};
+use cw0::{must_pay, parse_reply_instantiate_data};
+use cw2::{get_contract_version, set_contract_version};
+use cw20::{
+    AllowanceResponse, BalanceResponse, Cw20Coin, Cw20ReceiveMsg, Expiration, Logo, LogoInfo,
+    MarketingInfoResponse,
+};
 use cw20_base::allowances::{
     deduct_allowance, execute_burn_from, execute_decrease_allowance, execute_increase_allowance,
     query_allowance,
 };
 use cw20_base::contract::{
     create_accounts, execute_burn,  execute_update_marketing, execute_upload_logo,
-    query_balance, query_download_logo, query_marketing_info, query_minter, query_token_info,
+    query_download_logo, query_marketing_info, query_minter, query_token_info,
 };
 use cw20_base::enumerable::{query_all_accounts, query_all_allowances};
 
 use crate::msg::{
-    ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, QueryTaxResponse, TreasuryExecuteMsg,
+    AccountTaxPaid, AddressListResponse, AddressStatusResponse, AllowanceLimit,
+    AllowanceLimitResponse, AntiWhaleTax, AuditEntry, AuditLogEntry, AuditLogResponse,
+    BatchTransferItem, ContractVersionResponse, DynamicTax, DynamicTaxResponse, ExecuteMsg,
+    ExportStateResponse, ExportedAllowance, ExportedConfig, InstantiateMsg, LaunchProtection,
+    LiquifyConfigResponse, LocksResponse, MerkleStage, MigrateMsg, PairConfig, PendingRates,
+    PendingReflectionResponse, PendingTaxChange, PendingTaxChangesResponse, PermitPayload,
+    PriceImpactTax, QueryMsg, QueryTaxResponse, RateChange, RateHistoryEntry, RateHistoryResponse,
+    RatesResponse, SellAllowanceResponse, SellLimit, SellVolume, SniperPenalty,
+    SupplyCheckResponse, SupplyDiscrepancy, TaxBucket, TaxChange, TaxDecay, TaxDecayResponse,
+    TaxMode, TaxRatesResponse, TaxSplitResponse, TaxStats, TaxStrategyQueryMsg,
+    TaxStrategyResponse, TaxTier, TaxTiersResponse, TokenLock, TreasuryExecuteMsg,
+    TreasuryInstantiateMsg, TreasuryMigrateMsg, TreasuryResponse, VestingResponse, VestingSchedule,
+    VotingPowerResponse,
+};
+use crate::overlay::StorageOverlay;
+use cw20_base::state::{
+    MinterData, TokenInfo, ALLOWANCES, BALANCES, LOGO, MARKETING_INFO, TOKEN_INFO,
 };
-use cw20_base::state::{MinterData, TokenInfo, BALANCES, LOGO, MARKETING_INFO, TOKEN_INFO};
 use cw20_base::ContractError;
-use cw_storage_plus::{Item, Map};
+use cw_storage_plus::{Bound, Item, Map, SnapshotItem, SnapshotMap, Strategy};
+use dojoswap::asset::AssetInfo;
+use dojoswap::pair::{PoolResponse, QueryMsg as PairQueryMsg};
+use reflection_tax::{rates, TaxEngine, TaxShares, TransferKind};
+use semver::Version;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "qtum:reflection";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+// name cw20-base stores in cw2, the only contract `MigrateMsg::FromCw20Base` converts
+const CW20_BASE_CONTRACT_NAME: &str = "crates.io:cw20-base";
 
-pub const TAX_RATE: Item<Decimal> = Item::new("tax_rate");
+// reply ids for submessages dispatched by this contract
+pub const INSTANTIATE_TREASURY_REPLY_ID: u64 = 1;
+pub const LIQUIFY_REPLY_ID: u64 = 2;
+
+pub const BUY_TAX_RATE: Item<Decimal> = Item::new("buy_tax_rate");
+pub const SELL_TAX_RATE: Item<Decimal> = Item::new("sell_tax_rate");
+pub const TRANSFER_TAX_RATE: Item<Decimal> = Item::new("transfer_tax_rate");
 pub const REFLECTION_RATE: Item<Decimal> = Item::new("reflection_rate");
 pub const BURN_RATE: Item<Decimal> = Item::new("burn_rate");
+// ceiling of the total tax rate of any transfer, only set at instantiate
+pub const MAX_TOTAL_TAX: Item<Decimal> = Item::new("max_total_tax");
+// declining launch tax, used instead of lower base rates until it has decayed
+pub const TAX_DECAY: Item<TaxDecay> = Item::new("tax_decay");
+// sell tax that moves between bounds with the recent sell volume
+pub const DYNAMIC_TAX: Item<DynamicTax> = Item::new("dynamic_tax");
+pub const SELL_VOLUME: Item<SellVolume> = Item::new("sell_volume");
+// rate changes only take effect after this delay, so holders can react to them
+pub const TAX_RATE_DELAY: Item<u64> = Item::new("tax_rate_delay");
+pub const PENDING_RATES: Item<PendingRates> = Item::new("pending_rates");
+// changes of the other tax settings waiting for the same delay, keyed by `tax_change_key`
+pub const PENDING_TAX_CHANGES: Map<String, PendingTaxChange> = Map::new("pending_tax_changes");
+// the last `RATE_HISTORY_SIZE` rate changes, keyed by an increasing id
+pub const RATE_HISTORY: Map<u64, RateChange> = Map::new("rate_history");
+pub const RATE_HISTORY_NEXT_ID: Item<u64> = Item::new("rate_history_next_id");
+pub const RATE_HISTORY_SIZE: u64 = 100;
+// append-only log of the privileged actions of the owner, keyed by an increasing id
+pub const AUDIT_LOG: Map<u64, AuditEntry> = Map::new("audit_log");
+pub const AUDIT_LOG_NEXT_ID: Item<u64> = Item::new("audit_log_next_id");
+// external contract that computes the base tax rate of transfers, see `TaxStrategyQueryMsg`
+pub const TAX_STRATEGY: Item<Addr> = Item::new("tax_strategy");
+// tax rates by transfer size, sorted by `min_supply_rate`. Replaces the base rate of taxed transfers when set
+pub const TAX_TIERS: Item<Vec<TaxTier>> = Item::new("tax_tiers");
+// extra sell tax that grows with the price impact of the sell on the pair
+pub const PRICE_IMPACT_TAX: Item<PriceImpactTax> = Item::new("price_impact_tax");
+// extra sell tax that grows with the fraction of the supply sold at once
+pub const ANTI_WHALE_TAX: Item<AntiWhaleTax> = Item::new("anti_whale_tax");
+// largest fraction of the total supply that can be moved in a single transfer
+pub const MAX_TRANSFER_SUPPLY_RATE: Item<Decimal> = Item::new("max_transfer_supply_rate");
+// largest fraction of the total supply a wallet can hold, disabled when unset
+pub const MAX_WALLET_RATE: Item<Decimal> = Item::new("max_wallet_rate");
+// addresses that can hold more than the max wallet, e.g. pairs, treasury, lockers
+pub const MAX_WALLET_EXEMPT: Map<&Addr, bool> = Map::new("max_wallet_exempt");
 
+// legacy admin, moved to cw-ownable by `migrate`
 pub const ADMIN: Item<String> = Item::new("admin");
+// legacy 1.0.0 state, converted by `migrate`: a single rate charged on every pair transfer, and
+// the pairlist before per-pair configs
+pub const LEGACY_TAX_RATE: Item<Decimal> = Item::new("tax_rate");
+pub const LEGACY_PAIRLIST: Map<String, bool> = Map::new("pairlist");
 pub const LAST_LIQUIFY: Item<u64> = Item::new("last_liquify");
+// seconds that must pass after a liquify before transfers trigger the next one, 1 if missing
+pub const LIQUIFY_COOLDOWN: Item<u64> = Item::new("liquify_cooldown");
+// taxes sent to the treasury since the last liquify, and how much must accumulate to trigger the next one
+pub const ACCUMULATED_TAX: Item<Uint128> = Item::new("accumulated_tax");
+pub const LIQUIFY_THRESHOLD: Item<Uint128> = Item::new("liquify_threshold");
 pub const TREASURY: Item<String> = Item::new("treasury");
-pub const PAIRLIST: Map<String, bool> = Map::new("pairlist");
+pub const PAIRLIST: Map<String, PairConfig> = Map::new("pairlist");
+// addresses that never pay taxes, even when transacting with pairs (routers, lockers, partner contracts)
+pub const TAX_EXEMPT: Map<&Addr, bool> = Map::new("tax_exempt");
+// cw20-ics20 contracts and channel escrow addresses the token is bridged through
+pub const BRIDGES: Map<&Addr, bool> = Map::new("bridges");
+// when set, transfers from and to recognized bridges are untaxed, enabled by default
+pub const BRIDGE_EXEMPTION_ENABLED: Item<bool> = Item::new("bridge_exemption_enabled");
 pub const BUYBACK_ENABLE: Item<bool> = Item::new("buyback_enable");
+// bucket receiving the rounding dust of the tax split, liquidity if missing
+pub const DUST_BUCKET: Item<TaxBucket> = Item::new("dust_bucket");
+// balance and total supply checkpoints for historical queries, maintained next to the cw20-base state
+pub const BALANCE_HISTORY: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "balance_history",
+    "balance_history__checkpoints",
+    "balance_history__changelog",
+    Strategy::EveryBlock,
+);
+pub const TOTAL_SUPPLY_HISTORY: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_supply_history",
+    "total_supply_history__checkpoints",
+    "total_supply_history__changelog",
+    Strategy::EveryBlock,
+);
+// taxes collected since the token was instantiated, or migrated to a version tracking them
+pub const TAX_STATS: Item<TaxStats> = Item::new("tax_stats");
+// taxes paid by each account over its lifetime
+pub const ACCOUNT_TAX_PAID: Map<&Addr, AccountTaxPaid> = Map::new("account_tax_paid");
+// number of accounts with a nonzero balance but the contract, kept up to date with the balance history
+pub const HOLDER_COUNT: Item<u64> = Item::new("holder_count");
+// delegate of each account that does not vote with its own balance
+pub const DELEGATES: Map<&Addr, Addr> = Map::new("delegates");
+// checkpointed balances delegated to each address, including its own
+pub const VOTING_POWER: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "voting_power",
+    "voting_power__checkpoints",
+    "voting_power__changelog",
+    Strategy::EveryBlock,
+);
+// set at instantiate. Reflections are compounded into balances instead of being claimed
+pub const REBASE: Item<bool> = Item::new("rebase");
+// whether taxes are taken out of the transferred amount or charged on top, inclusive if missing
+pub const TAX_MODE: Item<TaxMode> = Item::new("tax_mode");
+// share of newly minted tokens routed to the treasury
+pub const MINT_TAX_RATE: Item<Decimal> = Item::new("mint_tax_rate");
+// when set, transfers report the full amount in `amount` and the taxes in separate attributes
+pub const STANDARD_ATTRIBUTES: Item<bool> = Item::new("standard_attributes");
+// when set, the burn share of the taxes is burned in the transfer instead of by the treasury
+pub const INSTANT_BURN: Item<bool> = Item::new("instant_burn");
+// before trading is enabled only the admin and tax exempt addresses can transfer
+pub const TRADING_ENABLED: Item<bool> = Item::new("trading_enabled");
+// block height at which trading was enabled
+pub const TRADING_ENABLED_AT: Item<u64> = Item::new("trading_enabled_at");
+// buyers within the first blocks of trading are flagged as snipers and penalized until cleared
+pub const LAUNCH_PROTECTION: Item<LaunchProtection> = Item::new("launch_protection");
+pub const SNIPERS: Map<&Addr, bool> = Map::new("snipers");
+// minimum seconds between two transfers from the same address, disabled when zero
+pub const TRANSFER_COOLDOWN: Item<u64> = Item::new("transfer_cooldown");
+pub const LAST_TRANSFER: Map<&Addr, u64> = Map::new("last_transfer");
+pub const COOLDOWN_EXEMPT: Map<&Addr, bool> = Map::new("cooldown_exempt");
+// when set, `Send` and `SendFrom` only accept pairs and the contracts in `SEND_ALLOWED`
+pub const SEND_ALLOWLIST_ENABLED: Item<bool> = Item::new("send_allowlist_enabled");
+pub const SEND_ALLOWED: Map<&Addr, bool> = Map::new("send_allowed");
+// amount each address can sell into pairs per 24 hours, disabled when unset
+pub const SELL_LIMIT: Item<SellLimit> = Item::new("sell_limit");
+pub const SELL_WINDOWS: Map<&Addr, SellWindow> = Map::new("sell_windows");
+
+// rejects selling into a pair in the same block as buying from one, against sandwich bots
+pub const SANDWICH_GUARD: Item<bool> = Item::new("sandwich_guard");
+pub const LAST_BUY_HEIGHT: Map<&Addr, u64> = Map::new("last_buy_height");
+pub const SANDWICH_EXEMPT: Map<&Addr, bool> = Map::new("sandwich_exempt");
+
+// whether the admin can force-move balances, only set at instantiate
+pub const CLAWBACK_ENABLED: Item<bool> = Item::new("clawback_enabled");
+// halts all transfers, sends, mints and burns during an incident
+pub const PAUSED: Item<bool> = Item::new("paused");
+// accounts that can receive but not send tokens until unfrozen
+pub const FROZEN: Map<&Addr, bool> = Map::new("frozen");
+
+// length of the sell limit window
+const SELL_WINDOW_SECONDS: u64 = 24 * 60 * 60;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct SellWindow {
+    /// Time of the first sell in the window
+    pub start: u64,
+    /// Balance of the seller at the start of the window
+    pub balance: Uint128,
+    pub sold: Uint128,
+}
+
+// per-period caps set by owners on what a spender can move with its allowance, and the current period
+pub const ALLOWANCE_LIMITS: Map<(&Addr, &Addr), AllowanceLimit> = Map::new("allowance_limits");
+pub const ALLOWANCE_WINDOWS: Map<(&Addr, &Addr), AllowanceWindow> = Map::new("allowance_windows");
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct AllowanceWindow {
+    /// Time of the first spend in the period
+    pub start: u64,
+    pub spent: Uint128,
+}
+
+// fraction of each tax that is distributed to holders instead of being sent to the treasury
+pub const HOLDER_REFLECTION_RATE: Item<Decimal> = Item::new("holder_reflection_rate");
+// checkpointed with the holders and the pool, so rebased balances can be queried at a height
+pub const REFLECTION_PER_SHARE: SnapshotItem<Uint256> = SnapshotItem::new(
+    "reflection_per_share",
+    "reflection_per_share__checkpoints",
+    "reflection_per_share__changelog",
+    Strategy::EveryBlock,
+);
+pub const REFLECTION_TOTAL_SHARES: Item<Uint128> = Item::new("reflection_total_shares");
+pub const HOLDER_REFLECTIONS: SnapshotMap<&Addr, HolderReflection> = SnapshotMap::new(
+    "holder_reflections",
+    "holder_reflections__checkpoints",
+    "holder_reflections__changelog",
+    Strategy::EveryBlock,
+);
+// reflections held in the contract balance until they are claimed or compounded. Missing for the
+// reflections distributed before it was tracked
+pub const REFLECTION_POOL: SnapshotItem<Uint128> = SnapshotItem::new(
+    "reflection_pool",
+    "reflection_pool__checkpoints",
+    "reflection_pool__changelog",
+    Strategy::EveryBlock,
+);
+// reflections paid in a native denom, deposited by the treasury
+pub const NATIVE_REFLECTION_DENOM: Item<String> = Item::new("native_reflection_denom");
+pub const NATIVE_REFLECTION_PER_SHARE: Item<Uint256> = Item::new("native_reflection_per_share");
+// addresses that do not accrue reflections, e.g. pairs, treasury, burn address, CEX wallets
+pub const REFLECTION_EXCLUDED: Map<&Addr, bool> = Map::new("reflection_excluded");
+
+// merkle airdrop stages, funded from the admin balance and held by the contract until claimed
+pub const LATEST_MERKLE_STAGE: Item<u8> = Item::new("latest_merkle_stage");
+pub const MERKLE_STAGES: Map<u8, MerkleStage> = Map::new("merkle_stages");
+// claimed leaf indexes per stage, 64 per word
+pub const MERKLE_CLAIMED: Map<(u8, u32), u64> = Map::new("merkle_claimed");
+
+// vesting initial balances, held by the contract until released with `ClaimVested`
+pub const VESTING: Map<&Addr, VestingSchedule> = Map::new("vesting");
+
+// balances locked until a timestamp, e.g. team tokens. Locked tokens cannot leave the beneficiary balance
+pub const LOCKS: Map<&Addr, Vec<TokenLock>> = Map::new("locks");
+
+// secp256k1 public keys registered by owners to sign permits, and the next permit nonce of each owner
+pub const PERMIT_KEYS: Map<&Addr, Binary> = Map::new("permit_keys");
+pub const PERMIT_NONCES: Map<&Addr, u64> = Map::new("permit_nonces");
+
+// set at instantiate without initial balances, `ImportState` is allowed until it is removed
+pub const IMPORT_OPEN: Item<bool> = Item::new("import_open");
+
+// largest number of transfers in a `BatchTransfer`
+const MAX_BATCH_TRANSFERS: usize = 100;
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     mut deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
@@ -51,15 +284,87 @@ pub fn instantiate(
     // check valid token info
     msg.validate()?;
 
-    ADMIN.save(deps.storage, &info.sender.to_string())?;
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(info.sender.as_str()))?;
 
-    TAX_RATE.save(deps.storage, &Decimal::zero())?;
+    BUY_TAX_RATE.save(deps.storage, &Decimal::zero())?;
+    SELL_TAX_RATE.save(deps.storage, &Decimal::zero())?;
+    TRANSFER_TAX_RATE.save(deps.storage, &Decimal::zero())?;
     REFLECTION_RATE.save(deps.storage, &Decimal::zero())?;
     BURN_RATE.save(deps.storage, &Decimal::zero())?;    
     BUYBACK_ENABLE.save(deps.storage, &false)?;
+    TRADING_ENABLED.save(deps.storage, &false)?;
+    CLAWBACK_ENABLED.save(deps.storage, &msg.enable_clawback.unwrap_or_default())?;
+    if let Some(protection) = &msg.launch_protection {
+        protection.validate()?;
+        LAUNCH_PROTECTION.save(deps.storage, protection)?;
+    }
+    HOLDER_REFLECTION_RATE.save(deps.storage, &Decimal::zero())?;
+    MAX_TRANSFER_SUPPLY_RATE.save(deps.storage, &Decimal::one())?;
+    let max_total_tax = msg.max_total_tax.unwrap_or_else(Decimal::one);
+    if max_total_tax > Decimal::one() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "max_total_tax must be <= 1",
+        )));
+    }
+    MAX_TOTAL_TAX.save(deps.storage, &max_total_tax)?;
+    if let Some(decay) = &msg.tax_decay {
+        validate_tax_decay(deps.storage, decay)?;
+        TAX_DECAY.save(deps.storage, decay)?;
+    }
+    REBASE.save(deps.storage, &msg.rebase.unwrap_or_default())?;
 
     // create initial accounts
-    let total_supply = create_accounts(&mut deps, &msg.initial_balances)?;
+    let (vesting, liquid): (Vec<_>, Vec<_>) = msg
+        .initial_balances
+        .iter()
+        .partition(|row| row.vesting.is_some());
+    let liquid: Vec<Cw20Coin> = liquid
+        .into_iter()
+        .map(|row| Cw20Coin {
+            address: row.address.clone(),
+            amount: row.amount,
+        })
+        .collect();
+    let mut total_supply = create_accounts(&mut deps, &liquid)?;
+    for row in liquid.iter() {
+        let address = deps.api.addr_validate(&row.address)?;
+        sync_reflection(deps.storage, &env, &address)?;
+    }
+
+    // vesting balances are held by the contract until released
+    for row in vesting {
+        let address = deps.api.addr_validate(&row.address)?;
+        let terms = row.vesting.as_ref().unwrap();
+        if terms.duration == 0 || terms.cliff > terms.duration {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Vesting duration must be > 0 and >= cliff",
+            )));
+        }
+        if VESTING.has(deps.storage, &address) {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Duplicate vesting address",
+            )));
+        }
+        VESTING.save(
+            deps.storage,
+            &address,
+            &VestingSchedule {
+                total: row.amount,
+                released: Uint128::zero(),
+                start: env.block.time.seconds(),
+                cliff: terms.cliff,
+                duration: terms.duration,
+            },
+        )?;
+        BALANCES.update(
+            deps.storage,
+            &env.contract.address,
+            |balance: Option<Uint128>| -> StdResult<_> {
+                Ok(balance.unwrap_or_default() + row.amount)
+            },
+        )?;
+        total_supply += row.amount;
+    }
 
     if let Some(limit) = msg.get_cap() {
         if total_supply > limit {
@@ -68,6 +373,9 @@ pub fn instantiate(
             )));
         }
     }
+    if total_supply.is_zero() {
+        IMPORT_OPEN.save(deps.storage, &true)?;
+    }
 
     let mint = match msg.mint {
         Some(m) => Some(MinterData {
@@ -108,14 +416,83 @@ pub fn instantiate(
     }
 
     TOKEN_INFO.save(deps.storage, &data)?;
+    checkpoint_total_supply(deps.storage, &env)?;
 
-    Ok(Response::default())
+    // Optionally instantiates the treasury, the address is stored in `reply` once it is known
+    let mut res = Response::default();
+    if let Some(treasury) = msg.treasury {
+        deps.api.addr_validate(&treasury.router)?;
+        let treasury_admin = treasury.admin.unwrap_or(msg.admin);
+        deps.api.addr_validate(&treasury_admin)?;
+
+        // the token is the wasm admin of the treasury so it can run `MigrateTreasury`
+        let admin = Some(env.contract.address.to_string());
+        let instantiate_msg = to_json_binary(&TreasuryInstantiateMsg {
+            admin: treasury_admin,
+            router: treasury.router,
+            token: env.contract.address.clone(),
+            lp_withdrawal_delay: treasury.lp_withdrawal_delay,
+            emergency_withdrawal_delay: treasury.emergency_withdrawal_delay,
+        })?;
+        let instantiate = if treasury.deterministic.unwrap_or_default() {
+            WasmMsg::Instantiate2 {
+                admin,
+                code_id: treasury.code_id,
+                label: treasury.label,
+                msg: instantiate_msg,
+                funds: vec![],
+                salt: treasury_salt(deps.api, &env.contract.address)?,
+            }
+        } else {
+            WasmMsg::Instantiate {
+                admin,
+                code_id: treasury.code_id,
+                msg: instantiate_msg,
+                funds: vec![],
+                label: treasury.label,
+            }
+        };
+        res = res.add_submessage(SubMsg::reply_on_success(
+            instantiate,
+            INSTANTIATE_TREASURY_REPLY_ID,
+        ));
+    }
+
+    Ok(res)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        INSTANTIATE_TREASURY_REPLY_ID => {
+            let res = parse_reply_instantiate_data(msg)
+                .map_err(|err| StdError::generic_err(err.to_string()))?;
+            let treasury = deps.api.addr_validate(&res.contract_address)?;
+            TREASURY.save(deps.storage, &treasury.to_string())?;
+
+            Ok(Response::new()
+                .add_attribute("action", "set_treasury")
+                .add_attribute("treasury", treasury))
+        }
+        // a failed liquify is reverted on its own and must not fail the transfer that triggered it
+        LIQUIFY_REPLY_ID => {
+            match msg.result {
+                SubMsgResult::Err(err) => Ok(Response::new()
+                    .add_event(Event::new("liquify_failed").add_attribute("error", err))),
+                SubMsgResult::Ok(_) => Ok(Response::default()),
+            }
+        }
+        id => Err(ContractError::Std(StdError::generic_err(format!(
+            "Unknown reply id: {}",
+            id
+        )))),
+    }
 }
 
 /// Standard CW20 transfer function that is modified to include tax functions
 /// These modifications are all applied to the `transfer`, `send`, `transfer_from`, and `send_from` functions
 pub fn execute_transfer(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     recipient: String,
@@ -124,531 +501,4126 @@ pub fn execute_transfer(
     if amount == Uint128::zero() {
         return Err(ContractError::InvalidZeroAmount {});
     }
-    // If whitelisetd, we simply do not apply taxes
-    let to_pair = PAIRLIST
-        .may_load(deps.storage, recipient.clone())?
-        .unwrap_or_default();
-    let from_pair = PAIRLIST
-        .may_load(deps.storage, info.sender.to_string())?
-        .unwrap_or_default();
-    let is_pair = to_pair || from_pair;
 
-    // Loads treasury addresses, and query for taxes on transfers
-    let treasury = TREASURY.may_load(deps.storage)?.unwrap_or_default();
     let rcpt_addr = deps.api.addr_validate(&recipient)?;
-    let taxes = query_tax(deps.storage, amount)?;
-    let outgoing_amount = if is_pair { taxes.after_tax } else { amount };
+    let transfer = transfer_with_tax(&mut deps, &env, &info.sender, &rcpt_addr, amount)?;
 
-    BALANCES.update(
-        deps.storage,
-        &info.sender,
-        |balance: Option<Uint128>| -> StdResult<_> {
-            Ok(balance.unwrap_or_default().checked_sub(amount)?)
-        },
-    )?;
-    BALANCES.update(
-        deps.storage,
-        &rcpt_addr,
-        |balance: Option<Uint128>| -> StdResult<_> {
-            Ok(balance.unwrap_or_default() + outgoing_amount)
-        },
-    )?;
+    let res = Response::new()
+        .add_submessages(transfer.messages)
+        .add_events(transfer.events)
+        .add_attribute("action", "transfer")
+        .add_attribute("from", info.sender)
+        .add_attribute("to", recipient)
+        .add_attributes(amount_attributes(deps.storage, &transfer.taxes)?);
+    Ok(res)
+}
 
-    let mut messages = vec![];
+/// Transfers to several recipients at once, e.g. for airdrops and payroll. The sender is checked and debited
+/// once, and each leg is taxed like a single transfer
+pub fn execute_batch_transfer(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    transfers: Vec<BatchTransferItem>,
+) -> Result<Response, ContractError> {
+    if transfers.is_empty() || transfers.len() > MAX_BATCH_TRANSFERS {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Batch must contain between 1 and {} transfers",
+            MAX_BATCH_TRANSFERS
+        ))));
+    }
+    if transfers.iter().any(|transfer| transfer.amount.is_zero()) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
 
-    // we apply taxes, and immediately add them to the treasury by modifying balance variables
-    // We also send generate a transfer teransaction log under `TransferEvent` to ensure explorer tracks transfer properly
-    if is_pair {
-        BALANCES.update(
-            deps.storage,
-            &deps.api.addr_validate(&treasury)?,
-            |balance: Option<Uint128>| -> StdResult<_> {
-                Ok(balance.unwrap_or_default() + taxes.taxed_amount)
-            },
-        )?;
+    let total = transfers
+        .iter()
+        .try_fold(Uint128::zero(), |total, transfer| {
+            total.checked_add(transfer.amount)
+        })
+        .map_err(StdError::from)?;
 
-        let buyback_enabled = BUYBACK_ENABLE.may_load(deps.storage)?.unwrap_or_default();
-        if buyback_enabled {
-            messages.push(WasmMsg::Execute {
-                contract_addr: env.contract.address.to_string(),
-                msg: to_json_binary(&ExecuteMsg::TransferEvent {
-                    from: info.sender.to_string(),
-                    to: treasury.to_string(),
-                    amount: taxes.taxed_amount,
-                })?,
-                funds: vec![],
-            })
-        }
+    let sniper_penalty = check_sender(deps.storage, &env, &info.sender)?;
+    compound_reflection(deps.storage, &env, &info.sender)?;
+    debit(deps.storage, &info.sender, total)?;
+
+    let mut messages = vec![];
+    let mut events = vec![];
+    let mut received = Uint128::zero();
+    let mut taxes = QueryTaxResponse::default();
+    for transfer in transfers.iter() {
+        let rcpt_addr = deps.api.addr_validate(&transfer.recipient)?;
+        let leg = credit_with_tax(
+            &mut deps,
+            &env,
+            &info.sender,
+            &rcpt_addr,
+            transfer.amount,
+            &sniper_penalty,
+        )?;
+        received += leg.received;
+        taxes.taxed_amount += leg.taxes.taxed_amount;
+        taxes.burn_amount += leg.taxes.burn_amount;
+        taxes.reflection_amount += leg.taxes.reflection_amount;
+        messages.extend(leg.messages);
+        events.extend(leg.events);
     }
+    check_locked_balance(deps.storage, &env, &info.sender)?;
+    sync_reflection(deps.storage, &env, &info.sender)?;
 
-    let res = Response::new()
-        .add_messages(messages)
-        .add_attribute("action", "transfer")
+    let mut res = Response::new()
+        .add_submessages(messages)
+        .add_events(events)
+        .add_attribute("action", "batch_transfer")
         .add_attribute("from", info.sender)
-        .add_attribute("to", recipient)
-        .add_attribute("amount", outgoing_amount);
+        .add_attribute("transfers", transfers.len().to_string())
+        .add_attribute("amount", total)
+        .add_attribute("received", received);
+    if STANDARD_ATTRIBUTES
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+    {
+        res = res.add_attributes(tax_attributes(&taxes));
+    }
     Ok(res)
 }
 
-pub fn execute_send(
+/// Returns the `amount` attribute of a transfer, which is the amount received unless standard attributes
+/// are enabled. In that case it is the full amount debited, followed by the tax breakdown
+pub fn amount_attributes(
+    storage: &dyn Storage,
+    taxes: &QueryTaxResponse,
+) -> StdResult<Vec<Attribute>> {
+    if !STANDARD_ATTRIBUTES.may_load(storage)?.unwrap_or_default() {
+        return Ok(vec![attr("amount", taxes.after_tax)]);
+    }
+
+    let mut attrs = vec![attr("amount", taxes.after_tax + taxes.taxed_amount)];
+    attrs.extend(tax_attributes(taxes));
+    Ok(attrs)
+}
+
+/// Tax breakdown attributes of standard attributes mode
+fn tax_attributes(taxes: &QueryTaxResponse) -> Vec<Attribute> {
+    vec![
+        attr("tax_amount", taxes.taxed_amount),
+        attr("burn_amount", taxes.burn_amount),
+        attr("reflection_amount", taxes.reflection_amount),
+    ]
+}
+
+/// Distributes tokens from the admin balance without taxes or transfer restrictions, e.g. to migrate
+/// holders from a previous token
+pub fn execute_airdrop(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    contract: String,
-    amount: Uint128,
-    msg: Binary,
+    recipients: Vec<(String, Uint128)>,
 ) -> Result<Response, ContractError> {
-    if amount == Uint128::zero() {
+    ensure_admin(&deps, &info)?;
+    if recipients.is_empty() || recipients.len() > MAX_BATCH_TRANSFERS {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Airdrop must contain between 1 and {} recipients",
+            MAX_BATCH_TRANSFERS
+        ))));
+    }
+    if recipients.iter().any(|(_, amount)| amount.is_zero()) {
         return Err(ContractError::InvalidZeroAmount {});
     }
-    let to_pair = PAIRLIST
-        .may_load(deps.storage, contract.clone())?
-        .unwrap_or_default();
-    let from_pair = PAIRLIST
-        .may_load(deps.storage, info.sender.to_string())?
-        .unwrap_or_default();
-    let is_pair = to_pair || from_pair;
-    let treasury = TREASURY.may_load(deps.storage)?.unwrap_or_default();
-    let rcpt_addr = deps.api.addr_validate(&contract)?;
-    let taxes = query_tax(deps.storage, amount)?;
-    let outgoing_amount = if is_pair { taxes.after_tax } else { amount };
-
-    // move the tokens to the contract
-    BALANCES.update(
-        deps.storage,
-        &info.sender,
-        |balance: Option<Uint128>| -> StdResult<_> {
-            Ok(balance.unwrap_or_default().checked_sub(amount)?)
-        },
-    )?;
-    BALANCES.update(
-        deps.storage,
-        &rcpt_addr,
-        |balance: Option<Uint128>| -> StdResult<_> {
-            Ok(balance.unwrap_or_default() + outgoing_amount)
-        },
-    )?;
 
-    let mut messages = vec![];
+    let total = recipients
+        .iter()
+        .try_fold(Uint128::zero(), |total, (_, amount)| {
+            total.checked_add(*amount)
+        })
+        .map_err(StdError::from)?;
+    compound_reflection(deps.storage, &env, &info.sender)?;
+    debit(deps.storage, &info.sender, total)?;
+    check_locked_balance(deps.storage, &env, &info.sender)?;
 
-    if is_pair {
+    for (recipient, amount) in recipients.iter() {
+        let rcpt_addr = deps.api.addr_validate(recipient)?;
         BALANCES.update(
             deps.storage,
-            &deps.api.addr_validate(&treasury)?,
-            |balance: Option<Uint128>| -> StdResult<_> {
-                Ok(balance.unwrap_or_default() + taxes.taxed_amount)
-            },
+            &rcpt_addr,
+            |balance: Option<Uint128>| -> StdResult<_> { Ok(balance.unwrap_or_default() + amount) },
         )?;
+        sync_reflection(deps.storage, &env, &rcpt_addr)?;
+    }
+    sync_reflection(deps.storage, &env, &info.sender)?;
 
-        let buyback_enabled = BUYBACK_ENABLE.may_load(deps.storage)?.unwrap_or_default();
-        if buyback_enabled {
-            messages.push(WasmMsg::Execute {
-                contract_addr: env.contract.address.to_string(),
-                msg: to_json_binary(&ExecuteMsg::TransferEvent {
-                    from: info.sender.to_string(),
-                    to: treasury.to_string(),
-                    amount: taxes.taxed_amount,
-                })?,
-                funds: vec![],
-            })
-        }        
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "airdrop"),
+        attr("from", info.sender),
+        attr("recipients", recipients.len().to_string()),
+        attr("amount", total),
+    ]))
+}
+
+/// Returns the amount of `address` that is still locked
+pub fn locked_amount(storage: &dyn Storage, env: &Env, address: &Addr) -> StdResult<Uint128> {
+    let now = env.block.time.seconds();
+    Ok(LOCKS
+        .may_load(storage, address)?
+        .unwrap_or_default()
+        .iter()
+        .filter(|lock| lock.unlock_time > now)
+        .map(|lock| lock.amount)
+        .sum())
+}
+
+/// Rejects balances of `address` below its locked amount, to be called after debiting it
+pub fn check_locked_balance(storage: &dyn Storage, env: &Env, address: &Addr) -> StdResult<()> {
+    let locked = locked_amount(storage, env, address)?;
+    if locked.is_zero() {
+        return Ok(());
     }
 
-    let res = Response::new()
-        .add_messages(messages)
-        .add_attribute("action", "send")
-        .add_attribute("from", &info.sender)
-        .add_attribute("to", &contract)
-        .add_attribute("amount", outgoing_amount)
-        .add_message(
-            // We do not modify the send message, but we allow the hooked contract to calculate taxes against this contract
-            Cw20ReceiveMsg {
-                sender: info.sender.into(),
-                amount: outgoing_amount,
-                msg,
-            }
-            .into_cosmos_msg(contract)?,
-        );
-    Ok(res)
+    let balance = BALANCES.may_load(storage, address)?.unwrap_or_default();
+    if balance < locked {
+        return Err(StdError::generic_err(format!(
+            "{} tokens are locked",
+            locked
+        )));
+    }
+
+    Ok(())
 }
 
-pub fn execute_transfer_from(
+/// Moves `amount` from the admin balance to `beneficiary` and locks it there until `unlock_time`
+pub fn lock_tokens(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    owner: String,
-    recipient: String,
+    beneficiary: String,
     amount: Uint128,
+    unlock_time: u64,
 ) -> Result<Response, ContractError> {
-    
-    let to_pair = PAIRLIST
-        .may_load(deps.storage, recipient.clone())?
-        .unwrap_or_default();
-    let from_pair = PAIRLIST
-        .may_load(deps.storage, info.sender.to_string())?
+    ensure_admin(&deps, &info)?;
+    if amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    let now = env.block.time.seconds();
+    if unlock_time <= now {
+        return Err(ContractError::Std(StdError::generic_err(
+            "unlock_time must be in the future",
+        )));
+    }
+
+    let beneficiary = deps.api.addr_validate(&beneficiary)?;
+    move_balance(deps.storage, &env, &info.sender, &beneficiary, amount)?;
+    check_locked_balance(deps.storage, &env, &info.sender)?;
+
+    // expired locks are dropped whenever a new lock is added
+    let mut locks = LOCKS
+        .may_load(deps.storage, &beneficiary)?
         .unwrap_or_default();
-    let is_pair = to_pair || from_pair;
-    let treasury = TREASURY.may_load(deps.storage)?.unwrap_or_default();
-    let rcpt_addr = deps.api.addr_validate(&recipient)?;
-    let owner_addr = deps.api.addr_validate(&owner)?;
-    let taxes = query_tax(deps.storage, amount)?;
-    let outgoing_amount = if is_pair { taxes.after_tax } else { amount };
+    locks.retain(|lock| lock.unlock_time > now);
+    locks.push(TokenLock {
+        amount,
+        unlock_time,
+    });
+    LOCKS.save(deps.storage, &beneficiary, &locks)?;
 
-    // deduct allowance before doing anything else have enough allowance
-    deduct_allowance(deps.storage, &owner_addr, &info.sender, &env.block, amount)?;
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "lock_tokens"),
+        attr("beneficiary", beneficiary),
+        attr("amount", amount),
+        attr("unlock_time", unlock_time.to_string()),
+    ]))
+}
 
-    BALANCES.update(
-        deps.storage,
-        &owner_addr,
-        |balance: Option<Uint128>| -> StdResult<_> {
-            Ok(balance.unwrap_or_default().checked_sub(amount)?)
-        },
-    )?;
-    BALANCES.update(
-        deps.storage,
-        &rcpt_addr,
-        |balance: Option<Uint128>| -> StdResult<_> {
-            Ok(balance.unwrap_or_default() + outgoing_amount)
-        },
-    )?;
+/// Caps what `spender` can move from the sender's allowance per period, `None` removes the cap
+pub fn set_allowance_limit(
+    deps: DepsMut,
+    info: MessageInfo,
+    spender: String,
+    limit: Option<AllowanceLimit>,
+) -> Result<Response, ContractError> {
+    let spender = deps.api.addr_validate(&spender)?;
+    let key = (&info.sender, &spender);
 
-    let mut messages = vec![];
-    if is_pair {
-        BALANCES.update(
-            deps.storage,
-            &deps.api.addr_validate(&treasury)?,
-            |balance: Option<Uint128>| -> StdResult<_> {
-                Ok(balance.unwrap_or_default() + taxes.taxed_amount)
-            },
-        )?;
+    match &limit {
+        Some(limit) => {
+            if limit.period == 0 {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "period must be greater than 0",
+                )));
+            }
+            ALLOWANCE_LIMITS.save(deps.storage, key, limit)?;
+        }
+        None => ALLOWANCE_LIMITS.remove(deps.storage, key),
+    }
+    ALLOWANCE_WINDOWS.remove(deps.storage, key);
 
-        let buyback_enabled = BUYBACK_ENABLE.may_load(deps.storage)?.unwrap_or_default();
-        if buyback_enabled {
-            messages.push(WasmMsg::Execute {
-                contract_addr: env.contract.address.to_string(),
-                msg: to_json_binary(&ExecuteMsg::TransferEvent {
-                    from: info.sender.to_string(),
-                    to: treasury.to_string(),
-                    amount: taxes.taxed_amount,
-                })?,
-                funds: vec![],
-            })
-        }           
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_allowance_limit"),
+        attr("owner", &info.sender),
+        attr("spender", spender),
+    ]))
+}
+
+/// Registers the public key the sender signs permits with, `None` disables permits
+pub fn set_permit_key(
+    deps: DepsMut,
+    info: MessageInfo,
+    pubkey: Option<Binary>,
+) -> Result<Response, ContractError> {
+    match &pubkey {
+        Some(pubkey) => {
+            if pubkey.len() != 33 && pubkey.len() != 65 {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "Invalid secp256k1 public key",
+                )));
+            }
+            PERMIT_KEYS.save(deps.storage, &info.sender, pubkey)?;
+        }
+        None => PERMIT_KEYS.remove(deps.storage, &info.sender),
     }
 
-    let res = Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "transfer_from"),
-        attr("from", owner),
-        attr("to", recipient),
-        attr("by", info.sender),
-        attr("amount", outgoing_amount),
-    ]);
-    Ok(res)
+    Ok(Response::new()
+        .add_attribute("action", "set_permit_key")
+        .add_attribute("owner", info.sender))
 }
 
-pub fn execute_send_from(
+/// Sets the allowance of `spender` over the tokens of `owner` from a permit signed off-chain by `owner`.
+/// The signature covers the sha256 hash of the JSON encoded `PermitPayload`
+#[allow(clippy::too_many_arguments)]
+pub fn execute_permit(
     deps: DepsMut,
     env: Env,
-    info: MessageInfo,
     owner: String,
-    contract: String,
+    spender: String,
     amount: Uint128,
-    msg: Binary,
+    expires: Option<Expiration>,
+    nonce: u64,
+    deadline: u64,
+    signature: Binary,
 ) -> Result<Response, ContractError> {
-   
-    let to_pair = PAIRLIST
-        .may_load(deps.storage, contract.clone())?
-        .unwrap_or_default();
-    let from_pair = PAIRLIST
-        .may_load(deps.storage, info.sender.to_string())?
-        .unwrap_or_default();
-    let is_pair = to_pair || from_pair ;
-    let treasury = TREASURY.may_load(deps.storage)?.unwrap_or_default();
-    let rcpt_addr = deps.api.addr_validate(&contract)?;
     let owner_addr = deps.api.addr_validate(&owner)?;
-    let taxes = query_tax(deps.storage, amount)?;
-    let outgoing_amount = if is_pair { taxes.after_tax  } else { amount };
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    if spender_addr == owner_addr {
+        return Err(ContractError::CannotSetOwnAccount {});
+    }
+    if env.block.time.seconds() > deadline {
+        return Err(ContractError::Std(StdError::generic_err("Permit expired")));
+    }
+    if expires.is_some_and(|expires| expires.is_expired(&env.block)) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Permit allowance already expired",
+        )));
+    }
+    let expected_nonce = PERMIT_NONCES
+        .may_load(deps.storage, &owner_addr)?
+        .unwrap_or_default();
+    if nonce != expected_nonce {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Invalid permit nonce, expected {}",
+            expected_nonce
+        ))));
+    }
+    let pubkey = PERMIT_KEYS
+        .may_load(deps.storage, &owner_addr)?
+        .ok_or_else(|| StdError::generic_err("Owner has no permit key"))?;
 
-    // deduct allowance before doing anything else have enough allowance
-    deduct_allowance(deps.storage, &owner_addr, &info.sender, &env.block, amount)?;
+    let payload = PermitPayload {
+        chain_id: env.block.chain_id.clone(),
+        contract: env.contract.address.to_string(),
+        owner: owner.clone(),
+        spender: spender.clone(),
+        amount,
+        expires,
+        nonce,
+        deadline,
+    };
+    let hash = Sha256::digest(to_json_vec(&payload)?);
+    let valid = deps
+        .api
+        .secp256k1_verify(&hash, &signature, &pubkey)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    if !valid {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Invalid permit signature",
+        )));
+    }
 
-    // move the tokens to the contract
-    BALANCES.update(
-        deps.storage,
-        &owner_addr,
-        |balance: Option<Uint128>| -> StdResult<_> {
-            Ok(balance.unwrap_or_default().checked_sub(amount)?)
-        },
-    )?;
-    BALANCES.update(
+    PERMIT_NONCES.save(deps.storage, &owner_addr, &(nonce + 1))?;
+    ALLOWANCES.save(
         deps.storage,
-        &rcpt_addr,
-        |balance: Option<Uint128>| -> StdResult<_> {
-            Ok(balance.unwrap_or_default() + outgoing_amount)
+        (&owner_addr, &spender_addr),
+        &AllowanceResponse {
+            allowance: amount,
+            expires: expires.unwrap_or_default(),
         },
     )?;
 
-    let mut messages = vec![];
-    if is_pair  {
-        BALANCES.update(
-            deps.storage,
-            &deps.api.addr_validate(&treasury)?,
-            |balance: Option<Uint128>| -> StdResult<_> {
-                Ok(balance.unwrap_or_default() + taxes.taxed_amount)
-            },
-        )?;
-
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "permit"),
+        attr("owner", owner),
+        attr("spender", spender),
+        attr("amount", amount),
+    ]))
+}
 
-        let buyback_enabled = BUYBACK_ENABLE.may_load(deps.storage)?.unwrap_or_default();
-        if buyback_enabled {
-            messages.push(WasmMsg::Execute {
-                contract_addr: env.contract.address.to_string(),
-                msg: to_json_binary(&ExecuteMsg::TransferEvent {
-                    from: info.sender.to_string(),
-                    to: treasury.to_string(),
-                    amount: taxes.taxed_amount,
-                })?,
-                funds: vec![],
-            })
-        }         
+/// Returns the amount of `schedule` vested at `now`. Nothing vests before the cliff,
+/// after which the total vests linearly from `start` over `duration`
+pub fn vested_amount(schedule: &VestingSchedule, now: u64) -> Uint128 {
+    let elapsed = now.saturating_sub(schedule.start);
+    if elapsed < schedule.cliff {
+        Uint128::zero()
+    } else if elapsed >= schedule.duration {
+        schedule.total
+    } else {
+        schedule.total.multiply_ratio(elapsed, schedule.duration)
     }
+}
 
-    let attrs = vec![
+/// Releases the vested tokens of the sender that were not released yet
+pub fn claim_vested(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut schedule = VESTING
+        .may_load(deps.storage, &info.sender)?
+        .ok_or_else(|| StdError::generic_err("No vesting schedule"))?;
+    let amount = vested_amount(&schedule, env.block.time.seconds()) - schedule.released;
+    if amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "No vested tokens to claim",
+        )));
+    }
+
+    schedule.released += amount;
+    VESTING.save(deps.storage, &info.sender, &schedule)?;
+    move_balance(
+        deps.storage,
+        &env,
+        &env.contract.address,
+        &info.sender,
+        amount,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "claim_vested")
+        .add_attribute("to", info.sender)
+        .add_attribute("amount", amount))
+}
+
+/// Returns the vesting schedule of `address` along with its vested and claimable amounts
+pub fn query_vesting(deps: Deps, env: Env, address: String) -> StdResult<VestingResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let schedule = VESTING.may_load(deps.storage, &address)?;
+    let vested = schedule
+        .as_ref()
+        .map(|schedule| vested_amount(schedule, env.block.time.seconds()))
+        .unwrap_or_default();
+    let released = schedule
+        .as_ref()
+        .map(|schedule| schedule.released)
+        .unwrap_or_default();
+
+    Ok(VestingResponse {
+        schedule,
+        vested,
+        claimable: vested - released,
+    })
+}
+
+/// Moves `amount` from `from` to `to` without taxes, syncing the reflections of both
+pub fn move_balance(
+    storage: &mut dyn Storage,
+    env: &Env,
+    from: &Addr,
+    to: &Addr,
+    amount: Uint128,
+) -> StdResult<()> {
+    compound_reflection(storage, env, from)?;
+    debit(storage, from, amount)?;
+    sync_reflection(storage, env, from)?;
+    credit(storage, env, to, amount)
+}
+
+/// Adds `amount` to the balance of `to`, syncing its reflections
+pub fn credit(storage: &mut dyn Storage, env: &Env, to: &Addr, amount: Uint128) -> StdResult<()> {
+    BALANCES.update(storage, to, |balance: Option<Uint128>| -> StdResult<_> {
+        Ok(balance.unwrap_or_default() + amount)
+    })?;
+    sync_reflection(storage, env, to)?;
+
+    Ok(())
+}
+
+/// Registers a new merkle airdrop stage funded with `total_amount` from the admin balance
+/// Leaves are `sha256("{index},{address},{amount}")` and proofs hash sorted pairs
+pub fn register_merkle_root(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    merkle_root: String,
+    total_amount: Uint128,
+    expiration: Option<u64>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let mut root = [0u8; 32];
+    hex::decode_to_slice(&merkle_root, &mut root)
+        .map_err(|_| StdError::generic_err("Invalid merkle root"))?;
+    if total_amount.is_zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    if expiration.is_some_and(|expiration| expiration <= env.block.time.seconds()) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Expiration must be in the future",
+        )));
+    }
+
+    let stage = LATEST_MERKLE_STAGE
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .checked_add(1)
+        .ok_or_else(|| StdError::generic_err("Too many merkle stages"))?;
+    LATEST_MERKLE_STAGE.save(deps.storage, &stage)?;
+    MERKLE_STAGES.save(
+        deps.storage,
+        stage,
+        &MerkleStage {
+            merkle_root: merkle_root.clone(),
+            total_amount,
+            claimed_amount: Uint128::zero(),
+            expiration,
+            closed: false,
+        },
+    )?;
+
+    move_balance(
+        deps.storage,
+        &env,
+        &info.sender,
+        &env.contract.address,
+        total_amount,
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "register_merkle_root"),
+        attr("stage", stage.to_string()),
+        attr("merkle_root", merkle_root),
+        attr("total_amount", total_amount),
+    ]))
+}
+
+/// Verifies the merkle proof of a leaf against a hex encoded root
+pub fn verify_merkle_proof(root: &str, leaf: &str, proof: &[String]) -> StdResult<bool> {
+    let mut hash: [u8; 32] = Sha256::digest(leaf.as_bytes()).into();
+    for sibling in proof {
+        let mut sibling_hash = [0u8; 32];
+        hex::decode_to_slice(sibling, &mut sibling_hash)
+            .map_err(|_| StdError::generic_err("Invalid merkle proof"))?;
+
+        let mut pair = [hash, sibling_hash];
+        pair.sort_unstable();
+        hash = Sha256::digest(pair.concat()).into();
+    }
+
+    Ok(hex::encode(hash) == root.to_lowercase())
+}
+
+/// Claims the airdrop of leaf `index` in `stage` for the sender
+pub fn claim_merkle_airdrop(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stage: u8,
+    index: u32,
+    amount: Uint128,
+    proof: Vec<String>,
+) -> Result<Response, ContractError> {
+    let mut merkle_stage = MERKLE_STAGES.load(deps.storage, stage)?;
+    if merkle_stage.closed
+        || merkle_stage
+            .expiration
+            .is_some_and(|expiration| env.block.time.seconds() >= expiration)
+    {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Airdrop stage has expired",
+        )));
+    }
+
+    let (word, bit) = (index / 64, 1u64 << (index % 64));
+    let claimed = MERKLE_CLAIMED
+        .may_load(deps.storage, (stage, word))?
+        .unwrap_or_default();
+    if claimed & bit != 0 {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Airdrop already claimed",
+        )));
+    }
+
+    let leaf = format!("{},{},{}", index, info.sender, amount);
+    if !verify_merkle_proof(&merkle_stage.merkle_root, &leaf, &proof)? {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Invalid merkle proof",
+        )));
+    }
+
+    merkle_stage.claimed_amount += amount;
+    if merkle_stage.claimed_amount > merkle_stage.total_amount {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Airdrop stage is exhausted",
+        )));
+    }
+    MERKLE_STAGES.save(deps.storage, stage, &merkle_stage)?;
+    MERKLE_CLAIMED.save(deps.storage, (stage, word), &(claimed | bit))?;
+
+    move_balance(
+        deps.storage,
+        &env,
+        &env.contract.address,
+        &info.sender,
+        amount,
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "claim_airdrop"),
+        attr("stage", stage.to_string()),
+        attr("to", info.sender),
+        attr("amount", amount),
+    ]))
+}
+
+/// Returns the unclaimed tokens of an expired stage to `recipient`, defaulting to the sender
+pub fn clawback_merkle_stage(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    stage: u8,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let mut merkle_stage = MERKLE_STAGES.load(deps.storage, stage)?;
+    let expired = merkle_stage
+        .expiration
+        .is_some_and(|expiration| env.block.time.seconds() >= expiration);
+    if merkle_stage.closed || !expired {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Airdrop stage has not expired",
+        )));
+    }
+
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => info.sender,
+    };
+    let unclaimed = merkle_stage.total_amount - merkle_stage.claimed_amount;
+    merkle_stage.closed = true;
+    MERKLE_STAGES.save(deps.storage, stage, &merkle_stage)?;
+
+    move_balance(
+        deps.storage,
+        &env,
+        &env.contract.address,
+        &recipient,
+        unclaimed,
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "clawback_airdrop"),
+        attr("stage", stage.to_string()),
+        attr("to", recipient),
+        attr("amount", unclaimed),
+    ]))
+}
+
+pub fn execute_send(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: String,
+    amount: Uint128,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    if amount == Uint128::zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    // move the tokens to the contract
+    let rcpt_addr = deps.api.addr_validate(&contract)?;
+    check_send_allowed(deps.storage, &rcpt_addr)?;
+    let transfer = transfer_with_tax(&mut deps, &env, &info.sender, &rcpt_addr, amount)?;
+
+    let res = Response::new()
+        .add_submessages(transfer.messages)
+        .add_events(transfer.events)
+        .add_attribute("action", "send")
+        .add_attribute("from", &info.sender)
+        .add_attribute("to", &contract)
+        .add_attributes(amount_attributes(deps.storage, &transfer.taxes)?)
+        .add_message(
+            // We do not modify the send message, but we allow the hooked contract to calculate taxes against this contract
+            Cw20ReceiveMsg {
+                sender: info.sender.into(),
+                amount: transfer.received,
+                msg,
+            }
+            .into_cosmos_msg(contract)?,
+        );
+    Ok(res)
+}
+
+pub fn execute_transfer_from(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let rcpt_addr = deps.api.addr_validate(&recipient)?;
+    let owner_addr = deps.api.addr_validate(&owner)?;
+
+    // the taxes are known once the transfer is applied, the whole transfer fails without allowance
+    let transfer = transfer_with_tax(&mut deps, &env, &owner_addr, &rcpt_addr, amount)?;
+    let spent = allowance_spent(deps.storage, amount, &transfer.taxes)?;
+    check_allowance_limit(deps.storage, &env, &owner_addr, &info.sender, spent)?;
+    deduct_allowance(deps.storage, &owner_addr, &info.sender, &env.block, spent)?;
+
+    let res = Response::new()
+        .add_submessages(transfer.messages)
+        .add_events(transfer.events)
+        .add_attributes(vec![
+            attr("action", "transfer_from"),
+            attr("from", owner),
+            attr("to", recipient),
+            attr("by", info.sender),
+        ])
+        .add_attributes(amount_attributes(deps.storage, &transfer.taxes)?);
+    Ok(res)
+}
+
+pub fn execute_send_from(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    contract: String,
+    amount: Uint128,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let rcpt_addr = deps.api.addr_validate(&contract)?;
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    check_send_allowed(deps.storage, &rcpt_addr)?;
+
+    // move the tokens to the contract, the whole send fails without allowance
+    let transfer = transfer_with_tax(&mut deps, &env, &owner_addr, &rcpt_addr, amount)?;
+    let spent = allowance_spent(deps.storage, amount, &transfer.taxes)?;
+    check_allowance_limit(deps.storage, &env, &owner_addr, &info.sender, spent)?;
+    deduct_allowance(deps.storage, &owner_addr, &info.sender, &env.block, spent)?;
+
+    let mut attrs = vec![
         attr("action", "send_from"),
         attr("from", &owner),
         attr("to", &contract),
         attr("by", &info.sender),
-        attr("amount", outgoing_amount),
     ];
+    attrs.extend(amount_attributes(deps.storage, &transfer.taxes)?);
 
     // create a send message
     let msg = Cw20ReceiveMsg {
         sender: info.sender.clone().into(),
-        amount: outgoing_amount,
+        amount: transfer.received,
         msg,
     }
     .into_cosmos_msg(contract)?;
 
     let res = Response::new()
-        .add_messages(messages)
+        .add_submessages(transfer.messages)
+        .add_events(transfer.events)
         .add_message(msg)
         .add_attributes(attrs);
     Ok(res)
 }
 
+/// Allowance used by a transfer of `amount` on behalf of its owner. In tax exclusive mode the owner is
+/// debited the taxes on top of `amount`, so the spender pays them out of the allowance too
+pub fn allowance_spent(
+    storage: &dyn Storage,
+    amount: Uint128,
+    taxes: &QueryTaxResponse,
+) -> StdResult<Uint128> {
+    match TAX_MODE.may_load(storage)? {
+        Some(TaxMode::Exclusive) => Ok(amount.checked_add(taxes.taxed_amount)?),
+        _ => Ok(amount),
+    }
+}
 
-pub fn execute_mint(
-    deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
-    recipient: String,
+/// Rejects `Send` to contracts outside of the allowlist when it is enabled. Pairs are always allowed
+pub fn check_send_allowed(storage: &dyn Storage, contract: &Addr) -> Result<(), ContractError> {
+    if !SEND_ALLOWLIST_ENABLED
+        .may_load(storage)?
+        .unwrap_or_default()
+    {
+        return Ok(());
+    }
+    if SEND_ALLOWED.has(storage, contract) || load_pair(storage, contract.as_str())?.is_some() {
+        return Ok(());
+    }
+
+    Err(ContractError::Std(StdError::generic_err(format!(
+        "{} is not allowed to receive tokens with Send",
+        contract
+    ))))
+}
+
+/// Shared balance movement for all transfer functions. Debits `amount` from `owner`, credits the after-tax
+/// amount to `recipient`, and credits the taxes to the treasury. The tax rate depends on the direction of the
+/// transfer (see `query_transfer_rate`).
+/// Returns the amount received by `recipient` and any messages and events to be dispatched alongside the transfer
+pub fn transfer_with_tax(
+    deps: &mut DepsMut,
+    env: &Env,
+    owner: &Addr,
+    recipient: &Addr,
     amount: Uint128,
-) -> Result<Response, ContractError> {
-    if amount == Uint128::zero() {
-        return Err(ContractError::InvalidZeroAmount {});
+) -> Result<TaxedTransfer, ContractError> {
+    let sniper_penalty = check_sender(deps.storage, env, owner)?;
+    compound_reflection(deps.storage, env, owner)?;
+    debit(deps.storage, owner, amount)?;
+    let res = credit_with_tax(deps, env, owner, recipient, amount, &sniper_penalty)?;
+    check_locked_balance(deps.storage, env, owner)?;
+    sync_reflection(deps.storage, env, owner)?;
+
+    Ok(res)
+}
+
+/// Checks that `owner` is allowed to transfer, once per transfer or batch
+/// Returns the sniper penalty of `owner`, if any
+pub fn check_sender(
+    storage: &mut dyn Storage,
+    env: &Env,
+    owner: &Addr,
+) -> Result<Option<SniperPenalty>, ContractError> {
+    check_trading_enabled(storage, owner)?;
+    if FROZEN.has(storage, owner) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Sender account is frozen",
+        )));
     }
+    let sniper_penalty = query_sniper_penalty(storage, owner)?;
+    if let Some(SniperPenalty::Freeze {}) = sniper_penalty {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Sender is flagged as a sniper",
+        )));
+    }
+    check_cooldown(storage, env, owner)?;
 
-    ensure_admin(&deps, &info)?;
+    Ok(sniper_penalty)
+}
 
-    let mut config = TOKEN_INFO.load(deps.storage)?;
- 
-    // update supply and enforce cap
-    config.total_supply += amount;
+/// Removes `amount` from the balance of `owner`, without syncing its reflections
+pub fn debit(storage: &mut dyn Storage, owner: &Addr, amount: Uint128) -> StdResult<()> {
+    BALANCES.update(storage, owner, |balance: Option<Uint128>| -> StdResult<_> {
+        Ok(balance.unwrap_or_default().checked_sub(amount)?)
+    })?;
 
-    TOKEN_INFO.save(deps.storage, &config)?;
+    Ok(())
+}
 
-    // add amount to recipient balance
-    let rcpt_addr = deps.api.addr_validate(&recipient)?;
-    BALANCES.update(
+/// Outcome of a single taxed transfer leg
+pub struct TaxedTransfer {
+    /// Amount credited to the recipient
+    pub received: Uint128,
+    pub taxes: QueryTaxResponse,
+    pub messages: Vec<SubMsg>,
+    /// `transfer` and `tax` events of the leg
+    pub events: Vec<Event>,
+}
+
+/// Credits `amount` already debited from `owner` to `recipient`, minus taxes which go to the treasury
+/// and holders. In tax exclusive mode the taxes are debited from `owner` on top of `amount` instead.
+/// `owner` must be synced and checked for locked tokens by the caller once all legs are credited
+pub fn credit_with_tax(
+    deps: &mut DepsMut,
+    env: &Env,
+    owner: &Addr,
+    recipient: &Addr,
+    amount: Uint128,
+    sniper_penalty: &Option<SniperPenalty>,
+) -> Result<TaxedTransfer, ContractError> {
+    TokenTax::new(deps.storage).check_max_transfer(owner, recipient, amount)?;
+    check_sell_limit(deps.storage, env, owner, recipient, amount)?;
+    check_same_block_trade(deps.storage, env, owner, recipient)?;
+
+    let rates = query_transfer_rates(deps.as_ref(), env, owner, recipient, amount)?;
+    let exempt = is_tax_exempt(deps.storage, owner)? || is_tax_exempt(deps.storage, recipient)?;
+    // the dynamic tax of this sell is priced on the volume before it
+    if !exempt && load_pair(deps.storage, recipient.as_str())?.is_some() {
+        record_sell_volume(deps.storage, env, amount)?;
+    }
+    // snipers pay the punitive tax on top of the regular tax
+    let surtax = match sniper_penalty {
+        Some(SniperPenalty::Tax { rate }) => rates.surtax + *rate,
+        _ => rates.surtax,
+    };
+    let trade = is_taxed_trade(deps.storage, owner, recipient)?;
+    let mut taxes = query_tax(deps.storage, env, amount, rates.tax_rate, surtax, trade)?;
+
+    // tax exclusive transfers charge the taxes on top, so the recipient receives exactly `amount`
+    if TAX_MODE.may_load(deps.storage)? == Some(TaxMode::Exclusive) {
+        debit(deps.storage, owner, taxes.taxed_amount)?;
+        taxes.after_tax = amount;
+    }
+
+    let recipient_balance = BALANCES.update(
         deps.storage,
-        &rcpt_addr,
-        |balance: Option<Uint128>| -> StdResult<_> { Ok(balance.unwrap_or_default() + amount) },
+        recipient,
+        |balance: Option<Uint128>| -> StdResult<_> {
+            Ok(balance.unwrap_or_default() + taxes.after_tax)
+        },
     )?;
+    let recipient_locked = locked_amount(deps.storage, env, recipient)?;
+    TokenTax::new(deps.storage)
+        .check_max_wallet(recipient, recipient_balance - recipient_locked)?;
+    flag_sniper(deps.storage, env, owner, recipient)?;
 
-    let res = Response::new()
-        .add_attribute("action", "mint")
+    let contract = &env.contract.address;
+    sync_reflection(deps.storage, env, recipient)?;
+
+    let mut messages = vec![];
+    let mut events = vec![Event::new("transfer")
+        .add_attribute("from", owner)
         .add_attribute("to", recipient)
-        .add_attribute("amount", amount);
-    Ok(res)
-}
+        .add_attribute("amount", taxes.after_tax)];
+    // the transfer went through at the base rate, indexers can still tell the strategy failed
+    if let Some(error) = rates.strategy_error {
+        events.push(
+            Event::new("tax_strategy_failed")
+                .add_attribute("from", owner)
+                .add_attribute("to", recipient)
+                .add_attribute("error", error),
+        );
+    }
 
+    // we apply taxes, and immediately add them to the treasury by modifying balance variables
+    // The `tax` event lets explorers track the treasury balance like a regular transfer
+    if !taxes.taxed_amount.is_zero() {
+        let treasury = TREASURY
+            .may_load(deps.storage)?
+            .ok_or_else(|| StdError::generic_err("Treasury is not set"))?;
+        let treasury_addr = deps.api.addr_validate(&treasury)?;
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    msg: ExecuteMsg,
-) -> Result<Response, ContractError> {
-    match msg {
-        ExecuteMsg::Transfer { recipient, amount } => {
-            execute_transfer(deps, env, info, recipient, amount)
+        // the holder share stays in the contract and is distributed pro-rata, the rest goes to the treasury
+        let distributed = distribute_reflection(deps.storage, env, taxes.holder_reflection_amount)?;
+        if distributed {
+            events.push(
+                Event::new("reflection")
+                    .add_attribute("from", owner)
+                    .add_attribute("to", contract)
+                    .add_attribute("asset", contract)
+                    .add_attribute("amount", taxes.holder_reflection_amount),
+            );
         }
-        ExecuteMsg::Burn { amount } => execute_burn(deps, env, info, amount),
-        ExecuteMsg::Send {
-            contract,
-            amount,
-            msg,
-        } => execute_send(deps, env, info, contract, amount, msg),
-        ExecuteMsg::Mint { recipient, amount } => execute_mint(deps, env, info, recipient, amount),
-        ExecuteMsg::IncreaseAllowance {
-            spender,
-            amount,
-            expires,
-        } => execute_increase_allowance(deps, env, info, spender, amount, expires),
-        ExecuteMsg::DecreaseAllowance {
-            spender,
-            amount,
-            expires,
-        } => execute_decrease_allowance(deps, env, info, spender, amount, expires),
-        ExecuteMsg::TransferFrom {
-            owner,
-            recipient,
-            amount,
-        } => execute_transfer_from(deps, env, info, owner, recipient, amount),
-        ExecuteMsg::BurnFrom { owner, amount } => execute_burn_from(deps, env, info, owner, amount),
-        ExecuteMsg::SendFrom {
-            owner,
-            contract,
-            amount,
-            msg,
-        } => execute_send_from(deps, env, info, owner, contract, amount, msg),
-        ExecuteMsg::UpdateMarketing {
-            project,
-            description,
-            marketing,
-        } => execute_update_marketing(deps, env, info, project, description, marketing),
-        ExecuteMsg::UploadLogo(logo) => execute_upload_logo(deps, env, info, logo),
+        // without holders to distribute to, the treasury receives the holder share too
+        let holder_amount = if distributed {
+            taxes.holder_reflection_amount
+        } else {
+            Uint128::zero()
+        };
+        let treasury_amount = taxes.reflection_amount
+            + taxes.burn_amount
+            + taxes.liquidity_amount
+            + (taxes.holder_reflection_amount - holder_amount);
 
-        // Reflection features
-        ExecuteMsg::SetTreasury { contract } => set_treasury(deps, info, contract),
-        ExecuteMsg::SetPair { contract, enable } => set_pairlist(deps, info, contract, enable),
-        ExecuteMsg::SetTaxRate {
-            global_rate,
-            reflection_rate,
-            burn_rate,
-        } => set_tax_rate(
-            deps,
-            env,
-            info,
-            global_rate,
-            reflection_rate,
-            burn_rate
+        let instant_burn = INSTANT_BURN.may_load(deps.storage)?.unwrap_or_default();
+        let burned = if instant_burn {
+            taxes.burn_amount
+        } else {
+            Uint128::zero()
+        };
+        if !burned.is_zero() {
+            TOKEN_INFO.update(deps.storage, |mut info| -> StdResult<_> {
+                info.total_supply = info.total_supply.checked_sub(burned)?;
+                Ok(info)
+            })?;
+            checkpoint_total_supply(deps.storage, env)?;
+            events.push(burn_event(owner, burned));
+        }
+        let treasury_amount = treasury_amount - burned;
+
+        let mut stats = TAX_STATS.may_load(deps.storage)?.unwrap_or_default();
+        stats.total_taxed += taxes.taxed_amount;
+        stats.total_burned += burned;
+        stats.total_to_treasury += treasury_amount;
+        stats.total_holder_reflections += holder_amount;
+        TAX_STATS.save(deps.storage, &stats)?;
+
+        // buys are paid by the buyer, sells and transfers by the sender
+        let kind = TokenTax::new(deps.storage).transfer_kind(owner, recipient)?;
+        let payer = match kind {
+            TransferKind::Buy(_) => recipient,
+            _ => owner,
+        };
+        let mut paid = ACCOUNT_TAX_PAID
+            .may_load(deps.storage, payer)?
+            .unwrap_or_default();
+        match kind {
+            TransferKind::Buy(_) => paid.buy += taxes.taxed_amount,
+            TransferKind::Sell(_) => paid.sell += taxes.taxed_amount,
+            TransferKind::Transfer => paid.transfer += taxes.taxed_amount,
+        }
+        ACCOUNT_TAX_PAID.save(deps.storage, payer, &paid)?;
+
+        BALANCES.update(
+            deps.storage,
+            &treasury_addr,
+            |balance: Option<Uint128>| -> StdResult<_> {
+                Ok(balance.unwrap_or_default() + treasury_amount)
+            },
+        )?;
+        sync_reflection(deps.storage, env, &treasury_addr)?;
+
+        events.push(
+            Event::new("tax")
+                .add_attribute("from", owner)
+                .add_attribute("to", &treasury)
+                .add_attribute("asset", contract)
+                .add_attribute("amount", treasury_amount)
+                .add_attribute("taxed_amount", taxes.taxed_amount),
+        );
+
+        // liquify at most once per cooldown so nested transfers of the treasury cannot run out of gas
+        let buyback_enabled = BUYBACK_ENABLE.may_load(deps.storage)?.unwrap_or_default();
+        let last_liquify = LAST_LIQUIFY.may_load(deps.storage)?.unwrap_or_default();
+        let cooldown = LIQUIFY_COOLDOWN.may_load(deps.storage)?.unwrap_or(1);
+        let accumulated =
+            ACCUMULATED_TAX.may_load(deps.storage)?.unwrap_or_default() + treasury_amount;
+        let threshold = LIQUIFY_THRESHOLD
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        if buyback_enabled
+            && env.block.time.seconds() > last_liquify + cooldown
+            && accumulated >= threshold
+        {
+            LAST_LIQUIFY.save(deps.storage, &env.block.time.seconds())?;
+            ACCUMULATED_TAX.save(deps.storage, &Uint128::zero())?;
+            messages.push(SubMsg::reply_on_error(
+                WasmMsg::Execute {
+                    contract_addr: treasury,
+                    msg: to_json_binary(&TreasuryExecuteMsg::Liquify {})?,
+                    funds: vec![],
+                },
+                LIQUIFY_REPLY_ID,
+            ));
+        } else {
+            ACCUMULATED_TAX.save(deps.storage, &accumulated)?;
+        }
+    }
+
+    Ok(TaxedTransfer {
+        received: taxes.after_tax,
+        taxes,
+        messages,
+        events,
+    })
+}
+
+/// The `burn` event of every supply reduction, whether burned by the holder or from the taxes
+pub fn burn_event(from: &Addr, amount: Uint128) -> Event {
+    Event::new("burn")
+        .add_attribute("from", from)
+        .add_attribute("amount", amount)
+}
+
+/// Used to scale the reflection accumulator so small distributions against a large supply are not lost to rounding
+pub const REFLECTION_MAGNITUDE: u128 = 1_000_000_000_000_000_000;
+
+/// Accrual state of a single holder. `shares` mirrors the holder's balance at the last sync (zero if excluded),
+/// and `debt` is the magnified amount of reflections already accounted for those shares
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct HolderReflection {
+    pub shares: Uint128,
+    pub debt: Uint256,
+    pub pending: Uint128,
+    pub claimed: Uint128,
+    /// Same accounting for reflections paid in the native denom
+    #[serde(default)]
+    pub native_debt: Uint256,
+    #[serde(default)]
+    pub native_pending: Uint128,
+    #[serde(default)]
+    pub native_claimed: Uint128,
+}
+
+/// Reflections accrued by `shares` since the accumulator was at `debt`
+fn accrued_reflection(shares: Uint128, per_share: Uint256, debt: Uint256) -> StdResult<Uint128> {
+    let accrued = Uint256::from(shares)
+        .checked_mul(per_share)?
+        .checked_sub(debt)?
+        .checked_div(Uint256::from(REFLECTION_MAGNITUDE))?;
+    Ok(Uint128::try_from(accrued)?)
+}
+
+/// Settles the accrued reflections of `address` and re-snapshots its shares from the current balance.
+/// Must be called after every balance change so the accrual of each holder stays pro-rata, and its
+/// balance history is checkpointed
+pub fn sync_reflection(
+    storage: &mut dyn Storage,
+    env: &Env,
+    address: &Addr,
+) -> StdResult<HolderReflection> {
+    let contract = &env.contract.address;
+    let mut holder = HOLDER_REFLECTIONS
+        .may_load(storage, address)?
+        .unwrap_or_default();
+    let per_share = REFLECTION_PER_SHARE.may_load(storage)?.unwrap_or_default();
+    let native_per_share = NATIVE_REFLECTION_PER_SHARE
+        .may_load(storage)?
+        .unwrap_or_default();
+
+    holder.pending += accrued_reflection(holder.shares, per_share, holder.debt)?;
+    // the fraction of a token left by the division is carried over, so the reflections lost to
+    // rounding do not add up with every sync
+    let remainder = Uint256::from(holder.shares)
+        .checked_mul(per_share)?
+        .checked_sub(holder.debt)?
+        .checked_rem(Uint256::from(REFLECTION_MAGNITUDE))?;
+    holder.native_pending +=
+        accrued_reflection(holder.shares, native_per_share, holder.native_debt)?;
+
+    // in rebase mode the reflections held by the contract are moved into the balance right away
+    if !holder.pending.is_zero()
+        && address != contract
+        && REBASE.may_load(storage)?.unwrap_or_default()
+    {
+        debit(storage, contract, holder.pending)?;
+        // checkpoints the contract balance, the contract never accrues so this does not recurse
+        sync_reflection(storage, env, contract)?;
+        take_from_reflection_pool(storage, env, holder.pending)?;
+        BALANCES.update(
+            storage,
+            address,
+            |balance: Option<Uint128>| -> StdResult<_> {
+                Ok(balance.unwrap_or_default() + holder.pending)
+            },
+        )?;
+        holder.claimed += holder.pending;
+        holder.pending = Uint128::zero();
+    }
+
+    // the contract itself holds the undistributed reflections, so it never earns
+    let excluded = REFLECTION_EXCLUDED
+        .may_load(storage, address)?
+        .unwrap_or_default();
+    let shares = if excluded || address == contract {
+        Uint128::zero()
+    } else {
+        BALANCES.may_load(storage, address)?.unwrap_or_default()
+    };
+
+    let total_shares = REFLECTION_TOTAL_SHARES
+        .may_load(storage)?
+        .unwrap_or_default();
+    REFLECTION_TOTAL_SHARES.save(
+        storage,
+        &total_shares
+            .checked_sub(holder.shares)?
+            .checked_add(shares)?,
+    )?;
+
+    holder.shares = shares;
+    holder.debt = Uint256::from(shares)
+        .checked_mul(per_share)?
+        .saturating_sub(remainder);
+    holder.native_debt = Uint256::from(shares).checked_mul(native_per_share)?;
+    HOLDER_REFLECTIONS.save(storage, address, &holder, env.block.height)?;
+
+    let balance = BALANCES.may_load(storage, address)?.unwrap_or_default();
+    let previous = BALANCE_HISTORY
+        .may_load(storage, address)?
+        .unwrap_or_default();
+    if balance != previous {
+        BALANCE_HISTORY.save(storage, address, &balance, env.block.height)?;
+        if address != contract && (previous.is_zero() || balance.is_zero()) {
+            let count = HOLDER_COUNT.may_load(storage)?.unwrap_or_default();
+            let count = if balance.is_zero() {
+                count.saturating_sub(1)
+            } else {
+                count + 1
+            };
+            HOLDER_COUNT.save(storage, &count)?;
+        }
+        let delegate = query_delegate(storage, address)?;
+        update_voting_power(storage, env, &delegate, previous, balance)?;
+    }
+
+    Ok(holder)
+}
+
+/// Returns the address voting with the balance of `address`, itself unless it delegated
+pub fn query_delegate(storage: &dyn Storage, address: &Addr) -> StdResult<Addr> {
+    Ok(DELEGATES
+        .may_load(storage, address)?
+        .unwrap_or_else(|| address.clone()))
+}
+
+/// Replaces `removed` by `added` in the voting power of `delegate`
+fn update_voting_power(
+    storage: &mut dyn Storage,
+    env: &Env,
+    delegate: &Addr,
+    removed: Uint128,
+    added: Uint128,
+) -> StdResult<()> {
+    let power = VOTING_POWER
+        .may_load(storage, delegate)?
+        .unwrap_or_default();
+    let power = power.checked_sub(removed)?.checked_add(added)?;
+    VOTING_POWER.save(storage, delegate, &power, env.block.height)
+}
+
+/// Delegates the voting power of the sender's balance to `delegate`. Delegating to oneself revokes the delegation
+pub fn execute_delegate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    delegate: String,
+) -> Result<Response, ContractError> {
+    let delegate = deps.api.addr_validate(&delegate)?;
+
+    // checkpoint the current balance so it is counted for the right delegate
+    sync_reflection(deps.storage, &env, &info.sender)?;
+    let balance = BALANCE_HISTORY
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+
+    let previous = query_delegate(deps.storage, &info.sender)?;
+    if previous != delegate {
+        update_voting_power(deps.storage, &env, &previous, balance, Uint128::zero())?;
+        update_voting_power(deps.storage, &env, &delegate, Uint128::zero(), balance)?;
+    }
+    if delegate == info.sender {
+        DELEGATES.remove(deps.storage, &info.sender);
+    } else {
+        DELEGATES.save(deps.storage, &info.sender, &delegate)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "delegate"),
+        attr("from", info.sender),
+        attr("delegate", delegate),
+    ]))
+}
+
+/// Checkpoints the total supply for `TotalSupplyAt`, must be called whenever it changes
+pub fn checkpoint_total_supply(storage: &mut dyn Storage, env: &Env) -> StdResult<()> {
+    let total_supply = TOKEN_INFO.load(storage)?.total_supply;
+    TOTAL_SUPPLY_HISTORY.save(storage, &total_supply, env.block.height)
+}
+
+/// In rebase mode, compounds the reflections of `address` into its balance so it can spend them.
+/// Must be called before debiting `address`
+pub fn compound_reflection(storage: &mut dyn Storage, env: &Env, address: &Addr) -> StdResult<()> {
+    if REBASE.may_load(storage)?.unwrap_or_default() {
+        sync_reflection(storage, env, address)?;
+    }
+    Ok(())
+}
+
+/// Returns the balance of `address`, including the reflections it has not compounded yet in rebase mode
+pub fn query_rebased_balance(deps: Deps, env: &Env, address: String) -> StdResult<BalanceResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let balance = BALANCES
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+    Ok(BalanceResponse {
+        balance: rebased_balance(deps.storage, env, &address, balance, None)?,
+    })
+}
+
+/// In rebase mode, adds the reflections `address` has not compounded yet to its stored `balance`, and
+/// leaves the reflections held for the holders out of the contract's own balance so they are not
+/// counted twice. With `height`, `balance` must be the stored balance at that height
+pub fn rebased_balance(
+    storage: &dyn Storage,
+    env: &Env,
+    address: &Addr,
+    balance: Uint128,
+    height: Option<u64>,
+) -> StdResult<Uint128> {
+    if !REBASE.may_load(storage)?.unwrap_or_default() {
+        return Ok(balance);
+    }
+
+    if address == env.contract.address {
+        let pool = match height {
+            Some(height) => REFLECTION_POOL.may_load_at_height(storage, height)?,
+            None => REFLECTION_POOL.may_load(storage)?,
+        };
+        return Ok(balance.saturating_sub(pool.unwrap_or_default()));
+    }
+
+    let (holder, per_share) = match height {
+        Some(height) => (
+            HOLDER_REFLECTIONS.may_load_at_height(storage, address, height)?,
+            REFLECTION_PER_SHARE.may_load_at_height(storage, height)?,
         ),
-        ExecuteMsg::SetBuyBack { enable } => set_buyback(deps, info, enable),
-        ExecuteMsg::TransferEvent { from, to, amount } => {
-            generate_transfer_event(deps, info, env, from, to, amount)
+        None => (
+            HOLDER_REFLECTIONS.may_load(storage, address)?,
+            REFLECTION_PER_SHARE.may_load(storage)?,
+        ),
+    };
+    let holder = holder.unwrap_or_default();
+    let accrued = accrued_reflection(holder.shares, per_share.unwrap_or_default(), holder.debt)?;
+    Ok(balance.checked_add(holder.pending)?.checked_add(accrued)?)
+}
+
+/// Removes the reflections paid out of the contract balance from the reflection pool
+fn take_from_reflection_pool(
+    storage: &mut dyn Storage,
+    env: &Env,
+    amount: Uint128,
+) -> StdResult<()> {
+    let pool = REFLECTION_POOL.may_load(storage)?.unwrap_or_default();
+    REFLECTION_POOL.save(storage, &pool.saturating_sub(amount), env.block.height)
+}
+
+/// Returns the accumulator `per_share` increased by `amount` spread over `total_shares`
+fn increase_per_share(
+    per_share: Uint256,
+    amount: Uint128,
+    total_shares: Uint128,
+) -> StdResult<Uint256> {
+    let increase = Uint256::from(amount)
+        .checked_mul(Uint256::from(REFLECTION_MAGNITUDE))?
+        .checked_div(Uint256::from(total_shares))?;
+    Ok(per_share.checked_add(increase)?)
+}
+
+/// Distributes `amount` to all holders pro-rata by increasing the reflection accumulator.
+/// The tokens themselves are held by the contract until claimed.
+/// Returns false if there are no holders to distribute to
+pub fn distribute_reflection(
+    storage: &mut dyn Storage,
+    env: &Env,
+    amount: Uint128,
+) -> StdResult<bool> {
+    let total_shares = REFLECTION_TOTAL_SHARES
+        .may_load(storage)?
+        .unwrap_or_default();
+    if total_shares.is_zero() || amount.is_zero() {
+        return Ok(false);
+    }
+
+    let per_share = REFLECTION_PER_SHARE.may_load(storage)?.unwrap_or_default();
+    REFLECTION_PER_SHARE.save(
+        storage,
+        &increase_per_share(per_share, amount, total_shares)?,
+        env.block.height,
+    )?;
+    let pool = REFLECTION_POOL.may_load(storage)?.unwrap_or_default();
+    REFLECTION_POOL.save(storage, &pool.checked_add(amount)?, env.block.height)?;
+    credit(storage, env, &env.contract.address, amount)?;
+
+    Ok(true)
+}
+
+/// Pays out the reflections accrued by the sender. Reflections are paid without taxes
+pub fn claim_reflection(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    if REBASE.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Reflections are compounded into balances in rebase mode",
+        )));
+    }
+
+    let mut holder = sync_reflection(deps.storage, &env, &info.sender)?;
+    let amount = holder.pending;
+    if amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "No reflections to claim",
+        )));
+    }
+
+    holder.pending = Uint128::zero();
+    holder.claimed += amount;
+    HOLDER_REFLECTIONS.save(deps.storage, &info.sender, &holder, env.block.height)?;
+    take_from_reflection_pool(deps.storage, &env, amount)?;
+    // the claimed amount increases the holder's shares
+    move_balance(
+        deps.storage,
+        &env,
+        &env.contract.address,
+        &info.sender,
+        amount,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "claim_reflection")
+        .add_attribute("to", info.sender)
+        .add_attribute("amount", amount))
+}
+
+/// Distributes the native funds sent with this message to all holders pro-rata.
+/// Used by the treasury to pay reflections in the native quote denom, but anyone can deposit
+pub fn deposit_native_reflection(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let denom = NATIVE_REFLECTION_DENOM
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("Native reflection denom is not set"))?;
+    let amount = must_pay(&info, &denom).map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    let total_shares = REFLECTION_TOTAL_SHARES
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    if total_shares.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "No holders to distribute to",
+        )));
+    }
+
+    let per_share = NATIVE_REFLECTION_PER_SHARE
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    NATIVE_REFLECTION_PER_SHARE.save(
+        deps.storage,
+        &increase_per_share(per_share, amount, total_shares)?,
+    )?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new("reflection")
+                .add_attribute("from", &info.sender)
+                .add_attribute("to", env.contract.address)
+                .add_attribute("asset", &denom)
+                .add_attribute("amount", amount),
+        )
+        .add_attribute("action", "deposit_native_reflection")
+        .add_attribute("from", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("denom", denom))
+}
+
+/// Pays out the native reflections accrued by the sender
+pub fn claim_native_reflection(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let denom = NATIVE_REFLECTION_DENOM
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("Native reflection denom is not set"))?;
+    let mut holder = sync_reflection(deps.storage, &env, &info.sender)?;
+    let amount = holder.native_pending;
+    if amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "No reflections to claim",
+        )));
+    }
+
+    holder.native_pending = Uint128::zero();
+    holder.native_claimed += amount;
+    HOLDER_REFLECTIONS.save(deps.storage, &info.sender, &holder, env.block.height)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(amount.u128(), &denom),
+        })
+        .add_attribute("action", "claim_native_reflection")
+        .add_attribute("to", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("denom", denom))
+}
+
+pub fn execute_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount == Uint128::zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let mut config = TOKEN_INFO.load(deps.storage)?;
+    if config
+        .mint
+        .as_ref()
+        .map(|minter| minter.minter != info.sender)
+        .unwrap_or(true)
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // update supply and enforce cap
+    config.total_supply += amount;
+    if let Some(limit) = config.get_cap() {
+        if config.total_supply > limit {
+            return Err(ContractError::CannotExceedCap {});
         }
-        ExecuteMsg::MigrateTreasury { code_id } => migrate_treasury(deps, env, info, code_id),
     }
+
+    TOKEN_INFO.save(deps.storage, &config)?;
+    checkpoint_total_supply(deps.storage, &env)?;
+
+    // the mint tax share of the new tokens funds the treasury
+    let mint_tax = amount.mul(MINT_TAX_RATE.may_load(deps.storage)?.unwrap_or_default());
+    if !mint_tax.is_zero() {
+        let treasury = TREASURY
+            .may_load(deps.storage)?
+            .ok_or_else(|| StdError::generic_err("Treasury is not set"))?;
+        let treasury_addr = deps.api.addr_validate(&treasury)?;
+        BALANCES.update(
+            deps.storage,
+            &treasury_addr,
+            |balance: Option<Uint128>| -> StdResult<_> {
+                Ok(balance.unwrap_or_default() + mint_tax)
+            },
+        )?;
+        sync_reflection(deps.storage, &env, &treasury_addr)?;
+
+        let mut stats = TAX_STATS.may_load(deps.storage)?.unwrap_or_default();
+        stats.total_taxed += mint_tax;
+        stats.total_to_treasury += mint_tax;
+        TAX_STATS.save(deps.storage, &stats)?;
+    }
+
+    // add amount to recipient balance
+    let rcpt_addr = deps.api.addr_validate(&recipient)?;
+    BALANCES.update(
+        deps.storage,
+        &rcpt_addr,
+        |balance: Option<Uint128>| -> StdResult<_> {
+            Ok(balance.unwrap_or_default() + amount - mint_tax)
+        },
+    )?;
+    sync_reflection(deps.storage, &env, &rcpt_addr)?;
+
+    let res = Response::new()
+        .add_attribute("action", "mint")
+        .add_attribute("to", recipient)
+        .add_attribute("amount", amount)
+        .add_attribute("mint_tax", mint_tax);
+    Ok(res)
+}
+
+/// Hands the minter role to `new_minter`, keeping the cap. `None` renounces minting for good
+pub fn execute_update_minter(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    new_minter: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = TOKEN_INFO.load(deps.storage)?;
+    let minter = config.mint.as_ref().ok_or(ContractError::Unauthorized {})?;
+    if minter.minter != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let cap = minter.cap;
+    config.mint = match &new_minter {
+        Some(new_minter) => Some(MinterData {
+            minter: deps.api.addr_validate(new_minter)?,
+            cap,
+        }),
+        None => None,
+    };
+    TOKEN_INFO.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_minter")
+        .add_attribute(
+            "new_minter",
+            new_minter.unwrap_or_else(|| "None".to_string()),
+        ))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn execute(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    // token movements and holder actions are not privileged, even when sent by the owner
+    let audited = !matches!(
+        msg,
+        ExecuteMsg::Transfer { .. }
+            | ExecuteMsg::BatchTransfer { .. }
+            | ExecuteMsg::Send { .. }
+            | ExecuteMsg::TransferFrom { .. }
+            | ExecuteMsg::SendFrom { .. }
+            | ExecuteMsg::Burn { .. }
+            | ExecuteMsg::BurnFrom { .. }
+            | ExecuteMsg::IncreaseAllowance { .. }
+            | ExecuteMsg::DecreaseAllowance { .. }
+            | ExecuteMsg::SetAllowanceLimit { .. }
+            | ExecuteMsg::SetPermitKey { .. }
+            | ExecuteMsg::Permit { .. }
+            | ExecuteMsg::Claim { .. }
+            | ExecuteMsg::ClaimVested { .. }
+            | ExecuteMsg::ClaimReflection { .. }
+            | ExecuteMsg::ClaimNative { .. }
+            | ExecuteMsg::DepositNativeReflection { .. }
+            | ExecuteMsg::Delegate { .. }
+            | ExecuteMsg::UpdateMarketing { .. }
+            | ExecuteMsg::UploadLogo(_)
+    );
+    let sender = info.sender.clone();
+    let was_owner = audited && cw_ownable::is_owner(deps.storage, &sender)?;
+
+    let res = execute_msg(deps.branch(), env.clone(), info, msg)?;
+    // the new owner accepting the ownership is recorded too
+    if audited && (was_owner || cw_ownable::is_owner(deps.storage, &sender)?) {
+        record_audit(deps.storage, &env, &sender, &res)?;
+    }
+    Ok(res)
+}
+
+pub fn execute_msg(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    // Every handler changing balances or allowances stops while paused. `ImportState` is the only
+    // one left open: it is how the admin restores the balances of a previous deployment, and the
+    // new deployment is expected to stay paused until the import is finished
+    if matches!(
+        msg,
+        ExecuteMsg::Transfer { .. }
+            | ExecuteMsg::BatchTransfer { .. }
+            | ExecuteMsg::Airdrop { .. }
+            | ExecuteMsg::Send { .. }
+            | ExecuteMsg::TransferFrom { .. }
+            | ExecuteMsg::SendFrom { .. }
+            | ExecuteMsg::Mint { .. }
+            | ExecuteMsg::Burn { .. }
+            | ExecuteMsg::BurnFrom { .. }
+            | ExecuteMsg::IncreaseAllowance { .. }
+            | ExecuteMsg::DecreaseAllowance { .. }
+            | ExecuteMsg::Permit { .. }
+            | ExecuteMsg::RegisterMerkleRoot { .. }
+            | ExecuteMsg::Claim { .. }
+            | ExecuteMsg::ClawbackAirdrop { .. }
+            | ExecuteMsg::LockTokens { .. }
+            | ExecuteMsg::ClaimVested { .. }
+            | ExecuteMsg::ClaimReflection { .. }
+            | ExecuteMsg::Clawback { .. }
+    ) {
+        ensure_not_paused(deps.storage)?;
+    }
+    apply_pending_rates(deps.storage, &env)?;
+    apply_pending_tax_changes(deps.storage, &env)?;
+
     match msg {
-        QueryMsg::Balance { address } => to_json_binary(&query_balance(deps, address)?),
-        QueryMsg::TokenInfo {} => to_json_binary(&query_token_info(deps)?),
-        QueryMsg::Minter {} => to_json_binary(&query_minter(deps)?),
-        QueryMsg::Allowance { owner, spender } => {
-            to_json_binary(&query_allowance(deps, owner, spender)?)
+        ExecuteMsg::Transfer { recipient, amount } => {
+            execute_transfer(deps, env, info, recipient, amount)
         }
-        QueryMsg::AllAllowances {
-            owner,
-            start_after,
-            limit,
-        } => to_json_binary(&query_all_allowances(deps, owner, start_after, limit)?),
-        QueryMsg::AllAccounts { start_after, limit } => {
-            to_json_binary(&query_all_accounts(deps, start_after, limit)?)
+        ExecuteMsg::BatchTransfer { transfers } => {
+            execute_batch_transfer(deps, env, info, transfers)
         }
-        QueryMsg::MarketingInfo {} => to_json_binary(&query_marketing_info(deps)?),
-        QueryMsg::DownloadLogo {} => to_json_binary(&query_download_logo(deps)?),
-        QueryMsg::QueryTax { amount } => to_json_binary(&query_tax(deps.storage, amount)?),
-        QueryMsg::QueryRates {} => to_json_binary(&query_rate(deps.storage)?),
-        QueryMsg::GetWhitelist { address } => {
-            to_json_binary(&query_pairlist(deps.storage, address)?)
+        ExecuteMsg::Airdrop { recipients } => execute_airdrop(deps, env, info, recipients),
+        ExecuteMsg::RegisterMerkleRoot {
+            merkle_root,
+            total_amount,
+            expiration,
+        } => register_merkle_root(deps, env, info, merkle_root, total_amount, expiration),
+        ExecuteMsg::Claim {
+            stage,
+            index,
+            amount,
+            proof,
+        } => claim_merkle_airdrop(deps, env, info, stage, index, amount, proof),
+        ExecuteMsg::ClawbackAirdrop { stage, recipient } => {
+            clawback_merkle_stage(deps, env, info, stage, recipient)
+        }
+        ExecuteMsg::LockTokens {
+            beneficiary,
+            amount,
+            unlock_time,
+        } => lock_tokens(deps, env, info, beneficiary, amount, unlock_time),
+        ExecuteMsg::Delegate { delegate } => execute_delegate(deps, env, info, delegate),
+        ExecuteMsg::ClaimVested {} => claim_vested(deps, env, info),
+        ExecuteMsg::Burn { amount } => {
+            let sender = info.sender.clone();
+            compound_reflection(deps.storage, &env, &sender)?;
+            let res = execute_burn(deps.branch(), env.clone(), info, amount)?
+                .add_event(burn_event(&sender, amount));
+            check_locked_balance(deps.storage, &env, &sender)?;
+            checkpoint_total_supply(deps.storage, &env)?;
+            sync_reflection(deps.storage, &env, &sender)?;
+            Ok(res)
+        }
+        ExecuteMsg::Send {
+            contract,
+            amount,
+            msg,
+        } => execute_send(deps, env, info, contract, amount, msg),
+        ExecuteMsg::Mint { recipient, amount } => execute_mint(deps, env, info, recipient, amount),
+        ExecuteMsg::UpdateMinter { new_minter } => {
+            execute_update_minter(deps, env, info, new_minter)
+        }
+        ExecuteMsg::IncreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => execute_increase_allowance(deps, env, info, spender, amount, expires),
+        ExecuteMsg::SetAllowanceLimit { spender, limit } => {
+            set_allowance_limit(deps, info, spender, limit)
+        }
+        ExecuteMsg::SetPermitKey { pubkey } => set_permit_key(deps, info, pubkey),
+        ExecuteMsg::Permit {
+            owner,
+            spender,
+            amount,
+            expires,
+            nonce,
+            deadline,
+            signature,
+        } => execute_permit(
+            deps, env, owner, spender, amount, expires, nonce, deadline, signature,
+        ),
+        ExecuteMsg::DecreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => execute_decrease_allowance(deps, env, info, spender, amount, expires),
+        ExecuteMsg::TransferFrom {
+            owner,
+            recipient,
+            amount,
+        } => execute_transfer_from(deps, env, info, owner, recipient, amount),
+        ExecuteMsg::BurnFrom { owner, amount } => {
+            let owner_addr = deps.api.addr_validate(&owner)?;
+            compound_reflection(deps.storage, &env, &owner_addr)?;
+            check_allowance_limit(deps.storage, &env, &owner_addr, &info.sender, amount)?;
+            let res = execute_burn_from(deps.branch(), env.clone(), info, owner, amount)?
+                .add_event(burn_event(&owner_addr, amount));
+            check_locked_balance(deps.storage, &env, &owner_addr)?;
+            checkpoint_total_supply(deps.storage, &env)?;
+            sync_reflection(deps.storage, &env, &owner_addr)?;
+            Ok(res)
+        }
+        ExecuteMsg::SendFrom {
+            owner,
+            contract,
+            amount,
+            msg,
+        } => execute_send_from(deps, env, info, owner, contract, amount, msg),
+        ExecuteMsg::UpdateMarketing {
+            project,
+            description,
+            marketing,
+        } => execute_update_marketing(deps, env, info, project, description, marketing),
+        ExecuteMsg::UploadLogo(logo) => execute_upload_logo(deps, env, info, logo),
+
+        // Reflection features
+        ExecuteMsg::SetTreasury { contract } => set_treasury(deps, info, contract),
+        ExecuteMsg::SetPair {
+            contract,
+            enable,
+            tax_rate,
+        } => set_pairlist(deps, env, info, contract, enable, tax_rate),
+        ExecuteMsg::SetTaxExempt { address, exempt } => set_tax_exempt(deps, info, address, exempt),
+        ExecuteMsg::SetTaxRate {
+            global_rate,
+            reflection_rate,
+            burn_rate,
+        } => set_tax_rate(
+            deps,
+            env,
+            info,
+            global_rate,
+            reflection_rate,
+            burn_rate
+        ),
+        ExecuteMsg::SetTaxRates {
+            buy_rate,
+            sell_rate,
+            transfer_rate,
+        } => set_tax_rates(deps, env, info, buy_rate, sell_rate, transfer_rate),
+        ExecuteMsg::SetDynamicTax { config } => set_dynamic_tax(deps, env, info, config),
+        ExecuteMsg::SetTaxDecay { decay } => set_tax_decay(deps, env, info, decay),
+        ExecuteMsg::CancelPendingRates {} => cancel_pending_rates(deps, info),
+        ExecuteMsg::CancelPendingTaxChanges {} => cancel_pending_tax_changes(deps, info),
+        ExecuteMsg::SetTaxRateDelay { seconds } => set_tax_rate_delay(deps, info, seconds),
+        ExecuteMsg::EnableTrading {} => enable_trading(deps, env, info),
+        ExecuteMsg::SetLaunchProtection { protection } => {
+            set_launch_protection(deps, info, protection)
+        }
+        ExecuteMsg::ClearSniper { address } => clear_sniper(deps, info, address),
+        ExecuteMsg::SetMaxTransferRate { rate } => set_max_transfer_rate(deps, info, rate),
+        ExecuteMsg::Clawback { from, to, amount } => clawback(deps, env, info, from, to, amount),
+        ExecuteMsg::UpdateOwnership(action) => update_ownership(deps, env, info, action),
+        ExecuteMsg::Pause {} => set_paused(deps, info, true),
+        ExecuteMsg::Unpause {} => set_paused(deps, info, false),
+        ExecuteMsg::Freeze { address } => freeze(deps, info, address),
+        ExecuteMsg::Unfreeze { address } => unfreeze(deps, info, address),
+        ExecuteMsg::SetSandwichGuard { enable } => set_sandwich_guard(deps, info, enable),
+        ExecuteMsg::SetSandwichExempt { address, exempt } => {
+            set_sandwich_exempt(deps, info, address, exempt)
+        }
+        ExecuteMsg::SetSellLimit { limit } => set_sell_limit(deps, info, limit),
+        ExecuteMsg::SetTransferCooldown { seconds } => set_transfer_cooldown(deps, info, seconds),
+        ExecuteMsg::SetCooldownExempt { address, exempt } => {
+            set_cooldown_exempt(deps, info, address, exempt)
+        }
+        ExecuteMsg::SetBridge { address, enable } => set_bridge(deps, info, address, enable),
+        ExecuteMsg::SetBridgeExemptionEnabled { enabled } => {
+            set_bridge_exemption_enabled(deps, info, enabled)
+        }
+        ExecuteMsg::SetSendAllowlistEnabled { enabled } => {
+            set_send_allowlist_enabled(deps, info, enabled)
+        }
+        ExecuteMsg::SetSendAllowed { contract, allowed } => {
+            set_send_allowed(deps, info, contract, allowed)
+        }
+        ExecuteMsg::SetMaxWalletRate { rate } => set_max_wallet_rate(deps, info, rate),
+        ExecuteMsg::SetMaxWalletExempt { address, exempt } => {
+            set_max_wallet_exempt(deps, info, address, exempt)
+        }
+        ExecuteMsg::SetTaxTiers { tiers } => set_tax_tiers(deps, env, info, tiers),
+        ExecuteMsg::SetTaxStrategy { contract } => set_tax_strategy(deps, env, info, contract),
+        ExecuteMsg::SetPriceImpactTax { config } => set_price_impact_tax(deps, env, info, config),
+        ExecuteMsg::SetAntiWhaleTax {
+            surtax_multiplier,
+            max_surtax,
+        } => set_anti_whale_tax(deps, env, info, surtax_multiplier, max_surtax),
+        ExecuteMsg::SetBuyBack { enable } => set_buyback(deps, info, enable),
+        ExecuteMsg::SetLiquifyCooldown { seconds } => set_liquify_cooldown(deps, info, seconds),
+        ExecuteMsg::SetLiquifyThreshold { amount } => set_liquify_threshold(deps, info, amount),
+        ExecuteMsg::SetInstantBurn { enable } => set_instant_burn(deps, info, enable),
+        ExecuteMsg::SetStandardAttributes { enable } => set_standard_attributes(deps, info, enable),
+        ExecuteMsg::SetDustBucket { bucket } => set_dust_bucket(deps, info, bucket),
+        ExecuteMsg::SetMintTax { rate } => set_mint_tax(deps, env, info, rate),
+        ExecuteMsg::SetTaxMode { mode } => set_tax_mode(deps, env, info, mode),
+        ExecuteMsg::SetHolderReflectionRate { rate } => {
+            set_holder_reflection_rate(deps, env, info, rate)
+        }
+        ExecuteMsg::ClaimReflection {} => claim_reflection(deps, env, info),
+        ExecuteMsg::DepositNativeReflection {} => deposit_native_reflection(deps, env, info),
+        ExecuteMsg::ClaimNative {} => claim_native_reflection(deps, env, info),
+        ExecuteMsg::SetNativeReflectionDenom { denom } => {
+            set_native_reflection_denom(deps, info, denom)
+        }
+        ExecuteMsg::SetReflectionExclusion { address, excluded } => {
+            set_reflection_exclusion(deps, env, info, address, excluded)
+        }
+        ExecuteMsg::MigrateTreasury { code_id } => migrate_treasury(deps, env, info, code_id),
+        ExecuteMsg::ImportState {
+            balances,
+            allowances,
+            finish,
+        } => import_state(deps, env, info, balances, allowances, finish),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    let storage = with_due_tax_changes(deps.storage, &env)?;
+    let deps = Deps {
+        storage: &storage,
+        api: deps.api,
+        querier: deps.querier,
+    };
+
+    match msg {
+        QueryMsg::Balance { address } => {
+            to_json_binary(&query_rebased_balance(deps, &env, address)?)
+        }
+        QueryMsg::TokenInfo {} => to_json_binary(&query_token_info(deps)?),
+        QueryMsg::Minter {} => to_json_binary(&query_minter(deps)?),
+        QueryMsg::Allowance { owner, spender } => {
+            to_json_binary(&query_allowance(deps, owner, spender)?)
+        }
+        QueryMsg::AllAllowances {
+            owner,
+            start_after,
+            limit,
+        } => to_json_binary(&query_all_allowances(deps, owner, start_after, limit)?),
+        QueryMsg::AllAccounts { start_after, limit } => {
+            to_json_binary(&query_all_accounts(deps, start_after, limit)?)
+        }
+        QueryMsg::MarketingInfo {} => to_json_binary(&query_marketing_info(deps)?),
+        QueryMsg::DownloadLogo {} => to_json_binary(&query_download_logo(deps)?),
+        QueryMsg::QueryTax {
+            amount,
+            pair,
+            sender: None,
+            recipient: None,
+        } => to_json_binary(&query_sell_tax(deps, &env, amount, pair)?),
+        QueryMsg::QueryTax {
+            amount,
+            sender,
+            recipient,
+            ..
+        } => {
+            // an address that is never a pair, exempt nor a sniper
+            let wallet = |address: Option<String>| match address {
+                Some(address) => deps.api.addr_validate(&address),
+                None => Ok(Addr::unchecked("")),
+            };
+            to_json_binary(&query_transfer_tax(
+                deps,
+                &env,
+                &wallet(sender)?,
+                &wallet(recipient)?,
+                amount,
+            )?)
+        }
+        QueryMsg::TaxSplit { amount, pair } => {
+            to_json_binary(&query_tax_split(deps, &env, amount, pair)?)
+        }
+        QueryMsg::QueryRates {} => to_json_binary(&query_rate(deps.storage)?),
+        QueryMsg::QueryTaxRates {} => to_json_binary(&query_tax_rates(deps.storage)?),
+        QueryMsg::TaxStrategy {} => to_json_binary(&TAX_STRATEGY.may_load(deps.storage)?),
+        QueryMsg::PriceImpactTax {} => to_json_binary(&PRICE_IMPACT_TAX.may_load(deps.storage)?),
+        QueryMsg::AntiWhaleTax {} => {
+            to_json_binary(&ANTI_WHALE_TAX.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::Locks { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(&LocksResponse {
+                locked: locked_amount(deps.storage, &env, &address)?,
+                locks: LOCKS.may_load(deps.storage, &address)?.unwrap_or_default(),
+            })
+        }
+        QueryMsg::AllowanceLimit { owner, spender } => {
+            to_json_binary(&query_allowance_limit(deps, &env, owner, spender)?)
+        }
+        QueryMsg::PermitNonce { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(
+                &PERMIT_NONCES
+                    .may_load(deps.storage, &address)?
+                    .unwrap_or_default(),
+            )
+        }
+        QueryMsg::PermitKey { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(&PERMIT_KEYS.may_load(deps.storage, &address)?)
+        }
+        QueryMsg::Vesting { address } => to_json_binary(&query_vesting(deps, env, address)?),
+        QueryMsg::LatestMerkleStage {} => to_json_binary(
+            &LATEST_MERKLE_STAGE
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::MerkleStage { stage } => {
+            to_json_binary(&MERKLE_STAGES.load(deps.storage, stage)?)
+        }
+        QueryMsg::IsClaimed { stage, index } => {
+            let claimed = MERKLE_CLAIMED
+                .may_load(deps.storage, (stage, index / 64))?
+                .unwrap_or_default();
+            to_json_binary(&(claimed & (1u64 << (index % 64)) != 0))
+        }
+        QueryMsg::TradingEnabled {} => {
+            to_json_binary(&TRADING_ENABLED.may_load(deps.storage)?.unwrap_or(true))
+        }
+        QueryMsg::LaunchProtection {} => to_json_binary(&LAUNCH_PROTECTION.may_load(deps.storage)?),
+        QueryMsg::IsSniper { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(&SNIPERS.has(deps.storage, &address))
+        }
+        QueryMsg::Snipers { start_after, limit } => {
+            to_json_binary(&query_address_list(deps, &SNIPERS, start_after, limit)?)
+        }
+        QueryMsg::ClawbackEnabled {} => {
+            to_json_binary(&CLAWBACK_ENABLED.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::Ownership {} => to_json_binary(&cw_ownable::get_ownership(deps.storage)?),
+        QueryMsg::ContractVersion {} => to_json_binary(&query_contract_version(deps.storage)?),
+        QueryMsg::BalanceAt { address, height } => {
+            let address = deps.api.addr_validate(&address)?;
+            let balance = BALANCE_HISTORY
+                .may_load_at_height(deps.storage, &address, height)?
+                .unwrap_or_default();
+            to_json_binary(&BalanceResponse {
+                balance: rebased_balance(deps.storage, &env, &address, balance, Some(height))?,
+            })
+        }
+        QueryMsg::AccountTaxPaid { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(
+                &ACCOUNT_TAX_PAID
+                    .may_load(deps.storage, &address)?
+                    .unwrap_or_default(),
+            )
+        }
+        QueryMsg::TaxStats {} => {
+            to_json_binary(&TAX_STATS.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::HolderCount {} => {
+            to_json_binary(&HOLDER_COUNT.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::TotalSupplyAt { height } => to_json_binary(
+            &TOTAL_SUPPLY_HISTORY
+                .may_load_at_height(deps.storage, height)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::Delegation { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(&query_delegate(deps.storage, &address)?)
+        }
+        QueryMsg::VotingPowerAt { address, height } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(&VotingPowerResponse {
+                power: VOTING_POWER
+                    .may_load_at_height(deps.storage, &address, height)?
+                    .unwrap_or_default(),
+                height,
+            })
+        }
+        QueryMsg::Rebase {} => to_json_binary(&REBASE.may_load(deps.storage)?.unwrap_or_default()),
+        QueryMsg::TaxMode {} => to_json_binary(
+            &TAX_MODE
+                .may_load(deps.storage)?
+                .unwrap_or(TaxMode::Inclusive),
+        ),
+        QueryMsg::StandardAttributes {} => to_json_binary(
+            &STANDARD_ATTRIBUTES
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::LiquifyConfig {} => to_json_binary(&LiquifyConfigResponse {
+            buyback_enabled: BUYBACK_ENABLE.may_load(deps.storage)?.unwrap_or_default(),
+            cooldown: LIQUIFY_COOLDOWN.may_load(deps.storage)?.unwrap_or(1),
+            last_liquify: LAST_LIQUIFY.may_load(deps.storage)?.unwrap_or_default(),
+            threshold: LIQUIFY_THRESHOLD
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+            accumulated_tax: ACCUMULATED_TAX.may_load(deps.storage)?.unwrap_or_default(),
+        }),
+        QueryMsg::InstantBurn {} => {
+            to_json_binary(&INSTANT_BURN.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::Paused {} => to_json_binary(&PAUSED.may_load(deps.storage)?.unwrap_or_default()),
+        QueryMsg::IsFrozen { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(&FROZEN.has(deps.storage, &address))
+        }
+        QueryMsg::FrozenAccounts { start_after, limit } => {
+            to_json_binary(&query_address_list(deps, &FROZEN, start_after, limit)?)
+        }
+        QueryMsg::SandwichGuard {} => {
+            to_json_binary(&SANDWICH_GUARD.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::SandwichExemptions { start_after, limit } => to_json_binary(&query_address_list(
+            deps,
+            &SANDWICH_EXEMPT,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::SellLimit {} => to_json_binary(&SELL_LIMIT.may_load(deps.storage)?),
+        QueryMsg::SellAllowance { address } => {
+            to_json_binary(&query_sell_allowance(deps, env, address)?)
+        }
+        QueryMsg::TransferCooldown {} => to_json_binary(
+            &TRANSFER_COOLDOWN
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::CooldownExemptions { start_after, limit } => to_json_binary(&query_address_list(
+            deps,
+            &COOLDOWN_EXEMPT,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Bridges { start_after, limit } => {
+            to_json_binary(&query_address_list(deps, &BRIDGES, start_after, limit)?)
+        }
+        QueryMsg::BridgeExemptionEnabled {} => to_json_binary(
+            &BRIDGE_EXEMPTION_ENABLED
+                .may_load(deps.storage)?
+                .unwrap_or(true),
+        ),
+        QueryMsg::SendAllowlistEnabled {} => to_json_binary(
+            &SEND_ALLOWLIST_ENABLED
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::SendAllowlist { start_after, limit } => to_json_binary(&query_address_list(
+            deps,
+            &SEND_ALLOWED,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::MaxWalletRate {} => to_json_binary(&MAX_WALLET_RATE.may_load(deps.storage)?),
+        QueryMsg::MaxWalletExemptions { start_after, limit } => to_json_binary(
+            &query_address_list(deps, &MAX_WALLET_EXEMPT, start_after, limit)?,
+        ),
+        QueryMsg::DynamicTax {} => {
+            let config = DYNAMIC_TAX.may_load(deps.storage)?;
+            let volume = match &config {
+                Some(config) => load_sell_volume(deps.storage, &env, config.epoch_seconds)?,
+                None => SellVolume::default(),
+            };
+            to_json_binary(&DynamicTaxResponse {
+                current_rate: query_dynamic_rate(deps.storage, &env)?,
+                config,
+                volume,
+            })
+        }
+        QueryMsg::TaxDecay {} => to_json_binary(&TaxDecayResponse {
+            decay: TAX_DECAY.may_load(deps.storage)?,
+            current_rate: query_decay_rate(deps.storage, &env)?,
+        }),
+        QueryMsg::PendingRates {} => to_json_binary(&PENDING_RATES.may_load(deps.storage)?),
+        QueryMsg::PendingTaxChanges {} => to_json_binary(&PendingTaxChangesResponse {
+            changes: PENDING_TAX_CHANGES
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| item.map(|(_, pending)| pending))
+                .collect::<StdResult<_>>()?,
+        }),
+        QueryMsg::AuditLog { start_after, limit } => {
+            to_json_binary(&query_audit_log(deps, start_after, limit)?)
+        }
+        QueryMsg::RateHistory { start_after, limit } => {
+            to_json_binary(&query_rate_history(deps, start_after, limit)?)
+        }
+        QueryMsg::TaxRateDelay {} => {
+            to_json_binary(&TAX_RATE_DELAY.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::TaxTiers {} => to_json_binary(&TaxTiersResponse {
+            tiers: TAX_TIERS.may_load(deps.storage)?.unwrap_or_default(),
+        }),
+        QueryMsg::GetWhitelist { address } => {
+            to_json_binary(&query_pairlist(deps.storage, address)?)
+        }
+        QueryMsg::IsTaxExempt { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(&is_tax_exempt(deps.storage, &address)?)
+        }
+        QueryMsg::TaxExemptions { start_after, limit } => {
+            to_json_binary(&query_address_list(deps, &TAX_EXEMPT, start_after, limit)?)
+        }
+        QueryMsg::AddressStatus { address } => {
+            to_json_binary(&query_address_status(deps, address)?)
+        }
+        QueryMsg::GetPair { address } => to_json_binary(
+            &PAIRLIST
+                .may_load(deps.storage, address)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::GetTreasury {} => to_json_binary(&query_treasury(deps.storage)?),
+        QueryMsg::PredictTreasuryAddress { code_id } => {
+            to_json_binary(&query_predict_treasury_address(deps, env, code_id)?)
+        }
+        QueryMsg::PendingReflection { address } => {
+            to_json_binary(&query_pending_reflection(deps, address)?)
+        }
+        QueryMsg::ReflectionExclusions { start_after, limit } => to_json_binary(
+            &query_address_list(deps, &REFLECTION_EXCLUDED, start_after, limit)?,
+        ),
+        QueryMsg::ExportState { start_after, limit } => {
+            to_json_binary(&query_export_state(deps, &env, start_after, limit)?)
+        }
+        QueryMsg::SupplyCheck {
+            start_after,
+            running_sum,
+            limit,
+        } => to_json_binary(&query_supply_check(deps, start_after, running_sum, limit)?),
+    }
+}
+
+/// Used to calculate the amount of taxes to be paid, to be used in all transfer functions
+/// `surtax` is added on top of the base or tier rate, the total rate never exceeds `MAX_TOTAL_TAX`.
+/// The launch tax applies to `trade`s even when their base rate is zero
+pub fn query_tax(
+    storage: &dyn Storage,
+    env: &Env,
+    amount: Uint128,
+    tax_rate: Decimal,
+    surtax: Decimal,
+    trade: bool,
+) -> Result<QueryTaxResponse, StdError> {
+    let decays = trade || !tax_rate.is_zero();
+    let tax_rate = if tax_rate.is_zero() {
+        tax_rate
+    } else {
+        query_tier_rate(storage, amount)?.unwrap_or(tax_rate)
+    };
+    let tax_rate = match query_decay_rate(storage, env)? {
+        Some(decay_rate) if decays => std::cmp::max(tax_rate, decay_rate),
+        _ => tax_rate,
+    };
+    TokenTax::new(storage).tax(amount, tax_rate, surtax)
+}
+
+/// Whether a transfer from `owner` to `recipient` is a taxed buy or sell on a listed pair
+pub fn is_taxed_trade(storage: &dyn Storage, owner: &Addr, recipient: &Addr) -> StdResult<bool> {
+    if is_tax_exempt(storage, owner)? || is_tax_exempt(storage, recipient)? {
+        return Ok(false);
+    }
+    Ok(load_pair(storage, owner.as_str())?.is_some()
+        || load_pair(storage, recipient.as_str())?.is_some())
+}
+
+/// Returns the tax breakdown of selling `amount`, using the override of `pair` if it has one
+pub fn query_sell_tax(
+    deps: Deps,
+    env: &Env,
+    amount: Uint128,
+    pair: Option<String>,
+) -> StdResult<QueryTaxResponse> {
+    let pair_addr = pair
+        .as_ref()
+        .map(|pair| deps.api.addr_validate(pair))
+        .transpose()?;
+    let pair = match pair {
+        Some(pair) => load_pair(deps.storage, &pair)?,
+        None => None,
+    };
+    // a pair override takes precedence over the dynamic tax, which replaces the sell rate
+    let sell_rate = SELL_TAX_RATE.may_load(deps.storage)?;
+    let tax_rate = pair
+        .and_then(|pair| pair.tax_rate)
+        .or(query_dynamic_rate(deps.storage, env)?)
+        .or(sell_rate);
+    let mut surtax = query_sell_surtax(deps.storage, amount)?;
+    if let Some(pair) = pair_addr {
+        surtax += query_price_impact_surtax(deps, env, &pair, amount)?;
+    }
+    query_tax(
+        deps.storage,
+        env,
+        amount,
+        tax_rate.unwrap_or_default(),
+        surtax,
+        true,
+    )
+}
+
+/// Rates a transfer is charged, see `query_transfer_rates`
+pub struct TransferRates {
+    pub tax_rate: Decimal,
+    pub surtax: Decimal,
+    /// Error of the tax strategy, when its rate fell back to the base rate
+    pub strategy_error: Option<String>,
+}
+
+/// Returns the tax rate and surtax a transfer of `amount` from `owner` to `recipient` is charged,
+/// the sniper penalty excluded
+pub fn query_transfer_rates(
+    deps: Deps,
+    env: &Env,
+    owner: &Addr,
+    recipient: &Addr,
+    amount: Uint128,
+) -> StdResult<TransferRates> {
+    let tax_rate = query_transfer_rate(deps.storage, owner, recipient)?;
+    let exempt = is_tax_exempt(deps.storage, owner)? || is_tax_exempt(deps.storage, recipient)?;
+    if exempt {
+        return Ok(TransferRates {
+            tax_rate,
+            surtax: Decimal::zero(),
+            strategy_error: None,
+        });
+    }
+
+    let (tax_rate, surtax) = if let Some(pair) = load_pair(deps.storage, recipient.as_str())? {
+        // a pair override takes precedence over the dynamic tax, which replaces the sell rate
+        let tax_rate = match pair.tax_rate {
+            Some(_) => tax_rate,
+            None => query_dynamic_rate(deps.storage, env)?.unwrap_or(tax_rate),
+        };
+        let surtax = query_sell_surtax(deps.storage, amount)?
+            + query_price_impact_surtax(deps, env, recipient, amount)?;
+        (tax_rate, surtax)
+    } else {
+        (tax_rate, Decimal::zero())
+    };
+    let (tax_rate, strategy_error) = query_strategy_rate(deps, owner, recipient, amount, tax_rate)?;
+    Ok(TransferRates {
+        tax_rate,
+        surtax,
+        strategy_error,
+    })
+}
+
+/// Returns the tax breakdown of a transfer of `amount` from `sender` to `recipient`
+pub fn query_transfer_tax(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    recipient: &Addr,
+    amount: Uint128,
+) -> StdResult<QueryTaxResponse> {
+    let rates = query_transfer_rates(deps, env, sender, recipient, amount)?;
+    let surtax = match query_sniper_penalty(deps.storage, sender)? {
+        Some(SniperPenalty::Tax { rate }) => rates.surtax + rate,
+        _ => rates.surtax,
+    };
+    let trade = is_taxed_trade(deps.storage, sender, recipient)?;
+    query_tax(deps.storage, env, amount, rates.tax_rate, surtax, trade)
+}
+
+/// Returns the split of the sell tax of `amount`, failing if the shares do not sum to the taxed amount
+pub fn query_tax_split(
+    deps: Deps,
+    env: &Env,
+    amount: Uint128,
+    pair: Option<String>,
+) -> StdResult<TaxSplitResponse> {
+    let taxes = query_sell_tax(deps, env, amount, pair)?;
+    let total = taxes.reflection_amount
+        + taxes.burn_amount
+        + taxes.liquidity_amount
+        + taxes.holder_reflection_amount;
+    if total != taxes.taxed_amount {
+        return Err(StdError::generic_err(format!(
+            "Tax shares sum to {} instead of {}",
+            total, taxes.taxed_amount
+        )));
+    }
+
+    Ok(TaxSplitResponse {
+        taxed_amount: taxes.taxed_amount,
+        reflection_amount: taxes.reflection_amount,
+        burn_amount: taxes.burn_amount,
+        liquidity_amount: taxes.liquidity_amount,
+        holder_reflection_amount: taxes.holder_reflection_amount,
+        dust_bucket: DUST_BUCKET
+            .may_load(deps.storage)?
+            .unwrap_or(TaxBucket::Liquidity),
+    })
+}
+
+/// Returns the sell volume of the current and previous epochs, rolled over to the current time
+pub fn load_sell_volume(
+    storage: &dyn Storage,
+    env: &Env,
+    epoch_seconds: u64,
+) -> StdResult<SellVolume> {
+    let epoch = env.block.time.seconds() / epoch_seconds;
+    let volume = SELL_VOLUME.may_load(storage)?.unwrap_or_default();
+
+    Ok(if volume.epoch == epoch {
+        volume
+    } else if volume.epoch + 1 == epoch {
+        SellVolume {
+            epoch,
+            volume: Uint128::zero(),
+            previous_volume: volume.volume,
+        }
+    } else {
+        SellVolume {
+            epoch,
+            ..SellVolume::default()
+        }
+    })
+}
+
+/// Adds `amount` to the sell volume of the current epoch
+pub fn record_sell_volume(storage: &mut dyn Storage, env: &Env, amount: Uint128) -> StdResult<()> {
+    let config = match DYNAMIC_TAX.may_load(storage)? {
+        Some(config) => config,
+        None => return Ok(()),
+    };
+
+    let mut volume = load_sell_volume(storage, env, config.epoch_seconds)?;
+    volume.volume += amount;
+    SELL_VOLUME.save(storage, &volume)
+}
+
+/// Returns the dynamic sell tax, `None` if disabled
+pub fn query_dynamic_rate(storage: &dyn Storage, env: &Env) -> StdResult<Option<Decimal>> {
+    let config = match DYNAMIC_TAX.may_load(storage)? {
+        Some(config) => config,
+        None => return Ok(None),
+    };
+
+    let volume = load_sell_volume(storage, env, config.epoch_seconds)?;
+    Ok(Some(rates::dynamic_rate(&config, &volume)))
+}
+
+/// Returns the launch tax at the current time, `None` once it has fully decayed
+pub fn query_decay_rate(storage: &dyn Storage, env: &Env) -> StdResult<Option<Decimal>> {
+    Ok(TAX_DECAY
+        .may_load(storage)?
+        .and_then(|decay| rates::decay_rate(&decay, env.block.time.seconds())))
+}
+
+/// Returns the rate of the largest tax tier reached by `amount`, relative to the total supply
+pub fn query_tier_rate(storage: &dyn Storage, amount: Uint128) -> StdResult<Option<Decimal>> {
+    let tiers = TAX_TIERS.may_load(storage)?.unwrap_or_default();
+    if tiers.is_empty() {
+        return Ok(None);
+    }
+
+    let total_supply = TOKEN_INFO.load(storage)?.total_supply;
+    Ok(rates::tier_rate(&tiers, amount, total_supply))
+}
+
+/// Rejects transfers before trading is enabled, unless the sender is the admin or tax exempt
+pub fn check_trading_enabled(storage: &dyn Storage, from: &Addr) -> StdResult<()> {
+    // contracts instantiated before the launch switch existed are already trading
+    if TRADING_ENABLED.may_load(storage)?.unwrap_or(true) {
+        return Ok(());
+    }
+
+    if cw_ownable::is_owner(storage, from)? || TAX_EXEMPT.has(storage, from) {
+        return Ok(());
+    }
+
+    Err(StdError::generic_err("Trading is not enabled yet"))
+}
+
+/// Returns the penalty applied to `address` if it is flagged as a sniper
+pub fn query_sniper_penalty(
+    storage: &dyn Storage,
+    address: &Addr,
+) -> StdResult<Option<SniperPenalty>> {
+    if !SNIPERS.has(storage, address) {
+        return Ok(None);
+    }
+    Ok(LAUNCH_PROTECTION
+        .may_load(storage)?
+        .map(|protection| protection.penalty))
+}
+
+/// Flags `to` as a sniper if it buys from a pair within the protected blocks after trading is enabled
+/// The admin and tax exempt addresses are never flagged
+pub fn flag_sniper(storage: &mut dyn Storage, env: &Env, from: &Addr, to: &Addr) -> StdResult<()> {
+    let protection = match LAUNCH_PROTECTION.may_load(storage)? {
+        Some(protection) => protection,
+        None => return Ok(()),
+    };
+    let enabled_at = match TRADING_ENABLED_AT.may_load(storage)? {
+        Some(enabled_at) => enabled_at,
+        None => return Ok(()),
+    };
+    if env.block.height >= enabled_at + protection.blocks {
+        return Ok(());
+    }
+
+    if cw_ownable::is_owner(storage, to)?
+        || TAX_EXEMPT.has(storage, to)
+        || load_pair(storage, to.as_str())?.is_some()
+    {
+        return Ok(());
+    }
+    if load_pair(storage, from.as_str())?.is_some() {
+        SNIPERS.save(storage, to, &true)?;
+    }
+
+    Ok(())
+}
+
+/// Rejects transfers from `from` within the cooldown of its last transfer, and records this one
+/// Pairs and exempt addresses have no cooldown
+pub fn check_cooldown(storage: &mut dyn Storage, env: &Env, from: &Addr) -> StdResult<()> {
+    let cooldown = TRANSFER_COOLDOWN.may_load(storage)?.unwrap_or_default();
+    if cooldown == 0
+        || COOLDOWN_EXEMPT.has(storage, from)
+        || load_pair(storage, from.as_str())?.is_some()
+    {
+        return Ok(());
+    }
+
+    let now = env.block.time.seconds();
+    if let Some(last_transfer) = LAST_TRANSFER.may_load(storage, from)? {
+        if now < last_transfer + cooldown {
+            return Err(StdError::generic_err(format!(
+                "Transfer cooldown active, try again in {} seconds",
+                last_transfer + cooldown - now
+            )));
+        }
+    }
+    LAST_TRANSFER.save(storage, from, &now)?;
+
+    Ok(())
+}
+
+/// Returns the sell window of `address` at the current time, a new window if the last one expired
+pub fn load_sell_window(storage: &dyn Storage, env: &Env, address: &Addr) -> StdResult<SellWindow> {
+    let now = env.block.time.seconds();
+    match SELL_WINDOWS.may_load(storage, address)? {
+        Some(window) if now < window.start + SELL_WINDOW_SECONDS => Ok(window),
+        _ => Ok(SellWindow {
+            start: now,
+            balance: BALANCES.may_load(storage, address)?.unwrap_or_default(),
+            sold: Uint128::zero(),
+        }),
+    }
+}
+
+/// Returns the amount `window` can still sell under the sell limit
+pub fn sell_limit_remaining(limit: &SellLimit, window: &SellWindow) -> Uint128 {
+    let max_amount = match limit {
+        SellLimit::Amount { amount } => *amount,
+        SellLimit::BalanceRate { rate } => window.balance.mul(*rate),
+    };
+    max_amount.saturating_sub(window.sold)
+}
+
+/// Rejects sells into a pair beyond the 24 hour sell limit of `from`, and records this one
+/// Tax exempt addresses have no sell limit
+pub fn check_sell_limit(
+    storage: &mut dyn Storage,
+    env: &Env,
+    from: &Addr,
+    to: &Addr,
+    amount: Uint128,
+) -> StdResult<()> {
+    let limit = match SELL_LIMIT.may_load(storage)? {
+        Some(limit) => limit,
+        None => return Ok(()),
+    };
+    if TAX_EXEMPT.has(storage, from) || load_pair(storage, to.as_str())?.is_none() {
+        return Ok(());
+    }
+
+    let mut window = load_sell_window(storage, env, from)?;
+    let remaining = sell_limit_remaining(&limit, &window);
+    if amount > remaining {
+        return Err(StdError::generic_err(format!(
+            "Sell exceeds the daily sell limit, {} left until {}",
+            remaining,
+            window.start + SELL_WINDOW_SECONDS
+        )));
+    }
+    window.sold += amount;
+    SELL_WINDOWS.save(storage, from, &window)?;
+
+    Ok(())
+}
+
+/// Returns the current spending period of `spender` over the tokens of `owner`, starting a new one if
+/// the previous one ended
+pub fn load_allowance_window(
+    storage: &dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    spender: &Addr,
+    limit: &AllowanceLimit,
+) -> StdResult<AllowanceWindow> {
+    let now = env.block.time.seconds();
+    match ALLOWANCE_WINDOWS.may_load(storage, (owner, spender))? {
+        Some(window) if now < window.start + limit.period => Ok(window),
+        _ => Ok(AllowanceWindow {
+            start: now,
+            spent: Uint128::zero(),
+        }),
+    }
+}
+
+/// Rejects spending more than the period cap the owner set on the allowance of `spender`, and records the spend.
+/// Must be called next to every allowance deduction
+pub fn check_allowance_limit(
+    storage: &mut dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    spender: &Addr,
+    amount: Uint128,
+) -> StdResult<()> {
+    let limit = match ALLOWANCE_LIMITS.may_load(storage, (owner, spender))? {
+        Some(limit) => limit,
+        None => return Ok(()),
+    };
+
+    let mut window = load_allowance_window(storage, env, owner, spender, &limit)?;
+    let remaining = limit.amount.saturating_sub(window.spent);
+    if amount > remaining {
+        return Err(StdError::generic_err(format!(
+            "Spend exceeds the allowance limit, {} left until {}",
+            remaining,
+            window.start + limit.period
+        )));
+    }
+    window.spent += amount;
+    ALLOWANCE_WINDOWS.save(storage, (owner, spender), &window)
+}
+
+/// Rejects a sell into a pair by an address that bought from a pair in the same block, and records buys
+pub fn check_same_block_trade(
+    storage: &mut dyn Storage,
+    env: &Env,
+    from: &Addr,
+    to: &Addr,
+) -> StdResult<()> {
+    if !SANDWICH_GUARD.may_load(storage)?.unwrap_or_default() {
+        return Ok(());
+    }
+
+    let height = env.block.height;
+    if load_pair(storage, to.as_str())?.is_some() && !SANDWICH_EXEMPT.has(storage, from) {
+        if LAST_BUY_HEIGHT.may_load(storage, from)? == Some(height) {
+            return Err(StdError::generic_err(
+                "Cannot sell in the same block as buying",
+            ));
+        }
+    } else if load_pair(storage, from.as_str())?.is_some() && !SANDWICH_EXEMPT.has(storage, to) {
+        LAST_BUY_HEIGHT.save(storage, to, &height)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the base tax rate computed by the tax strategy contract, or `base_rate` if none is set
+/// Falls back to `base_rate` if the strategy query fails, so a broken strategy cannot block transfers.
+/// The error is returned with the fallback rate so it can be reported
+pub fn query_strategy_rate(
+    deps: Deps,
+    from: &Addr,
+    to: &Addr,
+    amount: Uint128,
+    base_rate: Decimal,
+) -> StdResult<(Decimal, Option<String>)> {
+    let strategy = match TAX_STRATEGY.may_load(deps.storage)? {
+        Some(strategy) => strategy,
+        None => return Ok((base_rate, None)),
+    };
+
+    let response: StdResult<TaxStrategyResponse> = deps.querier.query_wasm_smart(
+        strategy,
+        &TaxStrategyQueryMsg::TaxRate {
+            from: from.to_string(),
+            to: to.to_string(),
+            amount,
+            base_rate,
+        },
+    );
+    Ok(match response {
+        Ok(response) => (response.tax_rate, None),
+        Err(err) => (base_rate, Some(err.to_string())),
+    })
+}
+
+/// Returns the price impact surtax of selling `amount` into `pair`
+/// The token reserve is the one the pair prices swaps with, queried from its pool
+pub fn query_price_impact_surtax(
+    deps: Deps,
+    env: &Env,
+    pair: &Addr,
+    amount: Uint128,
+) -> StdResult<Decimal> {
+    let config = match PRICE_IMPACT_TAX.may_load(deps.storage)? {
+        Some(config) => config,
+        None => return Ok(Decimal::zero()),
+    };
+    if load_pair(deps.storage, pair.as_str())?.is_none() {
+        return Ok(Decimal::zero());
+    }
+
+    let reserve = query_pair_reserve(deps, env, pair)?;
+    Ok(rates::price_impact_surtax(&config, reserve, amount))
+}
+
+/// Returns the reserve of this token in the pool of `pair`
+pub fn query_pair_reserve(deps: Deps, env: &Env, pair: &Addr) -> StdResult<Uint128> {
+    let pool: PoolResponse = deps
+        .querier
+        .query_wasm_smart(pair, &PairQueryMsg::Pool {})?;
+    let token = AssetInfo::Token {
+        contract_addr: env.contract.address.to_string(),
+    };
+    pool.assets
+        .iter()
+        .find(|asset| asset.info.equal(&token))
+        .map(|asset| asset.amount)
+        .ok_or_else(|| StdError::generic_err(format!("Pair {} does not trade this token", pair)))
+}
+
+/// Returns the anti-whale surtax of selling `amount` into a pair
+pub fn query_sell_surtax(storage: &dyn Storage, amount: Uint128) -> StdResult<Decimal> {
+    let config = ANTI_WHALE_TAX.may_load(storage)?.unwrap_or_default();
+    if config.max_surtax.is_zero() {
+        return Ok(Decimal::zero());
+    }
+
+    let total_supply = TOKEN_INFO.load(storage)?.total_supply;
+    Ok(rates::sell_surtax(&config, amount, total_supply))
+}
+
+/// Returns the tax rate applied to a transfer between `from` and `to`
+/// Buying from a pair uses the buy rate, selling into a pair uses the sell rate, anything else uses the transfer rate
+/// Transfers from or to a tax exempt address are never taxed
+pub fn query_transfer_rate(storage: &dyn Storage, from: &Addr, to: &Addr) -> StdResult<Decimal> {
+    TokenTax::new(storage).transfer_rate(from, to)
+}
+
+/// Tax exempt addresses, and recognized bridges unless the bridge exemption is disabled
+pub fn is_tax_exempt(storage: &dyn Storage, address: &Addr) -> StdResult<bool> {
+    if TAX_EXEMPT.has(storage, address) {
+        return Ok(true);
+    }
+
+    Ok(
+        BRIDGES.has(storage, address)
+            && BRIDGE_EXEMPTION_ENABLED.may_load(storage)?.unwrap_or(true),
+    )
+}
+
+/// The tax engine over the token storage
+pub struct TokenTax<'a> {
+    storage: &'a dyn Storage,
+}
+
+impl<'a> TokenTax<'a> {
+    pub fn new(storage: &'a dyn Storage) -> Self {
+        TokenTax { storage }
+    }
+}
+
+impl TaxEngine for TokenTax<'_> {
+    fn pair(&self, address: &Addr) -> StdResult<Option<PairConfig>> {
+        load_pair(self.storage, address.as_str())
+    }
+
+    fn is_tax_exempt(&self, address: &Addr) -> StdResult<bool> {
+        is_tax_exempt(self.storage, address)
+    }
+
+    fn buy_tax_rate(&self) -> StdResult<Decimal> {
+        Ok(BUY_TAX_RATE.may_load(self.storage)?.unwrap_or_default())
+    }
+
+    fn sell_tax_rate(&self) -> StdResult<Decimal> {
+        Ok(SELL_TAX_RATE.may_load(self.storage)?.unwrap_or_default())
+    }
+
+    fn transfer_tax_rate(&self) -> StdResult<Decimal> {
+        Ok(TRANSFER_TAX_RATE
+            .may_load(self.storage)?
+            .unwrap_or_default())
+    }
+
+    fn tax_shares(&self) -> StdResult<TaxShares> {
+        Ok(TaxShares {
+            reflection_rate: REFLECTION_RATE.load(self.storage)?,
+            burn_rate: BURN_RATE.load(self.storage)?,
+            holder_reflection_rate: HOLDER_REFLECTION_RATE
+                .may_load(self.storage)?
+                .unwrap_or_default(),
+            dust_bucket: DUST_BUCKET
+                .may_load(self.storage)?
+                .unwrap_or(TaxBucket::Liquidity),
+        })
+    }
+
+    fn total_supply(&self) -> StdResult<Uint128> {
+        Ok(TOKEN_INFO.load(self.storage)?.total_supply)
+    }
+
+    fn max_total_tax(&self) -> StdResult<Decimal> {
+        Ok(MAX_TOTAL_TAX
+            .may_load(self.storage)?
+            .unwrap_or_else(Decimal::one))
+    }
+
+    fn max_transfer_rate(&self) -> StdResult<Decimal> {
+        Ok(MAX_TRANSFER_SUPPLY_RATE
+            .may_load(self.storage)?
+            .unwrap_or_else(Decimal::one))
+    }
+
+    fn max_wallet_rate(&self) -> StdResult<Option<Decimal>> {
+        MAX_WALLET_RATE.may_load(self.storage)
+    }
+
+    /// The treasury is not limited either
+    fn is_transfer_limit_exempt(&self, address: &Addr) -> StdResult<bool> {
+        let treasury = TREASURY.may_load(self.storage)?.unwrap_or_default();
+        Ok(is_tax_exempt(self.storage, address)? || *address == treasury)
+    }
+
+    fn is_max_wallet_exempt(&self, address: &Addr) -> StdResult<bool> {
+        Ok(MAX_WALLET_EXEMPT.has(self.storage, address))
+    }
+}
+
+/// Loads the config of `address` if it is an enabled pair
+pub fn load_pair(storage: &dyn Storage, address: &str) -> StdResult<Option<PairConfig>> {
+    let pair = PAIRLIST.may_load(storage, address.to_string())?;
+    Ok(pair.filter(|pair| pair.enabled))
+}
+
+/// Returns the current tax rates
+/// The tax rate is the sell rate, which is the rate charged when tokens are sold into a pair.
+/// The burn rate is zero with instant burn, as the burn share never reaches the treasury
+pub fn query_rate(storage: &dyn Storage) -> StdResult<RatesResponse> {
+    let tax_rate = SELL_TAX_RATE.may_load(storage)?.unwrap_or_default();
+    let reflection_rate = REFLECTION_RATE.may_load(storage)?.unwrap();
+    let burn_rate = if INSTANT_BURN.may_load(storage)?.unwrap_or_default() {
+        Decimal::zero()
+    } else {
+        BURN_RATE.may_load(storage)?.unwrap()
+    };
+    let max_transfer_rate = MAX_TRANSFER_SUPPLY_RATE
+        .may_load(storage)?
+        .unwrap_or_else(Decimal::one);
+
+    Ok(RatesResponse {
+        tax_rate,
+        reflection_rate,
+        burn_rate,
+        max_transfer_rate,
+    })
+}
+
+/// Returns the buy, sell, and wallet-to-wallet tax rates
+pub fn query_tax_rates(storage: &dyn Storage) -> StdResult<TaxRatesResponse> {
+    Ok(TaxRatesResponse {
+        buy_rate: BUY_TAX_RATE.may_load(storage)?.unwrap_or_default(),
+        sell_rate: SELL_TAX_RATE.may_load(storage)?.unwrap_or_default(),
+        transfer_rate: TRANSFER_TAX_RATE.may_load(storage)?.unwrap_or_default(),
+        max_total_tax: MAX_TOTAL_TAX
+            .may_load(storage)?
+            .unwrap_or_else(Decimal::one),
+        mint_tax_rate: MINT_TAX_RATE.may_load(storage)?.unwrap_or_default(),
+    })
+}
+
+/// Returns the reflections accrued by `address` that have not been claimed yet, without settling them,
+/// and its lifetime reflections
+pub fn query_pending_reflection(
+    deps: Deps,
+    address: String,
+) -> StdResult<PendingReflectionResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let holder = HOLDER_REFLECTIONS
+        .may_load(deps.storage, &address)?
+        .unwrap_or_default();
+    let per_share = REFLECTION_PER_SHARE
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+    let native_per_share = NATIVE_REFLECTION_PER_SHARE
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+
+    let accrued = accrued_reflection(holder.shares, per_share, holder.debt)?;
+    let native_accrued = accrued_reflection(holder.shares, native_per_share, holder.native_debt)?;
+    let pending = holder.pending.checked_add(accrued)?;
+    let native_pending = holder.native_pending.checked_add(native_accrued)?;
+
+    Ok(PendingReflectionResponse {
+        pending,
+        claimed: holder.claimed,
+        native_pending,
+        native_claimed: holder.native_claimed,
+        earned: holder.claimed.checked_add(pending)?,
+        native_earned: holder.native_claimed.checked_add(native_pending)?,
+        native_denom: NATIVE_REFLECTION_DENOM.may_load(deps.storage)?,
+        shares: holder.shares,
+        total_shares: REFLECTION_TOTAL_SHARES
+            .may_load(deps.storage)?
+            .unwrap_or_default(),
+    })
+}
+
+/// Lists the addresses stored in one of the address maps (e.g. reflection exclusions). Supports pagination
+pub fn query_address_list(
+    deps: Deps,
+    map: &Map<&Addr, bool>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AddressListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into_bytes()));
+
+    let addresses = map
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(Into::into))
+        .collect::<StdResult<_>>()?;
+
+    Ok(AddressListResponse { addresses })
+}
+
+/// Returns the flags of `address` in every access list
+pub fn query_address_status(deps: Deps, address: String) -> StdResult<AddressStatusResponse> {
+    let address = deps.api.addr_validate(&address)?;
+
+    Ok(AddressStatusResponse {
+        pair: load_pair(deps.storage, address.as_str())?.is_some(),
+        tax_exempt: is_tax_exempt(deps.storage, &address)?,
+        bridge: BRIDGES.has(deps.storage, &address),
+        max_wallet_exempt: MAX_WALLET_EXEMPT.has(deps.storage, &address),
+        cooldown_exempt: COOLDOWN_EXEMPT.has(deps.storage, &address),
+        sandwich_exempt: SANDWICH_EXEMPT.has(deps.storage, &address),
+        send_allowed: SEND_ALLOWED.has(deps.storage, &address),
+        sniper: SNIPERS.has(deps.storage, &address),
+        frozen: FROZEN.has(deps.storage, &address),
+        reflection_excluded: REFLECTION_EXCLUDED.has(deps.storage, &address),
+    })
+}
+
+/// Returns a page of balances, rebased in rebase mode, with the allowances their owners granted, and
+/// the config on the first page
+pub fn query_export_state(
+    deps: Deps,
+    env: &Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ExportStateResponse> {
+    let config = match start_after {
+        Some(_) => None,
+        None => Some(query_exported_config(deps)?),
+    };
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into_bytes()));
+
+    let balances: Vec<Cw20Coin> = BALANCES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (address, amount) = item?;
+            Ok(Cw20Coin {
+                amount: rebased_balance(deps.storage, env, &address, amount, None)?,
+                address: address.into(),
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    let mut allowances = vec![];
+    for row in balances.iter() {
+        let owner = Addr::unchecked(&row.address);
+        for item in ALLOWANCES
+            .prefix(&owner)
+            .range(deps.storage, None, None, Order::Ascending)
+        {
+            let (spender, allowance) = item?;
+            allowances.push(ExportedAllowance {
+                owner: row.address.clone(),
+                spender: spender.into(),
+                allowance: allowance.allowance,
+                expires: allowance.expires,
+            });
+        }
+    }
+
+    Ok(ExportStateResponse {
+        config,
+        balances,
+        allowances,
+    })
+}
+
+/// Adds a page of balances to `running_sum`, and compares the sum with the total supply once the
+/// last balance is summed
+pub fn query_supply_check(
+    deps: Deps,
+    start_after: Option<String>,
+    running_sum: Option<Uint128>,
+    limit: Option<u32>,
+) -> StdResult<SupplyCheckResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into_bytes()));
+
+    // one more balance is loaded to know whether this is the last page
+    let mut balances: Vec<(Addr, Uint128)> = BALANCES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .collect::<StdResult<_>>()?;
+    let last_page = balances.len() <= limit;
+    balances.truncate(limit);
+
+    let mut running_sum = running_sum.unwrap_or_default();
+    for (_, amount) in balances.iter() {
+        running_sum = running_sum.checked_add(*amount)?;
+    }
+    let total_supply = TOKEN_INFO.load(deps.storage)?.total_supply;
+
+    let discrepancy = if !last_page || running_sum == total_supply {
+        None
+    } else if running_sum > total_supply {
+        Some(SupplyDiscrepancy::Excess {
+            amount: running_sum - total_supply,
+        })
+    } else {
+        Some(SupplyDiscrepancy::Shortfall {
+            amount: total_supply - running_sum,
+        })
+    };
+
+    Ok(SupplyCheckResponse {
+        running_sum,
+        last_address: match last_page {
+            true => None,
+            false => balances.last().map(|(address, _)| address.to_string()),
+        },
+        total_supply,
+        discrepancy,
+    })
+}
+
+fn query_exported_config(deps: Deps) -> StdResult<ExportedConfig> {
+    let token_info = TOKEN_INFO.load(deps.storage)?;
+    Ok(ExportedConfig {
+        name: token_info.name,
+        symbol: token_info.symbol,
+        decimals: token_info.decimals,
+        total_supply: token_info.total_supply,
+        minter: query_minter(deps)?,
+        marketing: MARKETING_INFO.may_load(deps.storage)?,
+        owner: cw_ownable::get_ownership(deps.storage)?.owner,
+        treasury: TREASURY.may_load(deps.storage)?,
+        buy_tax_rate: BUY_TAX_RATE.may_load(deps.storage)?.unwrap_or_default(),
+        sell_tax_rate: SELL_TAX_RATE.may_load(deps.storage)?.unwrap_or_default(),
+        transfer_tax_rate: TRANSFER_TAX_RATE
+            .may_load(deps.storage)?
+            .unwrap_or_default(),
+        reflection_rate: REFLECTION_RATE.may_load(deps.storage)?.unwrap_or_default(),
+        burn_rate: BURN_RATE.may_load(deps.storage)?.unwrap_or_default(),
+        holder_reflection_rate: HOLDER_REFLECTION_RATE
+            .may_load(deps.storage)?
+            .unwrap_or_default(),
+        trading_enabled: TRADING_ENABLED.may_load(deps.storage)?.unwrap_or(true),
+    })
+}
+
+/// Returns the treasury address that receives taxes, empty if not set yet
+pub fn query_treasury(storage: &dyn Storage) -> StdResult<TreasuryResponse> {
+    Ok(TreasuryResponse {
+        treasury: TREASURY.may_load(storage)?.unwrap_or_default(),
+    })
+}
+
+/// Salt of the treasury instantiated with `Instantiate2`, the canonical token address
+pub fn treasury_salt(api: &dyn Api, token: &Addr) -> StdResult<Binary> {
+    Ok(Binary::from(
+        api.addr_canonicalize(token.as_str())?.as_slice(),
+    ))
+}
+
+/// Returns the address `Instantiate2` gives a treasury of `code_id` instantiated by the token
+pub fn query_predict_treasury_address(
+    deps: Deps,
+    env: Env,
+    code_id: u64,
+) -> StdResult<TreasuryResponse> {
+    let checksum = deps.querier.query_wasm_code_info(code_id)?.checksum;
+    let creator = deps.api.addr_canonicalize(env.contract.address.as_str())?;
+    let salt = treasury_salt(deps.api, &env.contract.address)?;
+    let address = instantiate2_address(checksum.as_slice(), &creator, salt.as_slice())
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    Ok(TreasuryResponse {
+        treasury: deps.api.addr_humanize(&address)?.to_string(),
+    })
+}
+
+pub fn query_pairlist(storage: &dyn Storage, address: String) -> Result<bool, StdError> {
+    let pairlist = PAIRLIST.may_load(storage, address)?.unwrap();
+
+    Ok(pairlist.enabled)
+}
+
+/// Global rate is number between 0 to 1. 0.1 refers to 10% taxes on all buys and sells (see `set_tax_rates` for separate rates)
+/// Reflection rate is number between 0 to 1. 0.5 refers to 50% of GLOBAL taxes gets transferred as reflection
+/// Burn rate is number between 0 to 1. 0.1 refers to 10% of GLOBAL taxes gets burnt
+/// Antiwhale rate is number between 0 to 1. 0.02 refers to when someone intends to move 2% of supply, anti-whale gets triggered
+pub fn set_tax_rate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    global_rate: Decimal,
+    reflection_rate: Decimal,
+    burn_rate: Decimal,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    if global_rate > Decimal::one() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "global_rate must be <= 1",
+        )));
+    }
+
+    if reflection_rate + burn_rate > Decimal::one() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "addition of reflection_rate & burn_rate must be <= 1",
+        )));
+    }
+    check_max_total_tax(deps.storage, global_rate)?;
+
+    let mut rates = load_scheduled_rates(deps.storage)?;
+    rates.buy_rate = global_rate;
+    rates.sell_rate = global_rate;
+    rates.reflection_rate = reflection_rate;
+    rates.burn_rate = burn_rate;
+    let effective_at = schedule_rates(deps.storage, &env, &info.sender, rates)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_tax_rate")
+        .add_attribute("effective_at", effective_at.to_string()))
+}
+
+/// Sets separate tax rates per transfer direction, each a number between 0 to 1
+/// Buy rate applies when the sender is a pair, sell rate when the recipient is a pair,
+/// and transfer rate applies to wallet-to-wallet transfers
+pub fn set_tax_rates(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    buy_rate: Decimal,
+    sell_rate: Decimal,
+    transfer_rate: Decimal,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    if buy_rate > Decimal::one() || sell_rate > Decimal::one() || transfer_rate > Decimal::one() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "buy_rate, sell_rate & transfer_rate must be <= 1",
+        )));
+    }
+    check_max_total_tax(deps.storage, buy_rate)?;
+    check_max_total_tax(deps.storage, sell_rate)?;
+    check_max_total_tax(deps.storage, transfer_rate)?;
+
+    let mut rates = load_scheduled_rates(deps.storage)?;
+    rates.buy_rate = buy_rate;
+    rates.sell_rate = sell_rate;
+    rates.transfer_rate = transfer_rate;
+    let effective_at = schedule_rates(deps.storage, &env, &info.sender, rates)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_tax_rates")
+        .add_attribute("buy_rate", buy_rate.to_string())
+        .add_attribute("sell_rate", sell_rate.to_string())
+        .add_attribute("transfer_rate", transfer_rate.to_string())
+        .add_attribute("effective_at", effective_at.to_string()))
+}
+
+/// Rejects tax rates above the ceiling set at instantiate
+pub fn check_max_total_tax(storage: &dyn Storage, rate: Decimal) -> Result<(), ContractError> {
+    let max_total_tax = MAX_TOTAL_TAX
+        .may_load(storage)?
+        .unwrap_or_else(Decimal::one);
+    if rate > max_total_tax {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Tax rate exceeds the max total tax of {}",
+            max_total_tax
+        ))));
+    }
+
+    Ok(())
+}
+
+/// Sets the volume-responsive sell tax, `None` disables it
+pub fn set_dynamic_tax(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: Option<DynamicTax>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    if let Some(config) = &config {
+        if config.epoch_seconds == 0 || config.volume_threshold.is_zero() {
+            return Err(ContractError::Std(StdError::generic_err(
+                "epoch_seconds & volume_threshold must be > 0",
+            )));
+        }
+        if config.min_rate > config.max_rate {
+            return Err(ContractError::Std(StdError::generic_err(
+                "min_rate must be <= max_rate",
+            )));
+        }
+        check_max_total_tax(deps.storage, config.max_rate)?;
+    }
+    let enabled = config.is_some();
+    let effective_at = schedule_tax_change(deps.storage, &env, TaxChange::DynamicTax { config })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_dynamic_tax")
+        .add_attribute("enabled", enabled.to_string())
+        .add_attribute("effective_at", effective_at.to_string()))
+}
+
+/// Sets the launch tax decay, `None` disables it
+pub fn set_tax_decay(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    decay: Option<TaxDecay>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    if let Some(decay) = &decay {
+        validate_tax_decay(deps.storage, decay)?;
+    }
+    let enabled = decay.is_some();
+    let effective_at = schedule_tax_change(deps.storage, &env, TaxChange::TaxDecay { decay })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_tax_decay")
+        .add_attribute("enabled", enabled.to_string())
+        .add_attribute("effective_at", effective_at.to_string()))
+}
+
+pub fn validate_tax_decay(storage: &dyn Storage, decay: &TaxDecay) -> Result<(), ContractError> {
+    if decay.duration == 0 {
+        return Err(ContractError::Std(StdError::generic_err(
+            "duration must be > 0",
+        )));
+    }
+    if decay.end_rate > decay.start_rate {
+        return Err(ContractError::Std(StdError::generic_err(
+            "end_rate must be <= start_rate",
+        )));
+    }
+    check_max_total_tax(storage, decay.start_rate)
+}
+
+/// Returns the pending rates if a change is scheduled, the current rates otherwise
+pub fn load_scheduled_rates(storage: &dyn Storage) -> StdResult<PendingRates> {
+    if let Some(pending) = PENDING_RATES.may_load(storage)? {
+        return Ok(pending);
+    }
+
+    Ok(PendingRates {
+        buy_rate: BUY_TAX_RATE.may_load(storage)?.unwrap_or_default(),
+        sell_rate: SELL_TAX_RATE.may_load(storage)?.unwrap_or_default(),
+        transfer_rate: TRANSFER_TAX_RATE.may_load(storage)?.unwrap_or_default(),
+        reflection_rate: REFLECTION_RATE.may_load(storage)?.unwrap_or_default(),
+        burn_rate: BURN_RATE.may_load(storage)?.unwrap_or_default(),
+        effective_at: 0,
+    })
+}
+
+/// Schedules `rates` to take effect after the tax rate delay, replacing any pending change, and
+/// records the change in the rate history
+/// Returns the time at which the rates take effect
+pub fn schedule_rates(
+    storage: &mut dyn Storage,
+    env: &Env,
+    sender: &Addr,
+    mut rates: PendingRates,
+) -> StdResult<u64> {
+    let delay = TAX_RATE_DELAY.may_load(storage)?.unwrap_or_default();
+    rates.effective_at = env.block.time.seconds() + delay;
+    let old_rates = load_scheduled_rates(storage)?;
+    record_rate_change(
+        storage,
+        RateChange {
+            old_rates,
+            new_rates: rates.clone(),
+            height: env.block.height,
+            sender: sender.clone(),
+        },
+    )?;
+    PENDING_RATES.save(storage, &rates)?;
+    apply_pending_rates(storage, env)?;
+
+    Ok(rates.effective_at)
+}
+
+/// Appends a rate change to the history, dropping the oldest one once it is full
+pub fn record_rate_change(storage: &mut dyn Storage, change: RateChange) -> StdResult<()> {
+    let id = RATE_HISTORY_NEXT_ID.may_load(storage)?.unwrap_or_default();
+    RATE_HISTORY.save(storage, id, &change)?;
+    RATE_HISTORY_NEXT_ID.save(storage, &(id + 1))?;
+    if id >= RATE_HISTORY_SIZE {
+        RATE_HISTORY.remove(storage, id - RATE_HISTORY_SIZE);
+    }
+
+    Ok(())
+}
+
+/// Appends a privileged action to the audit log, described by the attributes of its response
+pub fn record_audit(
+    storage: &mut dyn Storage,
+    env: &Env,
+    sender: &Addr,
+    res: &Response,
+) -> StdResult<()> {
+    let id = AUDIT_LOG_NEXT_ID.may_load(storage)?.unwrap_or_default();
+    AUDIT_LOG.save(
+        storage,
+        id,
+        &AuditEntry {
+            sender: sender.clone(),
+            height: env.block.height,
+            time: env.block.time.seconds(),
+            attributes: res.attributes.clone(),
+        },
+    )?;
+    AUDIT_LOG_NEXT_ID.save(storage, &(id + 1))
+}
+
+/// Returns the audit log entries after `start_after`, oldest first
+pub fn query_audit_log(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<AuditLogResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let entries = AUDIT_LOG
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, entry)| AuditLogEntry { id, entry }))
+        .collect::<StdResult<_>>()?;
+
+    Ok(AuditLogResponse { entries })
+}
+
+/// Returns the recorded rate changes after `start_after`, oldest first
+pub fn query_rate_history(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<RateHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let changes = RATE_HISTORY
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, change)| RateHistoryEntry { id, change }))
+        .collect::<StdResult<_>>()?;
+
+    Ok(RateHistoryResponse { changes })
+}
+
+/// Applies the pending rates once their delay has passed
+pub fn apply_pending_rates(storage: &mut dyn Storage, env: &Env) -> StdResult<()> {
+    let rates = match PENDING_RATES.may_load(storage)? {
+        Some(rates) if rates.effective_at <= env.block.time.seconds() => rates,
+        _ => return Ok(()),
+    };
+
+    BUY_TAX_RATE.save(storage, &rates.buy_rate)?;
+    SELL_TAX_RATE.save(storage, &rates.sell_rate)?;
+    TRANSFER_TAX_RATE.save(storage, &rates.transfer_rate)?;
+    REFLECTION_RATE.save(storage, &rates.reflection_rate)?;
+    BURN_RATE.save(storage, &rates.burn_rate)?;
+    PENDING_RATES.remove(storage);
+
+    Ok(())
+}
+
+/// Storage with the pending rates and tax setting changes that are due applied in memory, so queries
+/// return the rates the next execute applies rather than the stored ones
+pub fn with_due_tax_changes<'a>(
+    storage: &'a dyn Storage,
+    env: &Env,
+) -> StdResult<StorageOverlay<'a>> {
+    let mut overlay = StorageOverlay::new(storage);
+    apply_pending_rates(&mut overlay, env)?;
+    apply_pending_tax_changes(&mut overlay, env)?;
+    Ok(overlay)
+}
+
+/// Pending tax changes are keyed by setting, so a new change replaces the pending change of the same
+/// setting, or of the same pair
+fn tax_change_key(change: &TaxChange) -> String {
+    match change {
+        TaxChange::TaxTiers { .. } => "tax_tiers".to_string(),
+        TaxChange::DynamicTax { .. } => "dynamic_tax".to_string(),
+        TaxChange::TaxDecay { .. } => "tax_decay".to_string(),
+        TaxChange::PriceImpactTax { .. } => "price_impact_tax".to_string(),
+        TaxChange::AntiWhaleTax { .. } => "anti_whale_tax".to_string(),
+        TaxChange::TaxStrategy { .. } => "tax_strategy".to_string(),
+        TaxChange::Pair { contract, .. } => format!("pair:{}", contract),
+        TaxChange::HolderReflectionRate { .. } => "holder_reflection_rate".to_string(),
+        TaxChange::MintTax { .. } => "mint_tax".to_string(),
+        TaxChange::TaxMode { .. } => "tax_mode".to_string(),
+    }
+}
+
+/// Schedules `change` to take effect after the tax rate delay, replacing the pending change of
+/// the same setting. Applied right away without a delay
+/// Returns the time at which the change takes effect
+pub fn schedule_tax_change(
+    storage: &mut dyn Storage,
+    env: &Env,
+    change: TaxChange,
+) -> StdResult<u64> {
+    let delay = TAX_RATE_DELAY.may_load(storage)?.unwrap_or_default();
+    let effective_at = env.block.time.seconds() + delay;
+    PENDING_TAX_CHANGES.save(
+        storage,
+        tax_change_key(&change),
+        &PendingTaxChange {
+            change,
+            effective_at,
+        },
+    )?;
+    apply_pending_tax_changes(storage, env)?;
+
+    Ok(effective_at)
+}
+
+/// Applies the pending tax changes whose delay has passed
+pub fn apply_pending_tax_changes(storage: &mut dyn Storage, env: &Env) -> StdResult<()> {
+    let due = PENDING_TAX_CHANGES
+        .range(storage, None, None, Order::Ascending)
+        .filter(|item| {
+            item.as_ref().map_or(true, |(_, pending)| {
+                pending.effective_at <= env.block.time.seconds()
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (key, pending) in due {
+        apply_tax_change(storage, pending.change)?;
+        PENDING_TAX_CHANGES.remove(storage, key);
+    }
+    Ok(())
+}
+
+fn apply_tax_change(storage: &mut dyn Storage, change: TaxChange) -> StdResult<()> {
+    match change {
+        TaxChange::TaxTiers { tiers } => TAX_TIERS.save(storage, &tiers),
+        TaxChange::DynamicTax { config } => {
+            match config {
+                Some(config) => DYNAMIC_TAX.save(storage, &config)?,
+                None => DYNAMIC_TAX.remove(storage),
+            }
+            SELL_VOLUME.remove(storage);
+            Ok(())
+        }
+        TaxChange::TaxDecay { decay } => match decay {
+            Some(decay) => TAX_DECAY.save(storage, &decay),
+            None => {
+                TAX_DECAY.remove(storage);
+                Ok(())
+            }
+        },
+        TaxChange::PriceImpactTax { config } => match config {
+            Some(config) => PRICE_IMPACT_TAX.save(storage, &config),
+            None => {
+                PRICE_IMPACT_TAX.remove(storage);
+                Ok(())
+            }
+        },
+        TaxChange::AntiWhaleTax {
+            surtax_multiplier,
+            max_surtax,
+        } => ANTI_WHALE_TAX.save(
+            storage,
+            &AntiWhaleTax {
+                surtax_multiplier,
+                max_surtax,
+            },
+        ),
+        TaxChange::TaxStrategy { contract } => match contract {
+            Some(contract) => TAX_STRATEGY.save(storage, &contract),
+            None => {
+                TAX_STRATEGY.remove(storage);
+                Ok(())
+            }
+        },
+        TaxChange::Pair { contract, config } => PAIRLIST.save(storage, contract, &config),
+        TaxChange::HolderReflectionRate { rate } => HOLDER_REFLECTION_RATE.save(storage, &rate),
+        TaxChange::MintTax { rate } => MINT_TAX_RATE.save(storage, &rate),
+        TaxChange::TaxMode { mode } => TAX_MODE.save(storage, &mode),
+    }
+}
+
+/// Cancels every pending tax setting change
+pub fn cancel_pending_tax_changes(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let keys = PENDING_TAX_CHANGES
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for key in keys.iter() {
+        PENDING_TAX_CHANGES.remove(deps.storage, key.clone());
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_pending_tax_changes")
+        .add_attribute("cancelled", keys.len().to_string()))
+}
+
+/// Cancels the pending rate change
+pub fn cancel_pending_rates(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    if PENDING_RATES.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "No pending rate change",
+        )));
+    }
+    PENDING_RATES.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "cancel_pending_rates"))
+}
+
+/// Sets the delay before rate changes take effect. The delay can only be increased,
+/// so it cannot be lowered right before a rate change
+pub fn set_tax_rate_delay(
+    deps: DepsMut,
+    info: MessageInfo,
+    seconds: u64,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let delay = TAX_RATE_DELAY.may_load(deps.storage)?.unwrap_or_default();
+    if seconds < delay {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Tax rate delay can only be increased",
+        )));
+    }
+    TAX_RATE_DELAY.save(deps.storage, &seconds)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_tax_rate_delay")
+        .add_attribute("seconds", seconds.to_string()))
+}
+
+/// Sets the tax tier schedule, an empty list disables tiers
+/// Each tier applies to transfers of at least `min_supply_rate` of the total supply
+pub fn set_tax_tiers(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mut tiers: Vec<TaxTier>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    if tiers
+        .iter()
+        .any(|tier| tier.min_supply_rate > Decimal::one() || tier.tax_rate > Decimal::one())
+    {
+        return Err(ContractError::Std(StdError::generic_err(
+            "min_supply_rate & tax_rate must be <= 1",
+        )));
+    }
+    for tier in tiers.iter() {
+        check_max_total_tax(deps.storage, tier.tax_rate)?;
+    }
+
+    tiers.sort_by_key(|tier| tier.min_supply_rate);
+    if tiers
+        .windows(2)
+        .any(|pair| pair[0].min_supply_rate == pair[1].min_supply_rate)
+    {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Duplicate min_supply_rate in tax tiers",
+        )));
+    }
+
+    let count = tiers.len();
+    let effective_at = schedule_tax_change(deps.storage, &env, TaxChange::TaxTiers { tiers })?;
+    Ok(Response::new()
+        .add_attribute("action", "set_tax_tiers")
+        .add_attribute("tiers", count.to_string())
+        .add_attribute("effective_at", effective_at.to_string()))
+}
+
+/// Opens trading to everyone, can only be called once
+pub fn enable_trading(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    if TRADING_ENABLED.may_load(deps.storage)?.unwrap_or(true) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Trading is already enabled",
+        )));
+    }
+
+    TRADING_ENABLED.save(deps.storage, &true)?;
+    TRADING_ENABLED_AT.save(deps.storage, &env.block.height)?;
+    Ok(Response::new()
+        .add_attribute("action", "enable_trading")
+        .add_attribute("height", env.block.height.to_string()))
+}
+
+/// Sets the launch protection applied after trading is enabled, `None` disables it
+/// Disabling it also lifts the penalties of flagged snipers, who stay flagged
+pub fn set_launch_protection(
+    deps: DepsMut,
+    info: MessageInfo,
+    protection: Option<LaunchProtection>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    match &protection {
+        Some(protection) => {
+            protection.validate()?;
+            LAUNCH_PROTECTION.save(deps.storage, protection)?;
+        }
+        None => LAUNCH_PROTECTION.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_launch_protection")
+        .add_attribute("enabled", protection.is_some().to_string()))
+}
+
+/// Clears the sniper flag of an address
+pub fn clear_sniper(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    let address = deps.api.addr_validate(&address)?;
+    SNIPERS.remove(deps.storage, &address);
+
+    Ok(Response::new()
+        .add_attribute("action", "clear_sniper")
+        .add_attribute("address", address))
+}
+
+/// Sets the largest fraction of the total supply that can be moved in a single transfer, 1 disables the limit
+pub fn set_max_transfer_rate(
+    deps: DepsMut,
+    info: MessageInfo,
+    rate: Decimal,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    if rate.is_zero() || rate > Decimal::one() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "rate must be > 0 and <= 1",
+        )));
+    }
+
+    MAX_TRANSFER_SUPPLY_RATE.save(deps.storage, &rate)?;
+    Ok(Response::new()
+        .add_attribute("action", "set_max_transfer_rate")
+        .add_attribute("rate", rate.to_string()))
+}
+
+/// Force-moves `amount` from `from` to `to` without taxes or transfer restrictions
+/// Only available if clawback was enabled at instantiate
+pub fn clawback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    from: String,
+    to: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    if !CLAWBACK_ENABLED.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Clawback is not enabled",
+        )));
+    }
+    if amount == Uint128::zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let from = deps.api.addr_validate(&from)?;
+    let to = deps.api.addr_validate(&to)?;
+    move_balance(deps.storage, &env, &from, &to, amount)?;
+
+    Ok(Response::new()
+        .add_attributes(vec![
+            attr("action", "clawback"),
+            attr("from", from.clone()),
+            attr("to", to.clone()),
+            attr("amount", amount),
+        ])
+        .add_event(
+            Event::new("clawback")
+                .add_attribute("from", from)
+                .add_attribute("to", to)
+                .add_attribute("asset", env.contract.address)
+                .add_attribute("amount", amount),
+        ))
+}
+
+/// Pauses or unpauses every balance and allowance change, see `execute_msg`
+pub fn set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    PAUSED.save(deps.storage, &paused)?;
+
+    let action = if paused { "pause" } else { "unpause" };
+    Ok(Response::new().add_attribute("action", action))
+}
+
+/// Blocks outbound transfers from an account, it can still receive tokens
+pub fn freeze(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    let address = deps.api.addr_validate(&address)?;
+    FROZEN.save(deps.storage, &address, &true)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "freeze")
+        .add_attribute("address", address))
+}
+
+/// Lifts the freeze of an account
+pub fn unfreeze(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    let address = deps.api.addr_validate(&address)?;
+    FROZEN.remove(deps.storage, &address);
+
+    Ok(Response::new()
+        .add_attribute("action", "unfreeze")
+        .add_attribute("address", address))
+}
+
+/// Enables or disables the same-block buy/sell guard
+pub fn set_sandwich_guard(
+    deps: DepsMut,
+    info: MessageInfo,
+    enable: bool,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    SANDWICH_GUARD.save(deps.storage, &enable)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_sandwich_guard")
+        .add_attribute("enable", enable.to_string()))
+}
+
+/// Exempts an address from the same-block buy/sell guard
+pub fn set_sandwich_exempt(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    exempt: bool,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    let address = deps.api.addr_validate(&address)?;
+
+    if exempt {
+        SANDWICH_EXEMPT.save(deps.storage, &address, &true)?;
+    } else {
+        SANDWICH_EXEMPT.remove(deps.storage, &address);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_sandwich_exempt")
+        .add_attribute("address", address)
+        .add_attribute("exempt", exempt.to_string()))
+}
+
+/// Sets the amount each address can sell into pairs per 24 hours, `None` disables the limit
+pub fn set_sell_limit(
+    deps: DepsMut,
+    info: MessageInfo,
+    limit: Option<SellLimit>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    match &limit {
+        Some(limit) => {
+            if let SellLimit::BalanceRate { rate } = limit {
+                if rate.is_zero() || *rate > Decimal::one() {
+                    return Err(ContractError::Std(StdError::generic_err(
+                        "rate must be > 0 and <= 1",
+                    )));
+                }
+            }
+            SELL_LIMIT.save(deps.storage, limit)?;
+        }
+        None => SELL_LIMIT.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_sell_limit")
+        .add_attribute("enabled", limit.is_some().to_string()))
+}
+
+/// Returns the spending cap on the allowance of `spender` over the tokens of `owner` and its current period
+pub fn query_allowance_limit(
+    deps: Deps,
+    env: &Env,
+    owner: String,
+    spender: String,
+) -> StdResult<AllowanceLimitResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let spender = deps.api.addr_validate(&spender)?;
+    let limit = match ALLOWANCE_LIMITS.may_load(deps.storage, (&owner, &spender))? {
+        Some(limit) => limit,
+        None => return Ok(AllowanceLimitResponse::default()),
+    };
+
+    let window = load_allowance_window(deps.storage, env, &owner, &spender, &limit)?;
+    Ok(AllowanceLimitResponse {
+        remaining: limit.amount.saturating_sub(window.spent),
+        period_end: window.start + limit.period,
+        limit: Some(limit),
+    })
+}
+
+/// Returns how much `address` sold in its current window and how much it can still sell
+pub fn query_sell_allowance(
+    deps: Deps,
+    env: Env,
+    address: String,
+) -> StdResult<SellAllowanceResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let window = load_sell_window(deps.storage, &env, &address)?;
+    let remaining = SELL_LIMIT
+        .may_load(deps.storage)?
+        .map(|limit| sell_limit_remaining(&limit, &window));
+
+    Ok(SellAllowanceResponse {
+        sold: window.sold,
+        remaining,
+        window_end: window.start + SELL_WINDOW_SECONDS,
+    })
+}
+
+/// Sets the minimum seconds between two transfers from the same address, zero disables the cooldown
+pub fn set_transfer_cooldown(
+    deps: DepsMut,
+    info: MessageInfo,
+    seconds: u64,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    TRANSFER_COOLDOWN.save(deps.storage, &seconds)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_transfer_cooldown")
+        .add_attribute("seconds", seconds.to_string()))
+}
+
+/// Registers or removes a cw20-ics20 contract or channel escrow address as a bridge
+pub fn set_bridge(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    enable: bool,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    let address = deps.api.addr_validate(&address)?;
+
+    if enable {
+        BRIDGES.save(deps.storage, &address, &true)?;
+    } else {
+        BRIDGES.remove(deps.storage, &address);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_bridge")
+        .add_attribute("address", address)
+        .add_attribute("enable", enable.to_string()))
+}
+
+/// Toggles the tax exemption of transfers from and to recognized bridges
+pub fn set_bridge_exemption_enabled(
+    deps: DepsMut,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    BRIDGE_EXEMPTION_ENABLED.save(deps.storage, &enabled)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_bridge_exemption_enabled")
+        .add_attribute("enabled", enabled.to_string()))
+}
+
+/// Restricts `Send` to pairs and allowlisted contracts
+pub fn set_send_allowlist_enabled(
+    deps: DepsMut,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    SEND_ALLOWLIST_ENABLED.save(deps.storage, &enabled)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_send_allowlist_enabled")
+        .add_attribute("enabled", enabled.to_string()))
+}
+
+/// Adds or removes a contract from the `Send` allowlist
+pub fn set_send_allowed(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: String,
+    allowed: bool,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    let contract = deps.api.addr_validate(&contract)?;
+
+    if allowed {
+        SEND_ALLOWED.save(deps.storage, &contract, &true)?;
+    } else {
+        SEND_ALLOWED.remove(deps.storage, &contract);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_send_allowed")
+        .add_attribute("contract", contract)
+        .add_attribute("allowed", allowed.to_string()))
+}
+
+/// Exempts an address from the transfer cooldown
+pub fn set_cooldown_exempt(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    exempt: bool,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    let address = deps.api.addr_validate(&address)?;
+
+    if exempt {
+        COOLDOWN_EXEMPT.save(deps.storage, &address, &true)?;
+    } else {
+        COOLDOWN_EXEMPT.remove(deps.storage, &address);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_cooldown_exempt")
+        .add_attribute("address", address)
+        .add_attribute("exempt", exempt.to_string()))
+}
+
+/// Sets the largest fraction of the total supply a wallet can hold, `None` disables the limit
+pub fn set_max_wallet_rate(
+    deps: DepsMut,
+    info: MessageInfo,
+    rate: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    match rate {
+        Some(rate) => {
+            if rate.is_zero() || rate > Decimal::one() {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "rate must be > 0 and <= 1",
+                )));
+            }
+            MAX_WALLET_RATE.save(deps.storage, &rate)?;
         }
+        None => MAX_WALLET_RATE.remove(deps.storage),
     }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_max_wallet_rate")
+        .add_attribute(
+            "rate",
+            rate.map_or_else(|| "none".to_string(), |rate| rate.to_string()),
+        ))
 }
 
-/// Used to calculate the amount of taxes to be paid, to be used in all transfer functions
-pub fn query_tax(storage: &dyn Storage, amount: Uint128) -> Result<QueryTaxResponse, StdError> {
-    let reflection_rate = REFLECTION_RATE.may_load(storage)?.unwrap();
-    let tax_rate = TAX_RATE.may_load(storage)?.unwrap();
-    let burn_rate = BURN_RATE.may_load(storage)?.unwrap();
-
-    let taxed_amount = amount.mul(tax_rate);
-    let after_tax = amount.sub(taxed_amount);
-    let reflection_amount = taxed_amount.mul(reflection_rate);
-    let burn_amount = taxed_amount.mul(burn_rate);
-    let liquidity_amount = taxed_amount.sub(reflection_amount).sub(burn_amount);
-
-    Ok(QueryTaxResponse {
-        taxed_amount,
-        after_tax,
-        reflection_amount,
-        liquidity_amount,
-    })
+/// Exempts an address from the max wallet limit
+pub fn set_max_wallet_exempt(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    exempt: bool,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    let address = deps.api.addr_validate(&address)?;
+
+    if exempt {
+        MAX_WALLET_EXEMPT.save(deps.storage, &address, &true)?;
+    } else {
+        MAX_WALLET_EXEMPT.remove(deps.storage, &address);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_max_wallet_exempt")
+        .add_attribute("address", address)
+        .add_attribute("exempt", exempt.to_string()))
 }
 
-/// Returns the current tax rates
-pub fn query_rate(storage: &dyn Storage) -> Result<(Decimal, Decimal, Decimal), StdError> {
-    let tax_rate = TAX_RATE.may_load(storage)?.unwrap();
-    let reflection_rate = REFLECTION_RATE.may_load(storage)?.unwrap();
-    let burn_rate = BURN_RATE.may_load(storage)?.unwrap();
+/// Sets the external contract that computes the base tax rate of transfers, `None` removes it
+pub fn set_tax_strategy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let contract = contract
+        .map(|contract| deps.api.addr_validate(&contract))
+        .transpose()?;
+    let strategy = contract
+        .as_ref()
+        .map_or_else(|| "none".to_string(), Addr::to_string);
+    let effective_at =
+        schedule_tax_change(deps.storage, &env, TaxChange::TaxStrategy { contract })?;
 
-    Ok((tax_rate, reflection_rate, burn_rate))
+    Ok(Response::new()
+        .add_attribute("action", "set_tax_strategy")
+        .add_attribute("strategy", strategy)
+        .add_attribute("effective_at", effective_at.to_string()))
 }
 
-pub fn query_pairlist(storage: &dyn Storage, address: String) -> Result<bool, StdError> {
-    let pairlist = PAIRLIST.may_load(storage, address)?.unwrap();
+/// Sets the price impact surtax on sells, `None` disables it
+pub fn set_price_impact_tax(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: Option<PriceImpactTax>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    if let Some(config) = &config {
+        check_max_total_tax(deps.storage, config.max_rate)?;
+    }
+    let enabled = config.is_some();
+    let effective_at =
+        schedule_tax_change(deps.storage, &env, TaxChange::PriceImpactTax { config })?;
 
-    Ok(pairlist)
+    Ok(Response::new()
+        .add_attribute("action", "set_price_impact_tax")
+        .add_attribute("enabled", enabled.to_string())
+        .add_attribute("effective_at", effective_at.to_string()))
 }
 
-/// Global rate is number between 0 to 1. 0.1 refers to 10% taxes on all transfers
-/// Reflection rate is number between 0 to 1. 0.5 refers to 50% of GLOBAL taxes gets transferred as reflection
-/// Burn rate is number between 0 to 1. 0.1 refers to 10% of GLOBAL taxes gets burnt
-/// Antiwhale rate is number between 0 to 1. 0.02 refers to when someone intends to move 2% of supply, anti-whale gets triggered
-pub fn set_tax_rate(
+/// Sets the anti-whale surtax on sells, a `max_surtax` of zero disables it
+pub fn set_anti_whale_tax(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    global_rate: Decimal,
-    reflection_rate: Decimal,
-    burn_rate: Decimal,
+    surtax_multiplier: Decimal,
+    max_surtax: Decimal,
 ) -> Result<Response, ContractError> {
     ensure_admin(&deps, &info)?;
 
-    if global_rate > Decimal::one() {
+    if max_surtax > Decimal::one() {
         return Err(ContractError::Std(StdError::generic_err(
-            "global_rate must be <= 1",
+            "max_surtax must be <= 1",
         )));
     }
 
-    if reflection_rate + burn_rate > Decimal::one() {
+    let effective_at = schedule_tax_change(
+        deps.storage,
+        &env,
+        TaxChange::AntiWhaleTax {
+            surtax_multiplier,
+            max_surtax,
+        },
+    )?;
+    Ok(Response::new()
+        .add_attribute("action", "set_anti_whale_tax")
+        .add_attribute("surtax_multiplier", surtax_multiplier.to_string())
+        .add_attribute("max_surtax", max_surtax.to_string())
+        .add_attribute("effective_at", effective_at.to_string()))
+}
+
+/// Set treasury address
+pub fn set_treasury(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: String,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    let treasury = deps.api.addr_validate(&contract)?;
+    let previous = TREASURY.may_load(deps.storage)?.unwrap_or_default();
+    TREASURY.save(deps.storage, &treasury.to_string())?;
+    Ok(Response::new()
+        .add_attribute("action", "set_treasury")
+        .add_attribute("previous_treasury", previous)
+        .add_attribute("treasury", treasury))
+}
+
+/// Holder reflection rate is number between 0 to 1. 0.5 refers to 50% of taxes being distributed to holders
+/// The remaining taxes are sent to the treasury as usual
+pub fn set_holder_reflection_rate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    rate: Decimal,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    if rate > Decimal::one() {
         return Err(ContractError::Std(StdError::generic_err(
-            "addition of reflection_rate & burn_rate must be <= 1",
+            "holder reflection rate must be <= 1",
         )));
     }
 
-    TAX_RATE.save(deps.storage, &global_rate)?;
-    REFLECTION_RATE.save(deps.storage, &reflection_rate)?;
-    BURN_RATE.save(deps.storage, &burn_rate)?;
-    Ok(Response::default())
+    let effective_at =
+        schedule_tax_change(deps.storage, &env, TaxChange::HolderReflectionRate { rate })?;
+    Ok(Response::new()
+        .add_attribute("action", "set_holder_reflection_rate")
+        .add_attribute("rate", rate.to_string())
+        .add_attribute("effective_at", effective_at.to_string()))
 }
 
-/// Set treasury address
-pub fn set_treasury(
+/// Sets the native denom holders are paid in when the treasury pays reflections natively.
+/// Can only be set once, since pending native reflections are accounted in this denom
+pub fn set_native_reflection_denom(
     deps: DepsMut,
     info: MessageInfo,
-    contract: String
+    denom: String,
 ) -> Result<Response, ContractError> {
     ensure_admin(&deps, &info)?;
-    deps.api.addr_validate(&contract.to_string())?;
-    TREASURY.save(deps.storage, &contract)?;
-    Ok(Response::default())
+
+    if NATIVE_REFLECTION_DENOM.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Native reflection denom is already set",
+        )));
+    }
+
+    NATIVE_REFLECTION_DENOM.save(deps.storage, &denom)?;
+    Ok(Response::new()
+        .add_attribute("action", "set_native_reflection_denom")
+        .add_attribute("denom", denom))
+}
+
+/// Excludes or includes an address in reflection accrual. Reflections accrued before the exclusion can still be claimed
+pub fn set_reflection_exclusion(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    excluded: bool,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    let address = deps.api.addr_validate(&address)?;
+
+    if excluded {
+        REFLECTION_EXCLUDED.save(deps.storage, &address, &true)?;
+    } else {
+        REFLECTION_EXCLUDED.remove(deps.storage, &address);
+    }
+    // settles the accrual so far and updates the shares of the address
+    sync_reflection(deps.storage, &env, &address)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_reflection_exclusion")
+        .add_attribute("address", address)
+        .add_attribute("excluded", excluded.to_string()))
 }
 
+/// Sets the seconds between two liquify calls triggered by transfers
+pub fn set_liquify_cooldown(
+    deps: DepsMut,
+    info: MessageInfo,
+    seconds: u64,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    LIQUIFY_COOLDOWN.save(deps.storage, &seconds)?;
+    Ok(Response::new()
+        .add_attribute("action", "set_liquify_cooldown")
+        .add_attribute("seconds", seconds.to_string()))
+}
+
+/// Sets the taxes that must accumulate since the last liquify before a transfer triggers the next one
+pub fn set_liquify_threshold(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    LIQUIFY_THRESHOLD.save(deps.storage, &amount)?;
+    Ok(Response::new()
+        .add_attribute("action", "set_liquify_threshold")
+        .add_attribute("amount", amount))
+}
 
 /// Start buyback
 pub fn set_buyback(
@@ -658,76 +4630,381 @@ pub fn set_buyback(
 ) -> Result<Response, ContractError> {
     ensure_admin(&deps, &info)?;
     BUYBACK_ENABLE.save(deps.storage, &enable)?;
-    Ok(Response::default())
+    Ok(Response::new()
+        .add_attribute("action", "set_buyback")
+        .add_attribute("enabled", enable.to_string()))
+}
+
+/// Sets the bucket receiving the rounding dust of the tax split
+pub fn set_dust_bucket(
+    deps: DepsMut,
+    info: MessageInfo,
+    bucket: TaxBucket,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    DUST_BUCKET.save(deps.storage, &bucket)?;
+    Ok(Response::new().add_attribute("action", "set_dust_bucket"))
+}
+
+/// Sets whether taxes are taken out of the transferred amount or charged to the sender on top of it
+pub fn set_tax_mode(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mode: TaxMode,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    let effective_at = schedule_tax_change(deps.storage, &env, TaxChange::TaxMode { mode })?;
+    Ok(Response::new()
+        .add_attribute("action", "set_tax_mode")
+        .add_attribute("effective_at", effective_at.to_string()))
+}
+
+/// Sets the share of newly minted tokens routed to the treasury
+pub fn set_mint_tax(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    rate: Decimal,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    check_max_total_tax(deps.storage, rate)?;
+
+    let effective_at = schedule_tax_change(deps.storage, &env, TaxChange::MintTax { rate })?;
+    Ok(Response::new()
+        .add_attribute("action", "set_mint_tax")
+        .add_attribute("rate", rate.to_string())
+        .add_attribute("effective_at", effective_at.to_string()))
+}
+
+/// Switches transfers between reporting the amount received and the standard cw20 attributes with a tax breakdown
+pub fn set_standard_attributes(
+    deps: DepsMut,
+    info: MessageInfo,
+    enable: bool,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    STANDARD_ATTRIBUTES.save(deps.storage, &enable)?;
+    Ok(Response::new()
+        .add_attribute("action", "set_standard_attributes")
+        .add_attribute("enable", enable.to_string()))
+}
+
+/// Burns the burn share of the taxes in the transfer itself instead of leaving it to the treasury
+pub fn set_instant_burn(
+    deps: DepsMut,
+    info: MessageInfo,
+    enable: bool,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    INSTANT_BURN.save(deps.storage, &enable)?;
+    Ok(Response::new()
+        .add_attribute("action", "set_instant_burn")
+        .add_attribute("enable", enable.to_string()))
 }
 
 /// Sets pair address (taxed)
+/// `tax_rate` optionally overrides the buy and sell rates for this pair, e.g. a lower tax on the primary pool
 pub fn set_pairlist(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     contract: String,
     enable: bool,
+    tax_rate: Option<Decimal>,
 ) -> Result<Response, ContractError> {
     ensure_admin(&deps, &info)?;
     deps.api.addr_validate(&contract.to_string())?;
-    PAIRLIST.save(deps.storage, contract.to_string(), &enable)?;
-    Ok(Response::default())
+
+    if tax_rate.is_some_and(|rate| rate > Decimal::one()) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "tax_rate must be <= 1",
+        )));
+    }
+    if let Some(tax_rate) = tax_rate {
+        check_max_total_tax(deps.storage, tax_rate)?;
+    }
+
+    let effective_at = schedule_tax_change(
+        deps.storage,
+        &env,
+        TaxChange::Pair {
+            contract: contract.clone(),
+            config: PairConfig {
+                enabled: enable,
+                tax_rate,
+            },
+        },
+    )?;
+    let tax_rate = tax_rate.map_or_else(|| "none".to_string(), |rate| rate.to_string());
+    Ok(Response::new()
+        .add_attribute("action", "set_pairlist")
+        .add_attribute("pair", contract)
+        .add_attribute("enabled", enable.to_string())
+        .add_attribute("tax_rate", tax_rate)
+        .add_attribute("effective_at", effective_at.to_string()))
 }
 
-/// This is used to ensure that only the admin can execute certain functions
-pub fn ensure_admin(deps: &DepsMut, info: &MessageInfo) -> Result<Response, ContractError> {
-    let admin = ADMIN.may_load(deps.storage)?.unwrap_or_default();
-    if info.sender != admin {
+/// Exempts an address from taxes, independently of `PAIRLIST`
+pub fn set_tax_exempt(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    exempt: bool,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    let address = deps.api.addr_validate(&address)?;
+
+    if exempt {
+        TAX_EXEMPT.save(deps.storage, &address, &true)?;
+    } else {
+        TAX_EXEMPT.remove(deps.storage, &address);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_tax_exempt")
+        .add_attribute("address", address)
+        .add_attribute("exempt", exempt.to_string()))
+}
+
+/// This is used to block token movements while the contract is paused
+pub fn ensure_not_paused(storage: &dyn Storage) -> Result<(), ContractError> {
+    if PAUSED.may_load(storage)?.unwrap_or_default() {
         return Err(ContractError::Std(StdError::generic_err(
-            "Unauthorized: not admin",
+            "Contract is paused",
         )));
     }
 
-    Ok(Response::default())
+    Ok(())
 }
 
+/// This is used to ensure that only the admin (the cw-ownable owner) can execute certain functions
+pub fn ensure_admin(deps: &DepsMut, info: &MessageInfo) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)
+        .map_err(|err| ContractError::Std(StdError::generic_err(err.to_string())))?;
+
+    Ok(Response::default())
+}
 
-/// This is used to generate a transfer event to treasury contract (so that explorer tracks transfer events properly, and balances shows up correctly)
-/// This is also used to trigger liquify (every 10 seconds) -> prevents recursive liquify that can cause out of gas
-pub fn generate_transfer_event(
+/// Proposes, accepts or renounces the ownership of the contract
+pub fn update_ownership(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
+    action: cw_ownable::Action,
+) -> Result<Response, ContractError> {
+    let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)
+        .map_err(|err| ContractError::Std(StdError::generic_err(err.to_string())))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_ownership")
+        .add_attributes(ownership.into_attributes()))
+}
+
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    match msg {
+        MigrateMsg::Upgrade {} => upgrade(deps, env),
+        MigrateMsg::FromCw20Base { admin } => migrate_from_cw20_base(deps, env, admin),
+    }
+}
+
+/// Runs the state transforms from the stored version of the reflection token to this one
+pub fn upgrade(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let stored_version = ensure_upgrade(deps.storage)?;
+
+    // Transforms run oldest first, each one brings the state of its predecessors up to its version
+    if stored_version < Version::new(1, 1, 0) {
+        migrate_v1_1_0(deps.storage, deps.api, &env)?;
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", stored_version.to_string())
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
+/// Credits balances and allowances exported from a previous deployment, the imported supply counts
+/// against the cap
+pub fn import_state(
+    deps: DepsMut,
     env: Env,
-    from: String,
-    to: String,
-    amount: Uint128,
+    info: MessageInfo,
+    balances: Vec<Cw20Coin>,
+    allowances: Vec<ExportedAllowance>,
+    finish: bool,
 ) -> Result<Response, ContractError> {
-    let last_liquify = LAST_LIQUIFY.may_load(deps.storage)?.unwrap_or_default();
-    if info.sender.to_string() != env.contract.address.to_string() {
+    ensure_admin(&deps, &info)?;
+    if !IMPORT_OPEN.may_load(deps.storage)?.unwrap_or_default() {
         return Err(ContractError::Std(StdError::generic_err(
-            "Unauthorized: not contract",
+            "State import is closed",
         )));
     }
-    let mut messages = vec![];
-    // Allowed to liquify every 1 seconds
-    if env.block.time.seconds() > last_liquify + 1 {
-        LAST_LIQUIFY.save(deps.storage, &env.block.time.seconds())?;
-        let treasury = TREASURY.may_load(deps.storage)?.unwrap_or_default();
-        let liquify_msg = WasmMsg::Execute {
-            contract_addr: treasury.to_string(),
-            msg: to_json_binary(&TreasuryExecuteMsg::Liquify {})?,
-            funds: vec![],
-        };
-        messages.push(liquify_msg);
+
+    let mut token_info = TOKEN_INFO.load(deps.storage)?;
+    for row in balances.iter() {
+        let address = deps.api.addr_validate(&row.address)?;
+        BALANCES.update(
+            deps.storage,
+            &address,
+            |balance: Option<Uint128>| -> StdResult<_> {
+                Ok(balance.unwrap_or_default().checked_add(row.amount)?)
+            },
+        )?;
+        token_info.total_supply = token_info
+            .total_supply
+            .checked_add(row.amount)
+            .map_err(StdError::from)?;
+        sync_reflection(deps.storage, &env, &address)?;
+    }
+    if let Some(limit) = token_info.get_cap() {
+        if token_info.total_supply > limit {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Imported supply greater than cap",
+            )));
+        }
     }
+    TOKEN_INFO.save(deps.storage, &token_info)?;
+    checkpoint_total_supply(deps.storage, &env)?;
 
-    let res = Response::new()
-        .add_messages(messages)
-        .add_attribute("action", "transfer")
-        .add_attribute("from", from)
-        .add_attribute("to", to)
-        .add_attribute("amount", amount);
-    Ok(res)
+    for row in allowances.iter() {
+        let owner = deps.api.addr_validate(&row.owner)?;
+        let spender = deps.api.addr_validate(&row.spender)?;
+        ALLOWANCES.save(
+            deps.storage,
+            (&owner, &spender),
+            &AllowanceResponse {
+                allowance: row.allowance,
+                expires: row.expires,
+            },
+        )?;
+    }
+
+    if finish {
+        IMPORT_OPEN.remove(deps.storage);
+    }
+    Ok(Response::new()
+        .add_attribute("action", "import_state")
+        .add_attribute("balances", balances.len().to_string())
+        .add_attribute("allowances", allowances.len().to_string())
+        .add_attribute("finished", finish.to_string()))
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    Ok(Response::default())
+/// Converts the state of a cw20-base token into the reflection token. Both share the balance,
+/// allowance, token info and marketing storage, so only the reflection token state is added with
+/// the defaults of `instantiate`. Holders start accruing reflections from the migration on.
+/// Every holder is synced, which bounds the number of holders a single migration can handle
+pub fn migrate_from_cw20_base(
+    deps: DepsMut,
+    env: Env,
+    admin: String,
+) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CW20_BASE_CONTRACT_NAME {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Cannot migrate from contract {}",
+            stored.contract
+        ))));
+    }
+
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(&admin))?;
+    BUY_TAX_RATE.save(deps.storage, &Decimal::zero())?;
+    SELL_TAX_RATE.save(deps.storage, &Decimal::zero())?;
+    TRANSFER_TAX_RATE.save(deps.storage, &Decimal::zero())?;
+    REFLECTION_RATE.save(deps.storage, &Decimal::zero())?;
+    BURN_RATE.save(deps.storage, &Decimal::zero())?;
+    BUYBACK_ENABLE.save(deps.storage, &false)?;
+    // the token is already traded, closing trading would lock its pairs
+    TRADING_ENABLED.save(deps.storage, &true)?;
+    CLAWBACK_ENABLED.save(deps.storage, &false)?;
+    HOLDER_REFLECTION_RATE.save(deps.storage, &Decimal::zero())?;
+    MAX_TRANSFER_SUPPLY_RATE.save(deps.storage, &Decimal::one())?;
+    MAX_TOTAL_TAX.save(deps.storage, &Decimal::one())?;
+    REBASE.save(deps.storage, &false)?;
+
+    // registers the reflection shares, balance history and voting power of every holder
+    let holders = BALANCES
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<Addr>>>()?;
+    for holder in holders.iter() {
+        sync_reflection(deps.storage, &env, holder)?;
+    }
+    checkpoint_total_supply(deps.storage, &env)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new()
+        .add_attribute("action", "migrate_from_cw20_base")
+        .add_attribute("from_version", stored.version)
+        .add_attribute("to_version", CONTRACT_VERSION)
+        .add_attribute("holders", holders.len().to_string()))
+}
+
+/// Returns the stored cw2 version, rejecting other contracts and downgrades
+pub fn ensure_upgrade(storage: &dyn Storage) -> StdResult<Version> {
+    let stored = get_contract_version(storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(StdError::generic_err(format!(
+            "Cannot migrate from contract {}",
+            stored.contract
+        )));
+    }
+    let stored_version = parse_version(&stored.version)?;
+    if stored_version > parse_version(CONTRACT_VERSION)? {
+        return Err(StdError::generic_err(format!(
+            "Cannot downgrade from version {} to {}",
+            stored_version, CONTRACT_VERSION
+        )));
+    }
+    Ok(stored_version)
+}
+
+fn parse_version(version: &str) -> StdResult<Version> {
+    version
+        .parse()
+        .map_err(|err: semver::Error| StdError::generic_err(err.to_string()))
+}
+
+/// 1.1.0 moved the admin into cw-ownable, split the tax rate by direction, replaced the pairlist
+/// flags by pair configs and started tracking holders. Every holder is synced, which bounds the
+/// number of holders a single migration can handle
+fn migrate_v1_1_0(storage: &mut dyn Storage, api: &dyn Api, env: &Env) -> StdResult<()> {
+    if let Some(admin) = ADMIN.may_load(storage)? {
+        cw_ownable::initialize_owner(storage, api, Some(&admin))?;
+        ADMIN.remove(storage);
+    }
+
+    // 1.0.0 charged its rate on buys and sells only
+    if let Some(tax_rate) = LEGACY_TAX_RATE.may_load(storage)? {
+        BUY_TAX_RATE.save(storage, &tax_rate)?;
+        SELL_TAX_RATE.save(storage, &tax_rate)?;
+        TRANSFER_TAX_RATE.save(storage, &Decimal::zero())?;
+        LEGACY_TAX_RATE.remove(storage);
+    }
+
+    let pairs = LEGACY_PAIRLIST
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, bool)>>>()?;
+    for (pair, enabled) in pairs {
+        PAIRLIST.save(
+            storage,
+            pair,
+            &PairConfig {
+                enabled,
+                tax_rate: None,
+            },
+        )?;
+    }
+
+    // registers the reflection shares, balance history and voting power of every holder
+    let holders = BALANCES
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<Addr>>>()?;
+    for holder in holders.iter() {
+        sync_reflection(storage, env, holder)?;
+    }
+    checkpoint_total_supply(storage, env)
 }
 
 pub fn migrate_treasury(
@@ -737,18 +5014,36 @@ pub fn migrate_treasury(
     code_id: u64,
 ) -> Result<Response, ContractError> {
     let treasury = TREASURY.load(deps.storage)?;
-    let admin = ADMIN.load(deps.storage)?;
-    if info.sender.to_string() != admin.to_string() {
-        return Err(ContractError::Std(StdError::generic_err("Not admin")));
-    }
+    ensure_admin(&deps, &info)?;
 
-    Ok(
-        Response::new().add_message(CosmosMsg::Wasm(WasmMsg::Migrate {
+    Ok(Response::new()
+        .add_attribute("action", "migrate_treasury")
+        .add_attribute("code_id", code_id.to_string())
+        .add_message(CosmosMsg::Wasm(WasmMsg::Migrate {
             contract_addr: treasury,
             new_code_id: code_id,
-            msg: to_json_binary(&MigrateMsg {
+            msg: to_json_binary(&TreasuryMigrateMsg {
                 msg: "".to_string(),
             })?,
-        })),
-    )
+        })))
+}
+
+/// Returns the cw2 version with the package and the crate features the contract was built with
+pub fn query_contract_version(storage: &dyn Storage) -> StdResult<ContractVersionResponse> {
+    let version = get_contract_version(storage)?;
+    let features = [
+        ("backtraces", cfg!(feature = "backtraces")),
+        ("library", cfg!(feature = "library")),
+    ]
+    .iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(feature, _)| feature.to_string())
+    .collect();
+
+    Ok(ContractVersionResponse {
+        contract: version.contract,
+        version: version.version,
+        package: env!("CARGO_PKG_NAME").to_string(),
+        features,
+    })
 }
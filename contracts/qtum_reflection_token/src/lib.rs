@@ -1,2 +1,3 @@
 pub mod contract;
-pub mod msg;
\ No newline at end of file
+pub mod msg;
+pub mod overlay;
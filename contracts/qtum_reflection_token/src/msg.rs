@@ -1,250 +1 @@
-use cosmwasm_std::{Addr, Binary};
-use cosmwasm_std::{Decimal, StdError, StdResult, Uint128};
-use cw20::{Cw20Coin, Expiration, Logo, MinterResponse};
-use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
-
-#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
-pub struct InstantiateMarketingInfo {
-    pub project: Option<String>,
-    pub description: Option<String>,
-    pub marketing: Option<String>,
-    pub logo: Option<Logo>,
-}
-
-#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
-pub struct InstantiateMsg {
-    pub name: String,
-    pub symbol: String,
-    pub decimals: u8,
-    pub initial_balances: Vec<Cw20Coin>,
-    pub admin: String,
-    pub mint: Option<MinterResponse>,
-    pub marketing: Option<InstantiateMarketingInfo>,
-}
-
-#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
-pub struct TreasuryInstantiateMsg {
-    pub admin: String,
-    pub router: String,
-    pub token: Addr,
-}
-
-#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
-#[serde(rename_all = "snake_case")]
-pub enum ExecuteMsg {
-    /// Transfer is a base message to move tokens to another account without triggering actions
-    Transfer {
-        recipient: String,
-        amount: Uint128,
-    },
-    /// Burn is a base message to destroy tokens forever
-    Burn {
-        amount: Uint128,
-    },
-    /// Send is a base message to transfer tokens to a contract and trigger an action
-    /// on the receiving contract.
-    Send {
-        contract: String,
-        amount: Uint128,
-        msg: Binary,
-    },
-    /// Only with "approval" extension. Allows spender to access an additional amount tokens
-    /// from the owner's (env.sender) account. If expires is Some(), overwrites current allowance
-    /// expiration with this one.
-    IncreaseAllowance {
-        spender: String,
-        amount: Uint128,
-        expires: Option<Expiration>,
-    },
-    /// Only with "approval" extension. Lowers the spender's access of tokens
-    /// from the owner's (env.sender) account by amount. If expires is Some(), overwrites current
-    /// allowance expiration with this one.
-    DecreaseAllowance {
-        spender: String,
-        amount: Uint128,
-        expires: Option<Expiration>,
-    },
-    /// Only with "approval" extension. Transfers amount tokens from owner -> recipient
-    /// if `env.sender` has sufficient pre-approval.
-    TransferFrom {
-        owner: String,
-        recipient: String,
-        amount: Uint128,
-    },
-    /// Only with "approval" extension. Sends amount tokens from owner -> contract
-    /// if `env.sender` has sufficient pre-approval.
-    SendFrom {
-        owner: String,
-        contract: String,
-        amount: Uint128,
-        msg: Binary,
-    },
-    /// Only with "approval" extension. Destroys tokens forever
-    BurnFrom {
-        owner: String,
-        amount: Uint128,
-    },
-    /// Only with the "mintable" extension. If authorized, creates amount new tokens
-    /// and adds to the recipient balance.
-    Mint {
-        recipient: String,
-        amount: Uint128,
-    },
-    /// Only with the "marketing" extension. If authorized, updates marketing metadata.
-    /// Setting None/null for any of these will leave it unchanged.
-    /// Setting Some("") will clear this field on the contract storage
-    UpdateMarketing {
-        /// A URL pointing to the project behind this token.
-        project: Option<String>,
-        /// A longer description of the token and it's utility. Designed for tooltips or such
-        description: Option<String>,
-        /// The address (if any) who can update this data structure
-        marketing: Option<String>,
-    },
-    /// If set as the "marketing" role on the contract, upload a new URL, SVG, or PNG for the token
-    UploadLogo(Logo),
-    SetBuyBack {
-        enable: bool
-    },
-    SetTaxRate {
-        global_rate: Decimal,
-        reflection_rate: Decimal,
-        burn_rate: Decimal
-    },
-    SetPair {
-        contract: String,
-        enable: bool,
-    },
-    SetTreasury {
-        contract: String,
-    },
-    TransferEvent {
-        from: String,
-        to: String,
-        amount: Uint128,
-    },
-    MigrateTreasury {
-        code_id: u64,
-    },
-}
-
-impl InstantiateMsg {
-    pub fn get_cap(&self) -> Option<Uint128> {
-        self.mint.as_ref().and_then(|v| v.cap)
-    }
-
-    pub fn validate(&self) -> StdResult<()> {
-        // Check name, symbol, decimals
-        if !is_valid_name(&self.name) {
-            return Err(StdError::generic_err(
-                "Name is not in the expected format (3-50 UTF-8 bytes)",
-            ));
-        }
-        if !is_valid_symbol(&self.symbol) {
-            return Err(StdError::generic_err(
-                "Ticker symbol is not in expected format [a-zA-Z\\-]{3,12}",
-            ));
-        }
-        if self.decimals > 18 {
-            return Err(StdError::generic_err("Decimals must not exceed 18"));
-        }
-        Ok(())
-    }
-}
-
-fn is_valid_name(name: &str) -> bool {
-    let bytes = name.as_bytes();
-    if bytes.len() < 3 || bytes.len() > 50 {
-        return false;
-    }
-    true
-}
-
-fn is_valid_symbol(symbol: &str) -> bool {
-    let bytes = symbol.as_bytes();
-    if bytes.len() < 3 || bytes.len() > 12 {
-        return false;
-    }
-    for byte in bytes.iter() {
-        if (*byte != 45) && (*byte < 65 || *byte > 90) && (*byte < 97 || *byte > 122) {
-            return false;
-        }
-    }
-    true
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-#[serde(rename_all = "snake_case")]
-pub enum QueryMsg {
-    /// Returns the current balance of the given address, 0 if unset.
-    /// Return type: BalanceResponse.
-    Balance {
-        address: String,
-    },
-    /// Returns metadata on the contract - name, decimals, supply, etc.
-    /// Return type: TokenInfoResponse.
-    TokenInfo {},
-    /// Only with "mintable" extension.
-    /// Returns who can mint and the hard cap on maximum tokens after minting.
-    /// Return type: MinterResponse.
-    Minter {},
-    /// Only with "allowance" extension.
-    /// Returns how much spender can use from owner account, 0 if unset.
-    /// Return type: AllowanceResponse.
-    Allowance {
-        owner: String,
-        spender: String,
-    },
-    /// Only with "enumerable" extension (and "allowances")
-    /// Returns all allowances this owner has approved. Supports pagination.
-    /// Return type: AllAllowancesResponse.
-    AllAllowances {
-        owner: String,
-        start_after: Option<String>,
-        limit: Option<u32>,
-    },
-    /// Only with "enumerable" extension
-    /// Returns all accounts that have balances. Supports pagination.
-    /// Return type: AllAccountsResponse.
-    AllAccounts {
-        start_after: Option<String>,
-        limit: Option<u32>,
-    },
-    /// Only with "marketing" extension
-    /// Returns more metadata on the contract to display in the client:
-    /// - description, logo, project url, etc.
-    /// Return type: MarketingInfoResponse
-    MarketingInfo {},
-    /// Only with "marketing" extension
-    /// Downloads the embedded logo data (if stored on chain). Errors if no logo data is stored for this
-    /// contract.
-    /// Return type: DownloadLogoResponse.
-    DownloadLogo {},
-    QueryTax {
-        amount: Uint128,
-    },
-    QueryRates {},
-    GetWhitelist {
-        address: String,
-    },
-}
-
-#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
-pub struct QueryTaxResponse {
-    pub taxed_amount: Uint128,
-    pub after_tax: Uint128,
-    pub reflection_amount: Uint128,
-    pub liquidity_amount: Uint128,
-}
-
-#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
-pub struct MigrateMsg {
-    pub msg: String,
-}
-
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-#[serde(rename_all = "snake_case")]
-pub enum TreasuryExecuteMsg {
-    Liquify {},
-}
+pub use cw20_reflection_interface::msg::*;
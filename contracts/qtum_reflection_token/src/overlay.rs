@@ -0,0 +1,64 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{Order, Record, Storage};
+
+/// Read-only view of a storage with writes kept in memory, so queries can see state changes an
+/// execute would make without persisting them
+pub struct StorageOverlay<'a> {
+    base: &'a dyn Storage,
+    /// `None` marks a removed key
+    writes: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+impl<'a> StorageOverlay<'a> {
+    pub fn new(base: &'a dyn Storage) -> Self {
+        StorageOverlay {
+            base,
+            writes: BTreeMap::new(),
+        }
+    }
+}
+
+impl Storage for StorageOverlay<'_> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        match self.writes.get(key) {
+            Some(value) => value.clone(),
+            None => self.base.get(key),
+        }
+    }
+
+    fn range<'b>(
+        &'b self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'b> {
+        if self.writes.is_empty() {
+            return self.base.range(start, end, order);
+        }
+
+        let in_range =
+            |key: &[u8]| start.is_none_or(|start| key >= start) && end.is_none_or(|end| key < end);
+        let mut records: BTreeMap<Vec<u8>, Vec<u8>> =
+            self.base.range(start, end, Order::Ascending).collect();
+        for (key, value) in self.writes.iter().filter(|(key, _)| in_range(key)) {
+            match value {
+                Some(value) => records.insert(key.clone(), value.clone()),
+                None => records.remove(key),
+            };
+        }
+
+        match order {
+            Order::Ascending => Box::new(records.into_iter()),
+            Order::Descending => Box::new(records.into_iter().rev()),
+        }
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.writes.insert(key.to_vec(), Some(value.to_vec()));
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.writes.insert(key.to_vec(), None);
+    }
+}
@@ -1,13 +1,18 @@
 use std::env::current_dir;
 use std::fs::create_dir_all;
 
-use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+use cosmwasm_schema::{export_schema, export_schema_with_title, remove_schemas, schema_for};
+use cosmwasm_std::Addr;
 
-use cw20::{
-    AllAccountsResponse, AllAllowancesResponse, AllowanceResponse, BalanceResponse,
-    TokenInfoResponse,
+use cw20::BalanceResponse;
+use cw_ownable::Ownership;
+use qtum_treasury::msg::{
+    AuditLogResponse, ContractVersionResponse, Cw20HookMsg, EmergencyWithdrawal, ExecuteMsg,
+    HarvestConfig, IbcReflection, InstantiateMsg, KeeperBounty, KeeperBountyLimitsResponse,
+    LiquifyConfigResponse, LpLockResponse, LpStaking, LpWithdrawal, MigrateMsg,
+    PendingTaxesResponse, PriceGuardResponse, QueryMsg, ReflectionSplit, RouteHop,
+    SimulateLiquifyResponse, SlippageResponse, TreasuryStats,
 };
-use cw20_base::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -18,9 +23,31 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
-    export_schema(&schema_for!(AllowanceResponse), &out_dir);
+    export_schema(&schema_for!(MigrateMsg), &out_dir);
+    export_schema(&schema_for!(Cw20HookMsg), &out_dir);
+
+    export_schema(&schema_for!(AuditLogResponse), &out_dir);
     export_schema(&schema_for!(BalanceResponse), &out_dir);
-    export_schema(&schema_for!(TokenInfoResponse), &out_dir);
-    export_schema(&schema_for!(AllAllowancesResponse), &out_dir);
-    export_schema(&schema_for!(AllAccountsResponse), &out_dir);
+    export_schema(&schema_for!(ContractVersionResponse), &out_dir);
+    export_schema(&schema_for!(EmergencyWithdrawal), &out_dir);
+    export_schema(&schema_for!(HarvestConfig), &out_dir);
+    export_schema(&schema_for!(IbcReflection), &out_dir);
+    export_schema(&schema_for!(KeeperBounty), &out_dir);
+    export_schema(&schema_for!(KeeperBountyLimitsResponse), &out_dir);
+    export_schema(&schema_for!(LiquifyConfigResponse), &out_dir);
+    export_schema(&schema_for!(LpLockResponse), &out_dir);
+    export_schema(&schema_for!(LpStaking), &out_dir);
+    export_schema(&schema_for!(LpWithdrawal), &out_dir);
+    export_schema(&schema_for!(PendingTaxesResponse), &out_dir);
+    export_schema(&schema_for!(PriceGuardResponse), &out_dir);
+    export_schema(&schema_for!(SimulateLiquifyResponse), &out_dir);
+    export_schema(&schema_for!(SlippageResponse), &out_dir);
+    export_schema(&schema_for!(TreasuryStats), &out_dir);
+    export_schema_with_title(
+        &schema_for!(Vec<ReflectionSplit>),
+        &out_dir,
+        "ReflectionSplits",
+    );
+    export_schema_with_title(&schema_for!(Vec<RouteHop>), &out_dir, "LiquifyRoute");
+    export_schema_with_title(&schema_for!(Ownership<Addr>), &out_dir, "OwnershipResponse");
 }
@@ -3,30 +3,57 @@ use std::ops::{Div, Mul, Sub};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    coin, from_json, to_json_binary, Addr, Api, Binary, Decimal, Deps, DepsMut, Env, MessageInfo,
-    QuerierWrapper, QueryRequest, Response, StdError, StdResult, Storage, Uint128, WasmMsg,
-    WasmQuery,
+    coin, from_json, to_json_binary, Addr, Api, BankMsg, Binary, CosmosMsg, Decimal, Deps, DepsMut,
+    Env, Event, IbcMsg, IbcTimeout, MessageInfo, Order, QuerierWrapper, QueryRequest, Reply,
+    Response, StdError, StdResult, Storage, SubMsg, Uint128, WasmMsg, WasmQuery,
 };
 
+use cw0::must_pay;
 use cw20::{BalanceResponse, Cw20ExecuteMsg};
-use dojoswap::pair::SimulationResponse;
 
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 
 use crate::msg::{
-    Cw20HookMsg, Cw20ReceiveMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, TokenQueryMsg,
+    AuditEntry, AuditLogEntry, AuditLogResponse, ContractVersionResponse, Cw20HookMsg,
+    Cw20ReceiveMsg, Dex, EmergencyWithdrawal, ExecuteMsg, HarvestConfig, HarvestMode,
+    IbcReflection, IbcReflectionInput, Ics20TransferMsg, InstantiateMsg, KeeperBounty,
+    KeeperBountyLimits, KeeperBountyLimitsResponse, LiquifyConfigResponse, LiquifySkip,
+    LockerHookMsg, LockerQueryMsg, LpDestination, LpLockResponse, LpLocker, LpStaking,
+    LpWithdrawal, MigrateMsg, PendingLiquidity, PendingLpDestination, PendingTaxesResponse,
+    PriceGuard, PriceGuardResponse, PriceObservation, QueryMsg, RatesResponse, ReflectionSplit,
+    ReflectionSplitInput, RouteHop, SimulateLiquifyResponse, SlippageResponse, TokenExecuteMsg,
+    TokenQueryMsg, TreasuryStats,
 };
 use cw20_base::ContractError;
-use cw_storage_plus::Item;
-use dojoswap::asset::{Asset, AssetInfo, PairInfo};
-use dojoswap::pair::QueryMsg as PairQueryMsg;
+use cw_storage_plus::{Bound, Item, Map};
+use dojoswap::asset::{Asset, AssetInfo};
+use semver::Version;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "dojoswap:reflection";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// reply ids for submessages dispatched by this contract
+pub const LIQUIFY_SWAP_REPLY_ID: u64 = 1;
+
 pub const MIN_LIQUIFY_AMT: Item<Uint128> = Item::new("min_liquify_amt"); // minimum number of babyTOKEN before liquifying
+pub const MAX_LIQUIFY_AMT: Item<Uint128> = Item::new("max_liquify_amt"); // maximum number of babyTOKEN liquified per call
+pub const LIQUIFY_PORTION: Item<Decimal> = Item::new("liquify_portion"); // share of the babyTOKEN balance liquified per call
+pub const LIQUIFY_PAUSED: Item<bool> = Item::new("liquify_paused"); // taxes accumulate in the treasury while paused
+
+// delay between scheduling an LP withdrawal and executing it, set at instantiation
+pub const LP_WITHDRAWAL_DELAY: Item<u64> = Item::new("lp_withdrawal_delay");
+pub const DEFAULT_LP_WITHDRAWAL_DELAY: u64 = 30 * 24 * 60 * 60;
+pub const PENDING_LP_WITHDRAWAL: Item<LpWithdrawal> = Item::new("pending_lp_withdrawal");
+// LP destination changes wait for the LP withdrawal delay too, as they can redirect future LP tokens
+pub const PENDING_LP_DESTINATION: Item<PendingLpDestination> = Item::new("pending_lp_destination");
+// seconds between scheduling an emergency withdrawal and executing it, set at instantiation
+pub const EMERGENCY_WITHDRAWAL_DELAY: Item<u64> = Item::new("emergency_withdrawal_delay");
+pub const DEFAULT_EMERGENCY_WITHDRAWAL_DELAY: u64 = 90 * 24 * 60 * 60;
+pub const PENDING_EMERGENCY_WITHDRAWAL: Item<EmergencyWithdrawal> =
+    Item::new("pending_emergency_withdrawal");
 
+// legacy admin, moved to cw-ownable by `migrate`
 pub const ADMIN: Item<String> = Item::new("admin");
 pub const TOKEN: Item<Addr> = Item::new("token");
 pub const ROUTER: Item<String> = Item::new("router");
@@ -35,6 +62,52 @@ pub const LIQUIDITY_PAIR_CONTRACT: Item<String> = Item::new("liquidity_pair_cont
 pub const REFLECTION_PAIR_CONTRACT: Item<String> = Item::new("reflection_pair_contract");
 pub const LIQUIDITY_PAIR: Item<[AssetInfo; 2]> = Item::new("liquidity_pair");
 pub const REFLECTION_PAIR: Item<[AssetInfo; 2]> = Item::new("reflection_pair");
+// babyTOKEN <> DOJO pair used instead of the route when set
+pub const DIRECT_REFLECTION_PAIR: Item<String> = Item::new("direct_reflection_pair");
+// native denoms the admin can't withdraw
+pub const PROTECTED_DENOMS: Item<Vec<String>> = Item::new("protected_denoms");
+// lifetime counters of the treasury
+pub const STATS: Item<TreasuryStats> = Item::new("stats");
+// receives the reflection token bought by the treasury, defaults to the treasury itself
+pub const FEE_COLLECTOR: Item<Addr> = Item::new("fee_collector");
+// recipients the reflection token bought by the treasury is split between, instead of the fee collector
+pub const REFLECTION_SPLITS: Item<Vec<ReflectionSplit>> = Item::new("reflection_splits");
+// forwards the reflection token bought by the treasury over IBC, instead of the splits and fee collector
+pub const IBC_REFLECTION: Item<IbcReflection> = Item::new("ibc_reflection");
+pub const DEFAULT_IBC_TIMEOUT: u64 = 60 * 60;
+// swaps from babyTOKEN to the reflection token, defaults to babyTOKEN -> INJ -> DOJO
+pub const REFLECTION_ROUTE: Item<Vec<RouteHop>> = Item::new("reflection_route");
+// DEX of each pair, pairs set before DEX selection are DojoSwap pairs
+pub const LIQUIDITY_PAIR_DEX: Item<Dex> = Item::new("liquidity_pair_dex");
+pub const REFLECTION_PAIR_DEX: Item<Dex> = Item::new("reflection_pair_dex");
+// when enabled, the reflection share is swapped into the native quote denom and paid to holders via the token
+pub const NATIVE_REFLECTION: Item<bool> = Item::new("native_reflection");
+// babyTOKEN and INJ balance kept between the liquify swap and its reply
+pub const PENDING_LIQUIDITY: Item<PendingLiquidity> = Item::new("pending_liquidity");
+// passed to every swap and liquidity provision of the treasury, None leaves the DEX default
+pub const MAX_SPREAD: Item<Decimal> = Item::new("max_spread");
+pub const SLIPPAGE_TOLERANCE: Item<Decimal> = Item::new("slippage_tolerance");
+// skips liquifies while the spot price deviates too much from the time weighted average
+pub const PRICE_GUARD: Item<PriceGuard> = Item::new("price_guard");
+pub const PRICE_OBSERVATION: Item<PriceObservation> = Item::new("price_observation");
+// LP tokens received by liquifies are bonded here when set
+pub const LP_STAKING: Item<LpStaking> = Item::new("lp_staking");
+// LP tokens received by liquifies are locked in this third party locker when set
+pub const LP_LOCKER: Item<LpLocker> = Item::new("lp_locker");
+// LP tokens received by liquifies are sent here for good when set, instead of being bonded
+pub const LP_BURN_ADDRESS: Item<Addr> = Item::new("lp_burn_address");
+// claims and processes the farming rewards of the bonded LP tokens
+pub const HARVEST: Item<HarvestConfig> = Item::new("harvest");
+// paid to whoever calls `Liquify` when it actually liquifies, so keepers process the treasury when volume is low
+pub const KEEPER_BOUNTY: Item<KeeperBounty> = Item::new("keeper_bounty");
+pub const KEEPER_BOUNTY_LIMITS: Item<KeeperBountyLimits> = Item::new("keeper_bounty_limits");
+// block time in seconds of the last bounty paid, for the cooldown
+pub const KEEPER_BOUNTY_PAID_AT: Item<u64> = Item::new("keeper_bounty_paid_at");
+// append-only log of the privileged actions of the owner, keyed by an increasing id
+pub const AUDIT_LOG: Map<u64, AuditEntry> = Map::new("audit_log");
+pub const AUDIT_LOG_NEXT_ID: Item<u64> = Item::new("audit_log_next_id");
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -46,16 +119,51 @@ pub fn instantiate(
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     deps.api.addr_validate(&msg.admin.to_string())?;
     deps.api.addr_validate(&msg.router.to_string())?;
-    ADMIN.save(deps.storage, &msg.admin.to_string())?;
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(&msg.admin))?;
     ROUTER.save(deps.storage, &msg.router.to_string())?;
     TOKEN.save(deps.storage, &msg.token)?;
     MIN_LIQUIFY_AMT.save(deps.storage, &Uint128::zero())?;
+    LP_WITHDRAWAL_DELAY.save(
+        deps.storage,
+        &msg.lp_withdrawal_delay
+            .unwrap_or(DEFAULT_LP_WITHDRAWAL_DELAY),
+    )?;
+    EMERGENCY_WITHDRAWAL_DELAY.save(
+        deps.storage,
+        &msg.emergency_withdrawal_delay
+            .unwrap_or(DEFAULT_EMERGENCY_WITHDRAWAL_DELAY),
+    )?;
 
     Ok(Response::default())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    // liquifies and harvests are not privileged, even when sent by the owner
+    let audited = !matches!(
+        msg,
+        ExecuteMsg::Receive(_)
+            | ExecuteMsg::Liquify { .. }
+            | ExecuteMsg::Harvest { .. }
+            | ExecuteMsg::ZapLiquidity { .. }
+    );
+    let sender = info.sender.clone();
+    let was_owner = audited && cw_ownable::is_owner(deps.storage, &sender)?;
+
+    let res = execute_msg(deps.branch(), env.clone(), info, msg)?;
+    // the new owner accepting the ownership is recorded too
+    if audited && (was_owner || cw_ownable::is_owner(deps.storage, &sender)?) {
+        record_audit(deps.storage, &env, &sender, &res)?;
+    }
+    Ok(res)
+}
+
+pub fn execute_msg(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
@@ -69,17 +177,109 @@ pub fn execute(
         ExecuteMsg::SetReflectionPair {
             asset_infos,
             pair_contract,
-        } => set_reflection_pair(deps, env, info, asset_infos, pair_contract),
+            dex,
+        } => set_reflection_pair(deps, env, info, asset_infos, pair_contract, dex),
         ExecuteMsg::SetLiquidityPair {
             asset_infos,
             pair_contract,
-        } => set_liquidity_pair(deps, env, info, asset_infos, pair_contract),
+            dex,
+        } => set_liquidity_pair(deps, env, info, asset_infos, pair_contract, dex),
+        ExecuteMsg::SetReflectionRoute { operations } => {
+            set_reflection_route(deps, info, operations)
+        }
+        ExecuteMsg::SetDirectReflectionPair { pair_contract } => {
+            set_direct_reflection_pair(deps, info, pair_contract)
+        }
+        ExecuteMsg::SetRouter { address } => set_router(deps, info, address),
+        ExecuteMsg::SetFeeCollector { address } => set_fee_collector(deps, info, address),
+        ExecuteMsg::SetReflectionSplits { splits } => set_reflection_splits(deps, info, splits),
+        ExecuteMsg::SetIbcReflection { config } => set_ibc_reflection(deps, info, config),
+        ExecuteMsg::ForwardIbcReflection { balance_before } => {
+            forward_ibc_reflection(deps, env, info, balance_before)
+        }
+        ExecuteMsg::SplitReflection { balance_before } => {
+            split_reflection(deps, env, info, balance_before)
+        }
+        ExecuteMsg::RecordReflection {
+            recipient,
+            balance_before,
+        } => record_reflection(deps, env, info, recipient, balance_before),
+        ExecuteMsg::RecordLiquidity { lp_balance_before } => {
+            record_liquidity(deps, env, info, lp_balance_before)
+        }
         ExecuteMsg::SetMinLiquify { min_liquify_amt } => {
             set_min_liquify_amt(deps, env, info, min_liquify_amt)
         }
-        // ExecuteMsg::SetToken { address } => set_token(deps, env, info, address),
-        ExecuteMsg::Liquify {} => liquify_treasury(&deps.querier, env, deps.storage),
+        ExecuteMsg::SetMaxLiquify { max_liquify_amt } => {
+            set_max_liquify_amt(deps, info, max_liquify_amt)
+        }
+        ExecuteMsg::SetLiquifyPortion { portion } => set_liquify_portion(deps, info, portion),
+        ExecuteMsg::PauseLiquify {} => set_liquify_paused(deps, info, true),
+        ExecuteMsg::ResumeLiquify {} => set_liquify_paused(deps, info, false),
+        ExecuteMsg::SetToken { address, sweep_to } => set_token(deps, env, info, address, sweep_to),
+        ExecuteMsg::Liquify {} => {
+            // liquify calls triggered by token transfers are not paid
+            let keeper = if info.sender == TOKEN.load(deps.storage)? {
+                None
+            } else {
+                Some(info.sender)
+            };
+            liquify_treasury(&deps.querier, env, deps.storage, keeper)
+        }
+        ExecuteMsg::SetLpStaking { staking } => set_lp_staking(deps, env, info, staking),
+        ExecuteMsg::StakeLiquidity { lp_balance_before } => {
+            stake_liquidity(deps, env, info, lp_balance_before)
+        }
+        ExecuteMsg::SetLpBurnAddress { address } => set_lp_burn_address(deps, env, info, address),
+        ExecuteMsg::BurnLiquidity { lp_balance_before } => {
+            burn_liquidity(deps, env, info, lp_balance_before)
+        }
+        ExecuteMsg::SetLpLocker { locker } => set_lp_locker(deps, env, info, locker),
+        ExecuteMsg::LockLiquidity { lp_balance_before } => {
+            lock_liquidity(deps, env, info, lp_balance_before)
+        }
+        ExecuteMsg::CancelLpDestination {} => cancel_lp_destination(deps, info),
+        ExecuteMsg::ExecuteLpDestination {} => execute_lp_destination(deps, env, info),
+        ExecuteMsg::SetHarvest { harvest } => set_harvest(deps, info, harvest),
+        ExecuteMsg::Harvest {} => harvest(deps, env),
+        ExecuteMsg::ProcessHarvest {
+            reward_balance_before,
+        } => process_harvest(deps, env, info, reward_balance_before),
+        ExecuteMsg::CompoundHarvest {
+            token_balance_before,
+        } => compound_harvest(deps, env, info, token_balance_before),
+        ExecuteMsg::ZapLiquidity {} => zap_liquidity(deps, env, info),
+        ExecuteMsg::ProvideZapLiquidity {
+            token_balance_before,
+            quote_amount,
+        } => provide_zap_liquidity(deps, env, info, token_balance_before, quote_amount),
+        ExecuteMsg::SetKeeperBounty { bounty, limits } => {
+            set_keeper_bounty(deps, info, bounty, limits)
+        }
+        ExecuteMsg::SetPriceGuard { guard } => set_price_guard(deps, info, guard),
+        ExecuteMsg::SetSlippage {
+            max_spread,
+            slippage_tolerance,
+        } => set_slippage(deps, info, max_spread, slippage_tolerance),
         ExecuteMsg::WithdrawToken { token } => withdraw_token(deps, env, info, token),
+        ExecuteMsg::WithdrawNative { denom } => withdraw_native(deps, env, info, denom),
+        ExecuteMsg::SetProtectedDenoms { denoms } => set_protected_denoms(deps, info, denoms),
+        ExecuteMsg::ScheduleLpWithdrawal { amount, recipient } => {
+            schedule_lp_withdrawal(deps, env, info, amount, recipient)
+        }
+        ExecuteMsg::CancelLpWithdrawal {} => cancel_lp_withdrawal(deps, info),
+        ExecuteMsg::ExecuteLpWithdrawal {} => execute_lp_withdrawal(deps, env, info),
+        ExecuteMsg::ScheduleEmergencyWithdraw { recipient } => {
+            schedule_emergency_withdraw(deps, env, info, recipient)
+        }
+        ExecuteMsg::CancelEmergencyWithdraw {} => cancel_emergency_withdraw(deps, info),
+        ExecuteMsg::ExecuteEmergencyWithdraw {} => execute_emergency_withdraw(deps, env, info),
+        ExecuteMsg::UpdateOwnership(action) => update_ownership(deps, env, info, action),
+        ExecuteMsg::SetNativeReflection { enable } => set_native_reflection(deps, info, enable),
+        ExecuteMsg::ForwardNativeReflection {
+            denom,
+            balance_before,
+        } => forward_native_reflection(deps, env, info, denom, balance_before),
     }
 }
 
@@ -90,6 +290,84 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             let token: Addr = TOKEN.load(deps.storage)?;
             to_json_binary(&query_balance(&deps.querier, token, env.contract.address)?)
         }
+        QueryMsg::Stats {} => to_json_binary(&STATS.may_load(deps.storage)?.unwrap_or_default()),
+        QueryMsg::PendingTaxes {} => to_json_binary(&query_pending_taxes(deps, env)?),
+        QueryMsg::SimulateLiquify {} => to_json_binary(&query_simulate_liquify(deps, env)?),
+        QueryMsg::Ownership {} => to_json_binary(&cw_ownable::get_ownership(deps.storage)?),
+        QueryMsg::ContractVersion {} => to_json_binary(&query_contract_version(deps.storage)?),
+        QueryMsg::ReflectionRoute {} => {
+            let token = TOKEN.load(deps.storage)?;
+            to_json_binary(&reflection_route(deps.storage, &token)?)
+        }
+        QueryMsg::DirectReflectionPair {} => {
+            to_json_binary(&DIRECT_REFLECTION_PAIR.may_load(deps.storage)?)
+        }
+        QueryMsg::ReflectionSplits {} => to_json_binary(
+            &REFLECTION_SPLITS
+                .may_load(deps.storage)?
+                .unwrap_or_default(),
+        ),
+        QueryMsg::AuditLog { start_after, limit } => {
+            to_json_binary(&query_audit_log(deps, start_after, limit)?)
+        }
+        QueryMsg::IbcReflection {} => to_json_binary(&IBC_REFLECTION.may_load(deps.storage)?),
+        QueryMsg::Router {} => to_json_binary(&ROUTER.load(deps.storage)?),
+        QueryMsg::FeeCollector {} => to_json_binary(&FEE_COLLECTOR.may_load(deps.storage)?),
+        QueryMsg::ProtectedDenoms {} => {
+            to_json_binary(&PROTECTED_DENOMS.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::LiquifyConfig {} => to_json_binary(&LiquifyConfigResponse {
+            min_liquify_amt: MIN_LIQUIFY_AMT.may_load(deps.storage)?.unwrap_or_default(),
+            max_liquify_amt: MAX_LIQUIFY_AMT.may_load(deps.storage)?,
+            portion: LIQUIFY_PORTION
+                .may_load(deps.storage)?
+                .unwrap_or(Decimal::one()),
+            paused: LIQUIFY_PAUSED.may_load(deps.storage)?.unwrap_or_default(),
+        }),
+        QueryMsg::LpStaking {} => to_json_binary(&LP_STAKING.may_load(deps.storage)?),
+        QueryMsg::PendingLpWithdrawal {} => {
+            to_json_binary(&PENDING_LP_WITHDRAWAL.may_load(deps.storage)?)
+        }
+        QueryMsg::PendingLpDestination {} => {
+            to_json_binary(&PENDING_LP_DESTINATION.may_load(deps.storage)?)
+        }
+        QueryMsg::PendingEmergencyWithdraw {} => {
+            to_json_binary(&PENDING_EMERGENCY_WITHDRAWAL.may_load(deps.storage)?)
+        }
+        QueryMsg::LpLock {} => {
+            let locker = LP_LOCKER.may_load(deps.storage)?;
+            let lock = match &locker {
+                Some(locker) => Some(deps.querier.query_wasm_smart(
+                    &locker.contract,
+                    &LockerQueryMsg::LockInfo {
+                        owner: env.contract.address.to_string(),
+                    },
+                )?),
+                None => None,
+            };
+            to_json_binary(&LpLockResponse { locker, lock })
+        }
+        QueryMsg::LpBurnAddress {} => to_json_binary(&LP_BURN_ADDRESS.may_load(deps.storage)?),
+        QueryMsg::Harvest {} => to_json_binary(&HARVEST.may_load(deps.storage)?),
+        QueryMsg::KeeperBounty {} => to_json_binary(&KEEPER_BOUNTY.may_load(deps.storage)?),
+        QueryMsg::KeeperBountyLimits {} => {
+            let limits = KEEPER_BOUNTY_LIMITS
+                .may_load(deps.storage)?
+                .unwrap_or_default();
+            to_json_binary(&KeeperBountyLimitsResponse {
+                min_amount: limits.min_amount,
+                cooldown: limits.cooldown,
+                last_paid_at: KEEPER_BOUNTY_PAID_AT.may_load(deps.storage)?,
+            })
+        }
+        QueryMsg::PriceGuard {} => to_json_binary(&PriceGuardResponse {
+            guard: PRICE_GUARD.may_load(deps.storage)?,
+            observation: PRICE_OBSERVATION.may_load(deps.storage)?,
+        }),
+        QueryMsg::Slippage {} => to_json_binary(&SlippageResponse {
+            max_spread: MAX_SPREAD.may_load(deps.storage)?,
+            slippage_tolerance: SLIPPAGE_TOLERANCE.may_load(deps.storage)?,
+        }),
     }
 }
 
@@ -106,11 +384,11 @@ pub fn receive_cw20(
     match from_json(&cw20_msg.msg) {
         Ok(Cw20HookMsg::Liquify {}) => {
             // only token contract can execute this message
-            if token.to_string() != api.addr_validate(info.sender.as_str())? {
+            if token != api.addr_validate(info.sender.as_str())? {
                 return Err(ContractError::Unauthorized {});
             }
 
-            liquify_treasury(&querier, env.clone(), storage)
+            liquify_treasury(querier, env.clone(), storage, None)
         }
         Err(_) => Err(ContractError::Unauthorized {}),
     }
@@ -120,335 +398,2226 @@ pub fn receive_cw20(
 /// 1. Liquify babyTOKEN into LP tokens
 /// 2. Reflect babyTOKEN into DOJO to be sent into fee collector wallet
 /// 3. Burn a portion of babyTOKEN
+///
+/// `keeper` is paid the keeper bounty, if any, when the treasury is liquified
 pub fn liquify_treasury(
     querier: &QuerierWrapper,
     env: Env,
     storage: &mut dyn Storage,
+    keeper: Option<Addr>,
 ) -> Result<Response, ContractError> {
-    let querier = querier.clone();
+    let querier = *querier;
+
+    let plan = plan_liquify(&querier, storage, &env, keeper.is_some())?;
+    if let Some(observation) = &plan.observation {
+        PRICE_OBSERVATION.save(storage, observation)?;
+    }
+    match (plan.skipped, plan.observation) {
+        // Transfers keep working and taxes stay in the treasury
+        (Some(LiquifySkip::Paused), _) => {
+            return Ok(Response::new().add_attribute("action", "liquify_paused"));
+        }
+        (Some(LiquifySkip::PriceDeviation), Some(observation)) => {
+            return Ok(Response::new()
+                .add_attribute("action", "liquify_skipped")
+                .add_attribute("price", observation.price.to_string())
+                .add_attribute("twap", observation.twap.to_string()));
+        }
+        (Some(_), _) => return Ok(Response::default()),
+        (None, _) => {}
+    }
 
     let router = ROUTER.may_load(storage)?.unwrap_or_default();
     // let admin = ADMIN.may_load(storage)?.unwrap_or_default();
     let token = TOKEN.load(storage)?;
-    let contract_balance = query_balance(&querier, token.clone(), env.contract.address.clone())?;
 
     let liquidity_pair = LIQUIDITY_PAIR.may_load(storage)?.unwrap();
     let liquidity_pair_contract = LIQUIDITY_PAIR_CONTRACT.may_load(storage)?.unwrap();
-    let reflection_pair = REFLECTION_PAIR.may_load(storage)?.unwrap();
-    let min_liquify_amt = MIN_LIQUIFY_AMT
+    let liquidity_dex = LIQUIDITY_PAIR_DEX
         .may_load(storage)?
-        .unwrap_or(Uint128::zero());
+        .unwrap_or_default()
+        .adapter();
+    let reflection_dex = REFLECTION_PAIR_DEX
+        .may_load(storage)?
+        .unwrap_or_default()
+        .adapter();
+    let max_spread = MAX_SPREAD.may_load(storage)?;
 
-    // Short circuit if there's not enough contract balance to liquify
-    if contract_balance < min_liquify_amt {
-        return Ok(Response::default());
+    let mut bounty_messages: Vec<CosmosMsg> = vec![];
+    // keepers are only paid for enough work, once per cooldown
+    let bounty = match keeper {
+        Some(_) if keeper_bounty_due(storage, &env, plan.amount)? => {
+            KEEPER_BOUNTY.may_load(storage)?
+        }
+        _ => None,
+    };
+    if let (Some(keeper), Some(bounty)) = (keeper, bounty) {
+        match bounty {
+            // paid out of the babyTOKEN being liquified
+            KeeperBounty::Token { amount } if !plan.token_bounty.is_zero() => {
+                bounty_messages.push(
+                    WasmMsg::Execute {
+                        contract_addr: token.to_string(),
+                        msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                            recipient: keeper.to_string(),
+                            amount,
+                        })?,
+                        funds: vec![],
+                    }
+                    .into(),
+                );
+            }
+            KeeperBounty::Native { denom, amount } => {
+                let balance = querier.query_balance(env.contract.address.clone(), &denom)?;
+                if balance.amount >= amount {
+                    bounty_messages.push(
+                        BankMsg::Send {
+                            to_address: keeper.to_string(),
+                            amount: vec![coin(amount.u128(), denom)],
+                        }
+                        .into(),
+                    );
+                }
+            }
+            _ => {}
+        }
+        if !bounty_messages.is_empty() {
+            KEEPER_BOUNTY_PAID_AT.save(storage, &env.block.time.seconds())?;
+        }
     }
 
-    // Loads all the tax rates from the modified CW20 token
-    let (_tax_rate, reflection_rate, burn_rate, _transfer_rate): (
-        Decimal,
-        Decimal,
-        Decimal,
-        Decimal,
-    ) = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: token.to_string(),
-        msg: to_json_binary(&TokenQueryMsg::QueryRates {})?,
-    }))?;
-
-    let mut messages: Vec<WasmMsg> = vec![];
+    let mut messages: Vec<SubMsg> = vec![];
 
-    let reflect_amt = contract_balance.mul(reflection_rate);
-    let burn_amt = contract_balance.mul(burn_rate);
-    let liquidity_amt = contract_balance.sub(reflect_amt).sub(burn_amt);
+    let reflect_amt = plan.reflection_amount;
+    let burn_amt = plan.burn_amount;
+    let liquidity_amt = plan.liquidity_amount;
     // Taxes - 100000
     // Reflection - 50000
     // Burn - 10000
     // Liq amt - 40000
     if liquidity_amt > Uint128::zero() {
-        // Swaps half of babyTOKEN into INJ
-        let swap_amount = liquidity_amt.div(Uint128::from(2u128));
-        // Increases allowance of babyTOKEN to liquidity pair contract (allows adding liquidity)
-        messages.push(WasmMsg::Execute {
-            contract_addr: token.to_string(),
-            msg: to_json_binary(&cw20::Cw20ExecuteMsg::IncreaseAllowance {
-                spender: liquidity_pair_contract.clone(),
-                amount: liquidity_amt.sub(swap_amount),
-                expires: None,
-            })?,
-            funds: vec![],
-        });
-
-        // Simulates swapping of half of babyTOKEN into INJ
-        let simulation = simulate(
+        messages.extend(liquidity_messages(
             &querier,
-            liquidity_pair_contract.clone(),
-            &Asset {
-                amount: swap_amount,
-                info: liquidity_pair[0].clone(),
-            },
-        )?;
-        // We formulate a swap message to swap babyTOKEN into INJ
-        messages.push(WasmMsg::Execute {
+            storage,
+            &env,
+            &token,
+            liquidity_amt,
+        )?);
+    }
+
+    let native_reflection = NATIVE_REFLECTION.may_load(storage)?.unwrap_or_default();
+    if reflect_amt > Uint128::zero() && native_reflection {
+        // 1. swap babyTOKEN into INJ through the liquidity pair
+        // 2. forward the INJ received to the token, which distributes it to holders
+        let denom = match liquidity_pair[1].clone() {
+            AssetInfo::NativeToken { denom } => denom,
+            AssetInfo::Token { .. } => {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "native reflection requires a native quote asset",
+                )));
+            }
+        };
+        let balance_before = querier.query_balance(env.contract.address.clone(), &denom)?;
+
+        messages.push(SubMsg::new(WasmMsg::Execute {
             contract_addr: token.to_string(),
             msg: to_json_binary(&cw20::Cw20ExecuteMsg::Send {
                 contract: liquidity_pair_contract.to_string(),
-                amount: swap_amount,
-                msg: to_json_binary(&dojoswap::pair::Cw20HookMsg::Swap {
-                    belief_price: None,
-                    max_spread: None,
-                    to: None,
-                    deadline: None,
-                })?,
+                amount: reflect_amt,
+                msg: liquidity_dex.swap_hook(max_spread, None)?,
             })?,
             funds: vec![],
-        });
-
-        // Formulate variable to allow us to add liquidity to the pool
-        let assets: [Asset; 2] = [
-            Asset {
-                amount: liquidity_amt.sub(swap_amount), // add remaining amount of babyTOKEN as liquidity
-                info: liquidity_pair[0].clone(),        // babyTOKEN
-            },
-            Asset {
-                amount: simulation.return_amount, // add simulated INJ return amount to be added as liquidity
-                info: liquidity_pair[1].clone(),  // INJ
-            },
-        ];
-
-        // We formulate a ProvideLiquidity message to add babyTOKEN liquidity to the pool
-        match reflection_pair[1].clone() {
-            AssetInfo::NativeToken { denom } => {
-                // If the asset is a native token, we provide liquidity via a denom message
-                messages.push(WasmMsg::Execute {
-                    contract_addr: liquidity_pair_contract.to_string(),
-                    msg: to_json_binary(&dojoswap::pair::ExecuteMsg::ProvideLiquidity {
-                        assets,
-                        receiver: None,
-                        deadline: None,
-                        slippage_tolerance: None,
-                    })?,
-                    funds: vec![coin(simulation.return_amount.u128(), denom)],
-                });
-            }
-            AssetInfo::Token { contract_addr } => {
-                // If asset is a CW20, we provide liquidity via increase allowance message
-                messages.push(WasmMsg::Execute {
-                    contract_addr,
-                    msg: to_json_binary(&Cw20ExecuteMsg::IncreaseAllowance {
-                        spender: liquidity_pair_contract.to_string(),
-                        amount: simulation.return_amount,
-                        expires: None,
+        }));
+        // Runs after the swap so the amount forwarded is what was actually received
+        messages.push(SubMsg::new(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_json_binary(&ExecuteMsg::ForwardNativeReflection {
+                denom,
+                balance_before: balance_before.amount,
+            })?,
+            funds: vec![],
+        }));
+    } else if reflect_amt > Uint128::zero() {
+        let (reflection_to, reflection_msg) = reflection_destination(&querier, storage, &env)?;
+        match DIRECT_REFLECTION_PAIR.may_load(storage)? {
+            Some(direct_pair) => {
+                // Swaps babyTOKEN into reflection target token (DOJO) in a single hop
+                messages.push(SubMsg::new(WasmMsg::Execute {
+                    contract_addr: token.to_string(),
+                    msg: to_json_binary(&cw20::Cw20ExecuteMsg::Send {
+                        contract: direct_pair,
+                        amount: reflect_amt,
+                        msg: reflection_dex.swap_hook(max_spread, reflection_to)?,
                     })?,
                     funds: vec![],
-                });
-                messages.push(WasmMsg::Execute {
-                    contract_addr: liquidity_pair_contract.to_string(),
-                    msg: to_json_binary(&dojoswap::pair::ExecuteMsg::ProvideLiquidity {
-                        assets,
-                        receiver: None,
-                        deadline: None,
-                        slippage_tolerance: None,
+                }));
+            }
+            None => {
+                // 1. swap babyToken into INJ, or along the configured route
+                // 2. swap INJ into reflection target token (DOJO)
+                // 3. sends reflection token to fee collector
+                let route = reflection_route(storage, &token)?;
+                let minimum_receive =
+                    route_minimum_receive(&querier, storage, router.clone(), reflect_amt, &route)?;
+                // Executes a sell of babyTOKEN along the route into reflection target token (DOJO) via router contract
+                messages.push(SubMsg::new(WasmMsg::Execute {
+                    contract_addr: token.to_string(),
+                    msg: to_json_binary(&cw20::Cw20ExecuteMsg::Send {
+                        contract: router.to_string(),
+                        amount: reflect_amt,
+                        // reflected token is sent to the fee collector, or here into treasury
+                        msg: reflection_dex.swap_operations_hook(
+                            &route,
+                            minimum_receive,
+                            reflection_to,
+                        )?,
                     })?,
                     funds: vec![],
-                });
+                }));
             }
-        };
-    }
-
-    if reflect_amt > Uint128::zero() {
-        // 1. swap babyToken into INJ
-        // 2. swap INJ into reflection target token (DOJO)
-        // 3. sends reflection token to fee collector
-        let operations = vec![
-            dojoswap::router::SwapOperation::DojoSwap {
-                offer_asset_info: dojoswap::asset::AssetInfo::Token {
-                    contract_addr: token.to_string(),
-                },
-                ask_asset_info: reflection_pair[1].clone(),
-            },
-            dojoswap::router::SwapOperation::DojoSwap {
-                offer_asset_info: reflection_pair[1].clone(),
-                ask_asset_info: reflection_pair[0].clone(),
-            },
-        ];
-        // Executes a sell of babyTOKEN into INJ, then INJ into reflection target token (DOJO) via router contract
-        messages.push(WasmMsg::Execute {
-            contract_addr: token.to_string(),
-            msg: to_json_binary(&cw20::Cw20ExecuteMsg::Send {
-                contract: router.to_string(),
-                amount: reflect_amt,
-                msg: to_json_binary(&dojoswap::router::ExecuteMsg::ExecuteSwapOperations {
-                    operations,
-                    minimum_receive: None,
-                    to: None, // reflected token is sent here into treasury
-                    deadline: None,
-                })?,
-            })?,
-            funds: vec![],
-        });
+        }
+        // Runs after the swap so only the reflection token bought is split and counted
+        messages.push(SubMsg::new(reflection_msg));
     }
 
     if burn_amt > Uint128::zero() {
         // Burns babyTOKEN
-        messages.push(WasmMsg::Execute {
+        messages.push(SubMsg::new(WasmMsg::Execute {
             contract_addr: token.to_string(),
             msg: to_json_binary(&cw20::Cw20ExecuteMsg::Burn { amount: burn_amt })?,
             funds: vec![],
-        });
+        }));
     }
 
-    let res = Response::new().add_messages(messages);
+    let mut stats = STATS.may_load(storage)?.unwrap_or_default();
+    stats.total_liquified += plan.amount;
+    stats.total_burned += burn_amt;
+    stats.liquify_count += 1;
+    stats.last_liquify = Some(env.block.time.seconds());
+    STATS.save(storage, &stats)?;
+
+    let res = Response::new()
+        .add_submessages(messages)
+        .add_messages(bounty_messages)
+        .add_event(
+            Event::new("liquify")
+                .add_attribute("from", env.contract.address)
+                .add_attribute("asset", token)
+                .add_attribute("amount", plan.amount)
+                .add_attribute("liquidity_amount", liquidity_amt)
+                .add_attribute("reflection_amount", reflect_amt)
+                .add_attribute("burn_amount", burn_amt),
+        );
 
     Ok(res)
 }
 
-/// Used to simulate swap operations against DojoSwap pair
-pub fn simulate(
-    querier: &QuerierWrapper,
-    pair_contract: String,
-    offer_asset: &Asset,
-) -> StdResult<SimulationResponse> {
-    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: pair_contract,
-        msg: to_json_binary(&PairQueryMsg::Simulation {
-            offer_asset: offer_asset.clone(),
-        })?,
-    }))
+/// babyTOKEN amounts of a liquify, planned from the treasury balance, the liquify config and the
+/// token rates
+#[derive(Default)]
+pub struct LiquifyPlan {
+    pub skipped: Option<LiquifySkip>,
+    /// Price sampled by the price guard, saved by the liquify
+    pub observation: Option<PriceObservation>,
+    /// babyTOKEN processed, the token keeper bounty excluded
+    pub amount: Uint128,
+    pub token_bounty: Uint128,
+    pub liquidity_amount: Uint128,
+    pub reflection_amount: Uint128,
+    pub burn_amount: Uint128,
 }
 
-pub fn query_balance(querier: &QuerierWrapper, token: Addr, address: Addr) -> StdResult<Uint128> {
-    let response: BalanceResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: token.to_string(),
-        msg: to_json_binary(&cw20::Cw20QueryMsg::Balance {
-            address: address.to_string(),
-        })?,
-    }))?;
-    Ok(response.balance)
-}
+/// Plans a liquify without executing anything. `keeper` tells whether the caller is paid the
+/// token keeper bounty out of the liquified babyTOKEN
+pub fn plan_liquify(
+    querier: &QuerierWrapper,
+    storage: &dyn Storage,
+    env: &Env,
+    keeper: bool,
+) -> StdResult<LiquifyPlan> {
+    if LIQUIFY_PAUSED.may_load(storage)?.unwrap_or_default() {
+        return Ok(LiquifyPlan {
+            skipped: Some(LiquifySkip::Paused),
+            ..LiquifyPlan::default()
+        });
+    }
 
-// Check below for pair ordering
-// 1. This contract address (babyToken)
-// 2. The quote token (inj)
-pub fn set_liquidity_pair(
-    deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
-    asset_infos: [AssetInfo; 2],
-    pair_contract: String,
-) -> Result<Response, ContractError> {
-    ensure_admin(&deps, &info)?;
-    let reflection_pair = REFLECTION_PAIR.load(deps.storage);
-    LIQUIDITY_PAIR.save(deps.storage, &asset_infos)?;
-    LIQUIDITY_PAIR_CONTRACT.save(deps.storage, &pair_contract)?;
+    let token = TOKEN.load(storage)?;
+    let mut contract_balance = query_balance(querier, token.clone(), env.contract.address.clone())?;
+    let min_liquify_amt = MIN_LIQUIFY_AMT
+        .may_load(storage)?
+        .unwrap_or(Uint128::zero());
 
-    match reflection_pair {
-        Err(_) => {}
-        Ok(asset_info) => {
-            let unbound = asset_info;
-            let reflect_1 = unbound.get(1).unwrap();
-            if !reflect_1.eq(&asset_infos[1]) {
-                return Err(ContractError::Std(StdError::generic_err(
-                    "asset_infos[1] do not match",
-                )));
-            }
+    // Short circuit if there's not enough contract balance to liquify
+    if contract_balance < min_liquify_amt || contract_balance.is_zero() {
+        return Ok(LiquifyPlan {
+            skipped: Some(LiquifySkip::BelowMinimum),
+            ..LiquifyPlan::default()
+        });
+    }
+
+    // Short circuit if the pool price was moved away from its average, e.g. right before a liquify
+    let mut observation = None;
+    if let Some(guard) = PRICE_GUARD.may_load(storage)? {
+        let sample = observe_price(querier, storage, env, &guard)?;
+        let deviation = if sample.price > sample.twap {
+            sample.price - sample.twap
+        } else {
+            sample.twap - sample.price
+        };
+        let moved = deviation
+            .checked_div(sample.twap)
+            .is_ok_and(|deviation| deviation > guard.max_deviation);
+        observation = Some(sample);
+        if moved {
+            return Ok(LiquifyPlan {
+                skipped: Some(LiquifySkip::PriceDeviation),
+                observation,
+                ..LiquifyPlan::default()
+            });
         }
-    };
+    }
 
-    let response: PairInfo = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: pair_contract,
-        msg: to_json_binary(&PairQueryMsg::Pair {})?,
-    }))?;
+    // Large balances are processed in chunks, the remainder stays for the next calls
+    if let Some(portion) = LIQUIFY_PORTION.may_load(storage)? {
+        contract_balance = contract_balance.mul(portion);
+    }
+    if let Some(max_liquify_amt) = MAX_LIQUIFY_AMT.may_load(storage)? {
+        contract_balance = contract_balance.min(max_liquify_amt);
+    }
+    if contract_balance.is_zero() {
+        return Ok(LiquifyPlan {
+            skipped: Some(LiquifySkip::BelowMinimum),
+            observation,
+            ..LiquifyPlan::default()
+        });
+    }
 
-    match response.asset_infos[0].clone() {
-        AssetInfo::Token { contract_addr } => {
-            deps.api.addr_validate(&contract_addr.to_string())?;
-        }
-        AssetInfo::NativeToken { denom: _ } => {
-            return Err(ContractError::Std(StdError::generic_err(
-                "token should be cw20",
-            )));
+    let mut token_bounty = Uint128::zero();
+    if let (true, Some(KeeperBounty::Token { amount })) = (keeper, KEEPER_BOUNTY.may_load(storage)?)
+    {
+        if amount < contract_balance && keeper_bounty_due(storage, env, contract_balance - amount)?
+        {
+            contract_balance -= amount;
+            token_bounty = amount;
         }
-    };
-
-    LIQUIDTY_TOKEN.save(deps.storage, &response.liquidity_token.to_string())?;
+    }
 
-    response
-        .asset_infos
-        .iter()
-        .find(|info| info.equal(&asset_infos[0]))
-        .ok_or(StdError::generic_err("asset_infos[0] is not valid"))?;
+    let rates = query_rates(querier, &token)?;
+    let reflection_amount = contract_balance.mul(rates.reflection_rate);
+    let burn_amount = contract_balance.mul(rates.burn_rate);
+    Ok(LiquifyPlan {
+        skipped: None,
+        observation,
+        amount: contract_balance,
+        token_bounty,
+        liquidity_amount: contract_balance.sub(reflection_amount).sub(burn_amount),
+        reflection_amount,
+        burn_amount,
+    })
+}
 
-    response
-        .asset_infos
-        .iter()
-        .find(|info| info.equal(&asset_infos[1]))
-        .ok_or(StdError::generic_err("asset_infos[1] is not valid"))?;
+/// Whether a keeper bounty can be paid for a liquify processing `amount` babyTOKEN, the token
+/// bounty excluded
+fn keeper_bounty_due(storage: &dyn Storage, env: &Env, amount: Uint128) -> StdResult<bool> {
+    let limits = KEEPER_BOUNTY_LIMITS.may_load(storage)?.unwrap_or_default();
+    if amount.is_zero() || amount < limits.min_amount {
+        return Ok(false);
+    }
+    Ok(match KEEPER_BOUNTY_PAID_AT.may_load(storage)? {
+        Some(paid_at) => env.block.time.seconds() >= paid_at.saturating_add(limits.cooldown),
+        None => true,
+    })
+}
 
-    Ok(Response::default())
+/// Loads all the tax rates from the modified CW20 token
+pub fn query_rates(querier: &QuerierWrapper, token: &Addr) -> StdResult<RatesResponse> {
+    querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: token.to_string(),
+        msg: to_json_binary(&TokenQueryMsg::QueryRates {})?,
+    }))
 }
 
-// Check below for pair ordering
-// 1. The reflection token (DOJO)
-// 2. The quote token (inj)
-pub fn set_reflection_pair(
-    deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
-    asset_infos: [AssetInfo; 2],
-    pair_contract: String,
-) -> Result<Response, ContractError> {
-    ensure_admin(&deps, &info)?;
-    let liquidity_pair = LIQUIDITY_PAIR.load(deps.storage);
-    REFLECTION_PAIR.save(deps.storage, &asset_infos)?;
-    REFLECTION_PAIR_CONTRACT.save(deps.storage, &pair_contract)?;
+/// Splits the whole treasury balance the way a liquify does, ignoring the portion, the max liquify
+/// amount and the keeper bounty
+pub fn query_pending_taxes(deps: Deps, env: Env) -> StdResult<PendingTaxesResponse> {
+    let token = TOKEN.load(deps.storage)?;
+    let balance = query_balance(&deps.querier, token.clone(), env.contract.address)?;
+    let min_liquify_amt = MIN_LIQUIFY_AMT
+        .may_load(deps.storage)?
+        .unwrap_or(Uint128::zero());
+    let rates = query_rates(&deps.querier, &token)?;
 
-    match liquidity_pair {
-        Err(_) => {}
-        Ok(asset_info) => {
-            let unbound = asset_info;
-            let liquidity_1 = unbound.get(1).unwrap();
-            if !liquidity_1.eq(&asset_infos[1]) {
-                return Err(ContractError::Std(StdError::generic_err(
-                    "asset_infos[1] do not match",
-                )));
-            }
-        }
+    let reflection_amount = balance.mul(rates.reflection_rate);
+    let burn_amount = balance.mul(rates.burn_rate);
+    Ok(PendingTaxesResponse {
+        balance,
+        liquidity_amount: balance.sub(reflection_amount).sub(burn_amount),
+        reflection_amount,
+        burn_amount,
+        min_liquify_amt,
+        above_min_liquify_amt: !balance.is_zero() && balance >= min_liquify_amt,
+    })
+}
+
+/// Plans a liquify called by a keeper and simulates its swaps against the current reserves
+pub fn query_simulate_liquify(deps: Deps, env: Env) -> StdResult<SimulateLiquifyResponse> {
+    let plan = plan_liquify(&deps.querier, deps.storage, &env, true)?;
+    let mut response = SimulateLiquifyResponse {
+        skipped: plan.skipped,
+        amount: plan.amount,
+        token_bounty: plan.token_bounty,
+        liquidity_amount: plan.liquidity_amount,
+        reflection_amount: plan.reflection_amount,
+        burn_amount: plan.burn_amount,
+        ..SimulateLiquifyResponse::default()
     };
+    if response.skipped.is_some() {
+        return Ok(response);
+    }
 
-    let response: PairInfo = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: pair_contract,
-        msg: to_json_binary(&PairQueryMsg::Pair {})?,
-    }))?;
+    let token = TOKEN.load(deps.storage)?;
+    let liquidity_pair = LIQUIDITY_PAIR.load(deps.storage)?;
+    let liquidity_pair_contract = LIQUIDITY_PAIR_CONTRACT.load(deps.storage)?;
+    let liquidity_dex = LIQUIDITY_PAIR_DEX
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .adapter();
 
-    response
-        .asset_infos
-        .iter()
-        .find(|info| info.equal(&asset_infos[0]))
-        .ok_or(StdError::generic_err("asset_infos[0] is not valid"))?;
+    if !plan.liquidity_amount.is_zero() {
+        response.swap_amount = plan.liquidity_amount.div(Uint128::from(2u128));
+        response.quote_amount = liquidity_dex.simulate_swap(
+            &deps.querier,
+            liquidity_pair_contract.clone(),
+            Asset {
+                info: liquidity_pair[0].clone(),
+                amount: response.swap_amount,
+            },
+        )?;
 
-    response
-        .asset_infos
-        .iter()
-        .find(|info| info.equal(&asset_infos[1]))
-        .ok_or(StdError::generic_err("asset_infos[1] is not valid"))?;
+        // The pair mints LP in proportion to the smaller side of the deposit
+        let pool = liquidity_dex.query_pool(&deps.querier, liquidity_pair_contract.clone())?;
+        let token_reserve = pool_reserve(&pool.assets, &liquidity_pair[0]) + response.swap_amount;
+        let quote_reserve =
+            pool_reserve(&pool.assets, &liquidity_pair[1]).saturating_sub(response.quote_amount);
+        let token_share = plan
+            .liquidity_amount
+            .sub(response.swap_amount)
+            .checked_multiply_ratio(pool.total_share, token_reserve);
+        let quote_share = response
+            .quote_amount
+            .checked_multiply_ratio(pool.total_share, quote_reserve);
+        if let (Ok(token_share), Ok(quote_share)) = (token_share, quote_share) {
+            response.lp_amount = token_share.min(quote_share);
+        }
+    }
 
-    Ok(Response::default())
+    if !plan.reflection_amount.is_zero() {
+        let reflection_dex = REFLECTION_PAIR_DEX
+            .may_load(deps.storage)?
+            .unwrap_or_default()
+            .adapter();
+        let offer_asset = Asset {
+            info: AssetInfo::Token {
+                contract_addr: token.to_string(),
+            },
+            amount: plan.reflection_amount,
+        };
+        response.reflection_output = if NATIVE_REFLECTION
+            .may_load(deps.storage)?
+            .unwrap_or_default()
+        {
+            liquidity_dex.simulate_swap(&deps.querier, liquidity_pair_contract, offer_asset)?
+        } else if let Some(direct_pair) = DIRECT_REFLECTION_PAIR.may_load(deps.storage)? {
+            reflection_dex.simulate_swap(&deps.querier, direct_pair, offer_asset)?
+        } else {
+            let route = reflection_route(deps.storage, &token)?;
+            reflection_dex.simulate_swap_operations(
+                &deps.querier,
+                ROUTER.may_load(deps.storage)?.unwrap_or_default(),
+                plan.reflection_amount,
+                &route,
+            )?
+        };
+    }
+
+    Ok(response)
 }
 
-/// Sets minimum babyTOKEN required to liquify
-pub fn set_min_liquify_amt(
-    deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
+/// The router bypasses the pair max spread, so its swaps are protected by a minimum receive
+/// derived from a simulation of the route minus the max spread
+pub fn route_minimum_receive(
+    querier: &QuerierWrapper,
+    storage: &dyn Storage,
+    router: String,
+    offer_amount: Uint128,
+    route: &[RouteHop],
+) -> StdResult<Option<Uint128>> {
+    match MAX_SPREAD.may_load(storage)? {
+        Some(max_spread) => {
+            let simulation = REFLECTION_PAIR_DEX
+                .may_load(storage)?
+                .unwrap_or_default()
+                .adapter()
+                .simulate_swap_operations(querier, router, offer_amount, route)?;
+            Ok(Some(simulation.mul(Decimal::one() - max_spread)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Swaps half of `liquidity_amt` babyTOKEN into INJ, the reply of the swap provides liquidity
+/// with the other half
+pub fn liquidity_messages(
+    querier: &QuerierWrapper,
+    storage: &mut dyn Storage,
+    env: &Env,
+    token: &Addr,
+    liquidity_amt: Uint128,
+) -> StdResult<Vec<SubMsg>> {
+    let liquidity_pair = LIQUIDITY_PAIR.load(storage)?;
+    let liquidity_pair_contract = LIQUIDITY_PAIR_CONTRACT.load(storage)?;
+    let liquidity_dex = LIQUIDITY_PAIR_DEX
+        .may_load(storage)?
+        .unwrap_or_default()
+        .adapter();
+    let max_spread = MAX_SPREAD.may_load(storage)?;
+
+    let mut messages: Vec<SubMsg> = vec![];
+    // Swaps half of babyTOKEN into INJ
+    let swap_amount = liquidity_amt.div(Uint128::from(2u128));
+    // Increases allowance of babyTOKEN to liquidity pair contract (allows adding liquidity)
+    messages.push(SubMsg::new(WasmMsg::Execute {
+        contract_addr: token.to_string(),
+        msg: to_json_binary(&cw20::Cw20ExecuteMsg::IncreaseAllowance {
+            spender: liquidity_pair_contract.clone(),
+            amount: liquidity_amt.sub(swap_amount),
+            expires: None,
+        })?,
+        funds: vec![],
+    }));
+
+    // Remembers the INJ balance before the swap, the reply provides liquidity with the
+    // amount actually received instead of a simulated one
+    PENDING_LIQUIDITY.save(
+        storage,
+        &PendingLiquidity {
+            token_amount: liquidity_amt.sub(swap_amount),
+            quote_balance_before: query_asset_balance(
+                querier,
+                &liquidity_pair[1],
+                env.contract.address.clone(),
+            )?,
+        },
+    )?;
+    // We formulate a swap message to swap babyTOKEN into INJ
+    messages.push(SubMsg::reply_on_success(
+        WasmMsg::Execute {
+            contract_addr: token.to_string(),
+            msg: to_json_binary(&cw20::Cw20ExecuteMsg::Send {
+                contract: liquidity_pair_contract.to_string(),
+                amount: swap_amount,
+                msg: liquidity_dex.swap_hook(max_spread, None)?,
+            })?,
+            funds: vec![],
+        },
+        LIQUIFY_SWAP_REPLY_ID,
+    ));
+
+    Ok(messages)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        LIQUIFY_SWAP_REPLY_ID => provide_liquidity(deps, env),
+        id => Err(ContractError::Std(StdError::generic_err(format!(
+            "Unknown reply id: {}",
+            id
+        )))),
+    }
+}
+
+/// Second step of `liquify_treasury`, runs once the babyTOKEN -> INJ swap succeeded.
+/// Provides liquidity with the remaining babyTOKEN and the INJ the swap actually returned
+pub fn provide_liquidity(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let pending = PENDING_LIQUIDITY.load(deps.storage)?;
+    PENDING_LIQUIDITY.remove(deps.storage);
+
+    let liquidity_pair = LIQUIDITY_PAIR.load(deps.storage)?;
+    let liquidity_pair_contract = LIQUIDITY_PAIR_CONTRACT.load(deps.storage)?;
+    let liquidity_dex = LIQUIDITY_PAIR_DEX
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .adapter();
+    let slippage_tolerance = SLIPPAGE_TOLERANCE.may_load(deps.storage)?;
+    let quote_balance = query_asset_balance(
+        &deps.querier,
+        &liquidity_pair[1],
+        env.contract.address.clone(),
+    )?;
+    let quote_amount = quote_balance.saturating_sub(pending.quote_balance_before);
+    if quote_amount.is_zero() {
+        return Ok(Response::default());
+    }
+
+    // Formulate variable to allow us to add liquidity to the pool
+    let assets: [Asset; 2] = [
+        Asset {
+            amount: pending.token_amount, // add remaining amount of babyTOKEN as liquidity
+            info: liquidity_pair[0].clone(), // babyTOKEN
+        },
+        Asset {
+            amount: quote_amount,            // add INJ received from the swap as liquidity
+            info: liquidity_pair[1].clone(), // INJ
+        },
+    ];
+
+    let mut messages: Vec<WasmMsg> = vec![];
+    // We formulate a ProvideLiquidity message to add babyTOKEN liquidity to the pool
+    match liquidity_pair[1].clone() {
+        AssetInfo::NativeToken { denom } => {
+            // If the asset is a native token, we provide liquidity via a denom message
+            messages.push(WasmMsg::Execute {
+                contract_addr: liquidity_pair_contract,
+                msg: liquidity_dex.provide_liquidity(assets, slippage_tolerance)?,
+                funds: vec![coin(quote_amount.u128(), denom)],
+            });
+        }
+        AssetInfo::Token { contract_addr } => {
+            // If asset is a CW20, we provide liquidity via increase allowance message
+            messages.push(WasmMsg::Execute {
+                contract_addr,
+                msg: to_json_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: liquidity_pair_contract.to_string(),
+                    amount: quote_amount,
+                    expires: None,
+                })?,
+                funds: vec![],
+            });
+            messages.push(WasmMsg::Execute {
+                contract_addr: liquidity_pair_contract,
+                msg: liquidity_dex.provide_liquidity(assets, slippage_tolerance)?,
+                funds: vec![],
+            });
+        }
+    };
+    // Runs after the liquidity is provided so the LP tokens minted are counted
+    let lp_balance_before = query_balance(
+        &deps.querier,
+        Addr::unchecked(LIQUIDTY_TOKEN.load(deps.storage)?),
+        env.contract.address.clone(),
+    )?;
+    messages.push(WasmMsg::Execute {
+        contract_addr: env.contract.address.to_string(),
+        msg: to_json_binary(&ExecuteMsg::RecordLiquidity { lp_balance_before })?,
+        funds: vec![],
+    });
+
+    // Runs after the liquidity is provided so the LP tokens minted are burned, locked or bonded too.
+    // LP tokens the treasury held before stay there, they can only leave through a withdrawal
+    if LP_BURN_ADDRESS.may_load(deps.storage)?.is_some() {
+        messages.push(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_json_binary(&ExecuteMsg::BurnLiquidity { lp_balance_before })?,
+            funds: vec![],
+        });
+    } else if LP_LOCKER.may_load(deps.storage)?.is_some() {
+        messages.push(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_json_binary(&ExecuteMsg::LockLiquidity { lp_balance_before })?,
+            funds: vec![],
+        });
+    } else if LP_STAKING.may_load(deps.storage)?.is_some() {
+        messages.push(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_json_binary(&ExecuteMsg::StakeLiquidity { lp_balance_before })?,
+            funds: vec![],
+        });
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "provide_liquidity")
+        .add_attribute("token_amount", pending.token_amount)
+        .add_attribute("quote_amount", quote_amount))
+}
+
+/// Internal callback of `provide_liquidity`.
+/// Bonds the LP tokens minted since `lp_balance_before` into the LP staking contract
+pub fn stake_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_balance_before: Uint128,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let staking = match LP_STAKING.may_load(deps.storage)? {
+        Some(staking) => staking,
+        None => return Ok(Response::default()),
+    };
+    let liquidity_token = LIQUIDTY_TOKEN.load(deps.storage)?;
+    let amount = query_balance(
+        &deps.querier,
+        Addr::unchecked(&liquidity_token),
+        env.contract.address,
+    )?
+    .saturating_sub(lp_balance_before);
+    if amount.is_zero() {
+        return Ok(Response::default());
+    }
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: liquidity_token,
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: staking.contract,
+                amount,
+                msg: staking.bond_msg,
+            })?,
+            funds: vec![],
+        })
+        .add_attribute("action", "stake_liquidity")
+        .add_attribute("amount", amount))
+}
+
+/// Internal callback of `provide_liquidity`.
+/// Sends the LP tokens minted since `lp_balance_before` to the LP burn address, locking the
+/// liquidity for good
+pub fn burn_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_balance_before: Uint128,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let burn_address = match LP_BURN_ADDRESS.may_load(deps.storage)? {
+        Some(burn_address) => burn_address,
+        None => return Ok(Response::default()),
+    };
+    let liquidity_token = LIQUIDTY_TOKEN.load(deps.storage)?;
+    let amount = query_balance(
+        &deps.querier,
+        Addr::unchecked(&liquidity_token),
+        env.contract.address,
+    )?
+    .saturating_sub(lp_balance_before);
+    if amount.is_zero() {
+        return Ok(Response::default());
+    }
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: liquidity_token,
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: burn_address.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        })
+        .add_attribute("action", "burn_liquidity")
+        .add_attribute("recipient", burn_address)
+        .add_attribute("amount", amount))
+}
+
+/// Internal callback of `provide_liquidity`.
+/// Locks the LP tokens minted since `lp_balance_before` in the LP locker until its unlock time, the
+/// treasury being the beneficiary
+pub fn lock_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_balance_before: Uint128,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let locker = match LP_LOCKER.may_load(deps.storage)? {
+        Some(locker) => locker,
+        None => return Ok(Response::default()),
+    };
+    let liquidity_token = LIQUIDTY_TOKEN.load(deps.storage)?;
+    let amount = query_balance(
+        &deps.querier,
+        Addr::unchecked(&liquidity_token),
+        env.contract.address.clone(),
+    )?
+    .saturating_sub(lp_balance_before);
+    if amount.is_zero() {
+        return Ok(Response::default());
+    }
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: liquidity_token,
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: locker.contract,
+                amount,
+                msg: to_json_binary(&LockerHookMsg::Lock {
+                    unlock_time: locker.unlock_time,
+                    beneficiary: env.contract.address.to_string(),
+                })?,
+            })?,
+            funds: vec![],
+        })
+        .add_attribute("action", "lock_liquidity")
+        .add_attribute("amount", amount)
+        .add_attribute("unlock_time", locker.unlock_time.to_string()))
+}
+
+/// Claims the farming rewards of the LP tokens bonded by the treasury, then compounds them or
+/// swaps them into the reflection token
+pub fn harvest(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let staking = LP_STAKING
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("LP staking is not set"))?;
+    let harvest = HARVEST
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("harvest is not set"))?;
+    let reward_balance_before =
+        query_asset_balance(&deps.querier, &harvest.reward, env.contract.address.clone())?;
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: staking.contract,
+            msg: harvest.claim_msg,
+            funds: vec![],
+        })
+        // Runs after the claim so only the rewards claimed are processed
+        .add_message(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_json_binary(&ExecuteMsg::ProcessHarvest {
+                reward_balance_before,
+            })?,
+            funds: vec![],
+        })
+        .add_attribute("action", "harvest"))
+}
+
+/// Internal callback of `harvest`.
+/// Swaps the rewards claimed since `reward_balance_before` along the harvest route
+pub fn process_harvest(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    reward_balance_before: Uint128,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let harvest = HARVEST.load(deps.storage)?;
+    let reward_balance =
+        query_asset_balance(&deps.querier, &harvest.reward, env.contract.address.clone())?;
+    let amount = reward_balance.saturating_sub(reward_balance_before);
+    // rewards paid in the reflection token are already where reflections are collected
+    if amount.is_zero() || harvest.route.is_empty() {
+        return Ok(Response::new()
+            .add_attribute("action", "process_harvest")
+            .add_attribute("amount", amount));
+    }
+
+    let router = ROUTER.load(deps.storage)?;
+    let minimum_receive = route_minimum_receive(
+        &deps.querier,
+        deps.storage,
+        router.clone(),
+        amount,
+        &harvest.route,
+    )?;
+    // bought babyTOKEN stays here to be compounded
+    let (to, reflection_msg) = match harvest.mode {
+        HarvestMode::Compound => (None, None),
+        HarvestMode::Reflect => {
+            let (to, reflection_msg) = reflection_destination(&deps.querier, deps.storage, &env)?;
+            (to, Some(reflection_msg))
+        }
+    };
+    let swap_msg = REFLECTION_PAIR_DEX
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .adapter()
+        .swap_operations_hook(&harvest.route, minimum_receive, to)?;
+    let mut messages: Vec<WasmMsg> = vec![match harvest.reward {
+        // the router takes the same payload as an execute message along with native funds
+        AssetInfo::NativeToken { denom } => WasmMsg::Execute {
+            contract_addr: router,
+            msg: swap_msg,
+            funds: vec![coin(amount.u128(), denom)],
+        },
+        AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+            contract_addr,
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: router,
+                amount,
+                msg: swap_msg,
+            })?,
+            funds: vec![],
+        },
+    }];
+
+    // Runs after the swap so only the reflection token bought is split and counted
+    if let Some(reflection_msg) = reflection_msg {
+        messages.push(reflection_msg);
+    }
+    if harvest.mode == HarvestMode::Compound {
+        let token = TOKEN.load(deps.storage)?;
+        // Runs after the swap so only the babyTOKEN bought with the rewards is compounded
+        messages.push(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_json_binary(&ExecuteMsg::CompoundHarvest {
+                token_balance_before: query_balance(
+                    &deps.querier,
+                    token,
+                    env.contract.address.clone(),
+                )?,
+            })?,
+            funds: vec![],
+        });
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "process_harvest")
+        .add_attribute("amount", amount))
+}
+
+/// Internal callback of `process_harvest` in compound mode.
+/// Provides liquidity with the babyTOKEN bought since `token_balance_before`
+pub fn compound_harvest(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_balance_before: Uint128,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let token = TOKEN.load(deps.storage)?;
+    let token_balance = query_balance(&deps.querier, token.clone(), env.contract.address.clone())?;
+    let amount = token_balance.saturating_sub(token_balance_before);
+    if amount.is_zero() {
+        return Ok(Response::default());
+    }
+
+    Ok(Response::new()
+        .add_submessages(liquidity_messages(
+            &deps.querier,
+            deps.storage,
+            &env,
+            &token,
+            amount,
+        )?)
+        .add_attribute("action", "compound_harvest")
+        .add_attribute("amount", amount))
+}
+
+/// Swaps half of the attached quote denom into babyTOKEN on the liquidity pair, the callback
+/// provides liquidity with the babyTOKEN bought and the other half
+pub fn zap_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    if LIQUIFY_PAUSED.may_load(deps.storage)?.unwrap_or_default() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "liquify is paused",
+        )));
+    }
+
+    let liquidity_pair = LIQUIDITY_PAIR.load(deps.storage)?;
+    let denom = match liquidity_pair[1].clone() {
+        AssetInfo::NativeToken { denom } => denom,
+        AssetInfo::Token { .. } => {
+            return Err(ContractError::Std(StdError::generic_err(
+                "zaps require a native quote asset",
+            )));
+        }
+    };
+    let amount = must_pay(&info, &denom).map_err(|err| StdError::generic_err(err.to_string()))?;
+    let swap_amount = amount.div(Uint128::from(2u128));
+    if swap_amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "zap amount is too small",
+        )));
+    }
+
+    let token = TOKEN.load(deps.storage)?;
+    let liquidity_dex = LIQUIDITY_PAIR_DEX
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .adapter();
+    let max_spread = MAX_SPREAD.may_load(deps.storage)?;
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: LIQUIDITY_PAIR_CONTRACT.load(deps.storage)?,
+            msg: liquidity_dex.swap(
+                Asset {
+                    info: liquidity_pair[1].clone(),
+                    amount: swap_amount,
+                },
+                max_spread,
+                None,
+            )?,
+            funds: vec![coin(swap_amount.u128(), denom)],
+        })
+        // Runs after the swap so only the babyTOKEN bought is provided
+        .add_message(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_json_binary(&ExecuteMsg::ProvideZapLiquidity {
+                token_balance_before: query_balance(
+                    &deps.querier,
+                    token,
+                    env.contract.address.clone(),
+                )?,
+                quote_amount: amount.sub(swap_amount),
+            })?,
+            funds: vec![],
+        })
+        .add_attribute("action", "zap_liquidity")
+        .add_attribute("amount", amount))
+}
+
+/// Internal callback of `zap_liquidity`.
+/// Provides liquidity with the babyTOKEN bought since `token_balance_before` and `quote_amount`
+pub fn provide_zap_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_balance_before: Uint128,
+    quote_amount: Uint128,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let token = TOKEN.load(deps.storage)?;
+    let token_balance = query_balance(&deps.querier, token.clone(), env.contract.address.clone())?;
+    let token_amount = token_balance.saturating_sub(token_balance_before);
+    if token_amount.is_zero() {
+        return Ok(Response::default());
+    }
+
+    // Same second step as a liquify, the quote kept aside is all the quote balance above `before`
+    let liquidity_pair = LIQUIDITY_PAIR.load(deps.storage)?;
+    let quote_balance = query_asset_balance(
+        &deps.querier,
+        &liquidity_pair[1],
+        env.contract.address.clone(),
+    )?;
+    PENDING_LIQUIDITY.save(
+        deps.storage,
+        &PendingLiquidity {
+            token_amount,
+            quote_balance_before: quote_balance.saturating_sub(quote_amount),
+        },
+    )?;
+    // Increases allowance of babyTOKEN to liquidity pair contract (allows adding liquidity)
+    let allowance = WasmMsg::Execute {
+        contract_addr: token.to_string(),
+        msg: to_json_binary(&cw20::Cw20ExecuteMsg::IncreaseAllowance {
+            spender: LIQUIDITY_PAIR_CONTRACT.load(deps.storage)?,
+            amount: token_amount,
+            expires: None,
+        })?,
+        funds: vec![],
+    };
+    let res = provide_liquidity(deps, env)?;
+
+    Ok(Response::new()
+        .add_message(allowance)
+        .add_submessages(res.messages)
+        .add_attributes(res.attributes)
+        .add_events(res.events)
+        .add_attribute("action", "provide_zap_liquidity")
+        .add_attribute("token_amount", token_amount))
+}
+
+/// Samples the babyTOKEN spot price of the liquidity pair and folds the previous sample into the
+/// time weighted average, weighted by how long it held over the guard window.
+/// The sample is saved by the caller
+pub fn observe_price(
+    querier: &QuerierWrapper,
+    storage: &dyn Storage,
+    env: &Env,
+    guard: &PriceGuard,
+) -> StdResult<PriceObservation> {
+    let liquidity_pair = LIQUIDITY_PAIR.load(storage)?;
+    let liquidity_pair_contract = LIQUIDITY_PAIR_CONTRACT.load(storage)?;
+    let pool = LIQUIDITY_PAIR_DEX
+        .may_load(storage)?
+        .unwrap_or_default()
+        .adapter()
+        .query_pool(querier, liquidity_pair_contract)?;
+    let price = Decimal::checked_from_ratio(
+        pool_reserve(&pool.assets, &liquidity_pair[1]),
+        pool_reserve(&pool.assets, &liquidity_pair[0]),
+    )
+    .map_err(|_| StdError::generic_err("liquidity pair has no reserves"))?;
+
+    let now = env.block.time.seconds();
+    let twap = match PRICE_OBSERVATION.may_load(storage)? {
+        Some(last) => {
+            let elapsed = now.saturating_sub(last.timestamp).min(guard.window);
+            let weight = Decimal::from_ratio(elapsed, guard.window);
+            last.twap * (Decimal::one() - weight) + last.price * weight
+        }
+        None => price,
+    };
+
+    Ok(PriceObservation {
+        price,
+        twap,
+        timestamp: now,
+    })
+}
+
+/// Returns the reserve of `info` in a pair pool
+pub fn pool_reserve(pool: &[Asset], info: &AssetInfo) -> Uint128 {
+    pool.iter()
+        .find(|asset| asset.info.equal(info))
+        .map(|asset| asset.amount)
+        .unwrap_or_default()
+}
+
+/// Returns the recipient of the reflection token bought by a swap, and the callback forwarding it
+/// over IBC, splitting it when reflection splits are set, or else counting it. The callback must
+/// run after the swap
+pub fn reflection_destination(
+    querier: &QuerierWrapper,
+    storage: &dyn Storage,
+    env: &Env,
+) -> StdResult<(Option<String>, WasmMsg)> {
+    let reflection_token = REFLECTION_PAIR.load(storage)?[0].clone();
+
+    let ibc_reflection = IBC_REFLECTION.may_load(storage)?;
+    if ibc_reflection.is_none() && REFLECTION_SPLITS.may_load(storage)?.is_none() {
+        let recipient = FEE_COLLECTOR
+            .may_load(storage)?
+            .unwrap_or_else(|| env.contract.address.clone());
+        let balance_before = query_asset_balance(querier, &reflection_token, recipient.clone())?;
+        let to = Some(recipient.clone()).filter(|recipient| recipient != env.contract.address);
+        return Ok((
+            to.map(|addr| addr.to_string()),
+            WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_json_binary(&ExecuteMsg::RecordReflection {
+                    recipient,
+                    balance_before,
+                })?,
+                funds: vec![],
+            },
+        ));
+    }
+
+    // the reflection token is bought into the treasury, then forwarded or split
+    let balance_before =
+        query_asset_balance(querier, &reflection_token, env.contract.address.clone())?;
+    let callback = if ibc_reflection.is_some() {
+        ExecuteMsg::ForwardIbcReflection { balance_before }
+    } else {
+        ExecuteMsg::SplitReflection { balance_before }
+    };
+    Ok((
+        None,
+        WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_json_binary(&callback)?,
+            funds: vec![],
+        },
+    ))
+}
+
+/// Internal callback of the reflection swaps when no reflection split is set.
+/// Counts the reflection token `recipient` received since `balance_before`
+pub fn record_reflection(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: Addr,
+    balance_before: Uint128,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let reflection_token = REFLECTION_PAIR.load(deps.storage)?[0].clone();
+    let balance = query_asset_balance(&deps.querier, &reflection_token, recipient.clone())?;
+    let amount = balance.saturating_sub(balance_before);
+
+    let mut stats = STATS.may_load(deps.storage)?.unwrap_or_default();
+    stats.total_reflected += amount;
+    STATS.save(deps.storage, &stats)?;
+
+    Ok(Response::new()
+        .add_event(reflection_event(
+            &env.contract.address,
+            &recipient,
+            &reflection_token,
+            amount,
+        ))
+        .add_attribute("action", "record_reflection")
+        .add_attribute("amount", amount))
+}
+
+/// Internal callback of `provide_liquidity`.
+/// Counts the LP tokens minted since `lp_balance_before`
+pub fn record_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    lp_balance_before: Uint128,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let lp_balance = query_balance(
+        &deps.querier,
+        Addr::unchecked(LIQUIDTY_TOKEN.load(deps.storage)?),
+        env.contract.address,
+    )?;
+    let amount = lp_balance.saturating_sub(lp_balance_before);
+
+    let mut stats = STATS.may_load(deps.storage)?.unwrap_or_default();
+    stats.total_lp_minted += amount;
+    STATS.save(deps.storage, &stats)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "record_liquidity")
+        .add_attribute("amount", amount))
+}
+
+/// Internal callback of the reflection swaps when reflection splits are set.
+/// Splits the reflection token received since `balance_before` between the split recipients
+pub fn split_reflection(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    balance_before: Uint128,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let splits = REFLECTION_SPLITS.load(deps.storage)?;
+    let reflection_token = REFLECTION_PAIR.load(deps.storage)?[0].clone();
+    let balance = query_asset_balance(
+        &deps.querier,
+        &reflection_token,
+        env.contract.address.clone(),
+    )?;
+    let amount = balance.saturating_sub(balance_before);
+    if amount.is_zero() {
+        return Ok(Response::default());
+    }
+
+    let mut stats = STATS.may_load(deps.storage)?.unwrap_or_default();
+    stats.total_reflected += amount;
+    STATS.save(deps.storage, &stats)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut events = vec![];
+    let mut remaining = amount;
+    for (i, split) in splits.iter().enumerate() {
+        // the last recipient receives the rounding dust
+        let share = if i == splits.len() - 1 {
+            remaining
+        } else {
+            amount.mul(split.weight)
+        };
+        remaining -= share;
+        if !share.is_zero() {
+            messages.push(transfer_asset_msg(
+                &reflection_token,
+                &split.recipient,
+                share,
+            )?);
+            events.push(reflection_event(
+                &env.contract.address,
+                &split.recipient,
+                &reflection_token,
+                share,
+            ));
+        }
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_events(events)
+        .add_attribute("action", "split_reflection")
+        .add_attribute("amount", amount))
+}
+
+/// Internal callback of the reflection swaps when IBC reflection is set.
+/// Forwards the reflection token received since `balance_before` to the remote recipient
+pub fn forward_ibc_reflection(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    balance_before: Uint128,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let config = IBC_REFLECTION.load(deps.storage)?;
+    let reflection_token = REFLECTION_PAIR.load(deps.storage)?[0].clone();
+    let balance = query_asset_balance(
+        &deps.querier,
+        &reflection_token,
+        env.contract.address.clone(),
+    )?;
+    let amount = balance.saturating_sub(balance_before);
+    if amount.is_zero() {
+        return Ok(Response::default());
+    }
+
+    let mut stats = STATS.may_load(deps.storage)?.unwrap_or_default();
+    stats.total_reflected += amount;
+    STATS.save(deps.storage, &stats)?;
+
+    let message: CosmosMsg = match &reflection_token {
+        AssetInfo::NativeToken { denom } => IbcMsg::Transfer {
+            channel_id: config.channel.clone(),
+            to_address: config.remote_recipient.clone(),
+            amount: coin(amount.u128(), denom),
+            timeout: IbcTimeout::with_timestamp(
+                env.block.time.plus_seconds(config.timeout_seconds),
+            ),
+        }
+        .into(),
+        AssetInfo::Token { contract_addr } => {
+            let ics20_contract = config.ics20_contract.clone().ok_or_else(|| {
+                StdError::generic_err("cw20 reflection tokens need an ics20 contract")
+            })?;
+            WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                    contract: ics20_contract.to_string(),
+                    amount,
+                    msg: to_json_binary(&Ics20TransferMsg {
+                        channel: config.channel.clone(),
+                        remote_address: config.remote_recipient.clone(),
+                        timeout: Some(config.timeout_seconds),
+                    })?,
+                })?,
+                funds: vec![],
+            }
+            .into()
+        }
+    };
+
+    Ok(Response::new()
+        .add_message(message)
+        .add_event(
+            Event::new("reflection")
+                .add_attribute("from", env.contract.address)
+                .add_attribute("to", &config.remote_recipient)
+                .add_attribute("asset", reflection_token.to_string())
+                .add_attribute("amount", amount)
+                .add_attribute("channel", &config.channel),
+        )
+        .add_attribute("action", "forward_ibc_reflection")
+        .add_attribute("channel", config.channel)
+        .add_attribute("remote_recipient", config.remote_recipient)
+        .add_attribute("amount", amount))
+}
+
+/// The `reflection` event of the reflection token delivered by the treasury. Native reflections
+/// forwarded to the token are reported by the token when it distributes them
+pub fn reflection_event(from: &Addr, to: &Addr, asset: &AssetInfo, amount: Uint128) -> Event {
+    Event::new("reflection")
+        .add_attribute("from", from)
+        .add_attribute("to", to)
+        .add_attribute("asset", asset.to_string())
+        .add_attribute("amount", amount)
+}
+
+/// Builds the transfer of `amount` of a native or cw20 asset to `recipient`
+pub fn transfer_asset_msg(
+    asset_info: &AssetInfo,
+    recipient: &Addr,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    Ok(match asset_info {
+        AssetInfo::NativeToken { denom } => BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin(amount.u128(), denom)],
+        }
+        .into(),
+        AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        }
+        .into(),
+    })
+}
+
+/// Internal callback of `liquify_treasury` in native reflection mode.
+/// Sends the native funds received since `balance_before` to the token for distribution to holders
+pub fn forward_native_reflection(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    balance_before: Uint128,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let token = TOKEN.load(deps.storage)?;
+    let balance = deps
+        .querier
+        .query_balance(env.contract.address, &denom)?
+        .amount;
+    let amount = balance.saturating_sub(balance_before);
+    if amount.is_zero() {
+        return Ok(Response::default());
+    }
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: token.to_string(),
+            msg: to_json_binary(&TokenExecuteMsg::DepositNativeReflection {})?,
+            funds: vec![coin(amount.u128(), &denom)],
+        })
+        .add_attribute("action", "forward_native_reflection")
+        .add_attribute("amount", amount)
+        .add_attribute("denom", denom))
+}
+
+/// Returns the balance of a native or cw20 asset held by `address`
+pub fn query_asset_balance(
+    querier: &QuerierWrapper,
+    asset_info: &AssetInfo,
+    address: Addr,
+) -> StdResult<Uint128> {
+    match asset_info {
+        AssetInfo::NativeToken { denom } => Ok(querier.query_balance(address, denom)?.amount),
+        AssetInfo::Token { contract_addr } => {
+            query_balance(querier, Addr::unchecked(contract_addr), address)
+        }
+    }
+}
+
+pub fn query_balance(querier: &QuerierWrapper, token: Addr, address: Addr) -> StdResult<Uint128> {
+    let response: BalanceResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: token.to_string(),
+        msg: to_json_binary(&cw20::Cw20QueryMsg::Balance {
+            address: address.to_string(),
+        })?,
+    }))?;
+    Ok(response.balance)
+}
+
+// Check below for pair ordering
+// 1. This contract address (babyToken)
+// 2. The quote token (inj)
+pub fn set_liquidity_pair(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    asset_infos: [AssetInfo; 2],
+    pair_contract: String,
+    dex: Option<Dex>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    let dex = dex.unwrap_or_default();
+    LIQUIDITY_PAIR_DEX.save(deps.storage, &dex)?;
+    let reflection_pair = REFLECTION_PAIR.load(deps.storage);
+    LIQUIDITY_PAIR.save(deps.storage, &asset_infos)?;
+    LIQUIDITY_PAIR_CONTRACT.save(deps.storage, &pair_contract)?;
+
+    match reflection_pair {
+        Err(_) => {}
+        Ok(asset_info) => {
+            let unbound = asset_info;
+            let reflect_1 = unbound.get(1).unwrap();
+            if !reflect_1.eq(&asset_infos[1]) {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "asset_infos[1] do not match",
+                )));
+            }
+        }
+    };
+
+    let response = dex
+        .adapter()
+        .query_pair(&deps.querier, pair_contract.clone())?;
+
+    match response.asset_infos[0].clone() {
+        AssetInfo::Token { contract_addr } => {
+            deps.api.addr_validate(&contract_addr.to_string())?;
+        }
+        AssetInfo::NativeToken { denom: _ } => {
+            return Err(ContractError::Std(StdError::generic_err(
+                "token should be cw20",
+            )));
+        }
+    };
+
+    LIQUIDTY_TOKEN.save(deps.storage, &response.liquidity_token)?;
+
+    response
+        .asset_infos
+        .iter()
+        .find(|info| info.equal(&asset_infos[0]))
+        .ok_or(StdError::generic_err("asset_infos[0] is not valid"))?;
+
+    response
+        .asset_infos
+        .iter()
+        .find(|info| info.equal(&asset_infos[1]))
+        .ok_or(StdError::generic_err("asset_infos[1] is not valid"))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_liquidity_pair")
+        .add_attribute("pair", pair_contract))
+}
+
+// Check below for pair ordering
+// 1. The reflection token (DOJO)
+// 2. The quote token (inj)
+pub fn set_reflection_pair(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    asset_infos: [AssetInfo; 2],
+    pair_contract: String,
+    dex: Option<Dex>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    let dex = dex.unwrap_or_default();
+    REFLECTION_PAIR_DEX.save(deps.storage, &dex)?;
+    let liquidity_pair = LIQUIDITY_PAIR.load(deps.storage);
+    REFLECTION_PAIR.save(deps.storage, &asset_infos)?;
+    REFLECTION_PAIR_CONTRACT.save(deps.storage, &pair_contract)?;
+
+    match liquidity_pair {
+        Err(_) => {}
+        Ok(asset_info) => {
+            let unbound = asset_info;
+            let liquidity_1 = unbound.get(1).unwrap();
+            if !liquidity_1.eq(&asset_infos[1]) {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "asset_infos[1] do not match",
+                )));
+            }
+        }
+    };
+
+    let response = dex
+        .adapter()
+        .query_pair(&deps.querier, pair_contract.clone())?;
+
+    response
+        .asset_infos
+        .iter()
+        .find(|info| info.equal(&asset_infos[0]))
+        .ok_or(StdError::generic_err("asset_infos[0] is not valid"))?;
+
+    response
+        .asset_infos
+        .iter()
+        .find(|info| info.equal(&asset_infos[1]))
+        .ok_or(StdError::generic_err("asset_infos[1] is not valid"))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_reflection_pair")
+        .add_attribute("pair", pair_contract))
+}
+
+/// Returns the swaps from babyTOKEN to the reflection token, the configured route if any
+pub fn reflection_route(storage: &dyn Storage, token: &Addr) -> StdResult<Vec<RouteHop>> {
+    if let Some(route) = REFLECTION_ROUTE.may_load(storage)? {
+        return Ok(route);
+    }
+
+    let reflection_pair = REFLECTION_PAIR.load(storage)?;
+    Ok(vec![
+        RouteHop {
+            offer_asset_info: AssetInfo::Token {
+                contract_addr: token.to_string(),
+            },
+            ask_asset_info: reflection_pair[1].clone(),
+        },
+        RouteHop {
+            offer_asset_info: reflection_pair[1].clone(),
+            ask_asset_info: reflection_pair[0].clone(),
+        },
+    ])
+}
+
+/// Sets the swaps from babyTOKEN to the reflection token, `None` restores babyTOKEN -> INJ -> DOJO.
+/// The route must be connected and is simulated against the router
+pub fn set_reflection_route(
+    deps: DepsMut,
+    info: MessageInfo,
+    operations: Option<Vec<RouteHop>>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let operations = match operations {
+        Some(operations) => operations,
+        None => {
+            REFLECTION_ROUTE.remove(deps.storage);
+            return Ok(Response::new().add_attribute("action", "set_reflection_route"));
+        }
+    };
+
+    let token = AssetInfo::Token {
+        contract_addr: TOKEN.load(deps.storage)?.to_string(),
+    };
+    let reflection_token = REFLECTION_PAIR.load(deps.storage)?[0].clone();
+    validate_route(&deps, &operations, &token, &reflection_token)?;
+
+    REFLECTION_ROUTE.save(deps.storage, &operations)?;
+    Ok(Response::new()
+        .add_attribute("action", "set_reflection_route")
+        .add_attribute("hops", operations.len().to_string()))
+}
+
+/// Checks `route` is connected, swaps `offer` into `ask` and is simulated by the router
+pub fn validate_route(
+    deps: &DepsMut,
+    route: &[RouteHop],
+    offer: &AssetInfo,
+    ask: &AssetInfo,
+) -> Result<(), ContractError> {
+    let connected = route
+        .windows(2)
+        .all(|hops| hops[0].ask_asset_info.equal(&hops[1].offer_asset_info));
+    match (route.first(), route.last()) {
+        (Some(first), Some(last))
+            if connected
+                && first.offer_asset_info.equal(offer)
+                && last.ask_asset_info.equal(ask) => {}
+        _ => {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "route must swap {} into {}",
+                offer, ask
+            ))));
+        }
+    }
+
+    // fails if the router has no pair for one of the hops
+    let router = ROUTER.load(deps.storage)?;
+    REFLECTION_PAIR_DEX
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .adapter()
+        .simulate_swap_operations(&deps.querier, router, Uint128::new(1_000_000), route)?;
+    Ok(())
+}
+
+/// Sets the babyTOKEN <> reflection token pair reflections are swapped on, `None` swaps along
+/// the reflection route
+pub fn set_direct_reflection_pair(
+    deps: DepsMut,
+    info: MessageInfo,
+    pair_contract: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let pair_contract = match pair_contract {
+        Some(pair_contract) => pair_contract,
+        None => {
+            DIRECT_REFLECTION_PAIR.remove(deps.storage);
+            return Ok(Response::new().add_attribute("action", "set_direct_reflection_pair"));
+        }
+    };
+    deps.api.addr_validate(&pair_contract)?;
+
+    let token = AssetInfo::Token {
+        contract_addr: TOKEN.load(deps.storage)?.to_string(),
+    };
+    let reflection_token = REFLECTION_PAIR.load(deps.storage)?[0].clone();
+    let response = REFLECTION_PAIR_DEX
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .adapter()
+        .query_pair(&deps.querier, pair_contract.clone())?;
+    for asset_info in [&token, &reflection_token] {
+        response
+            .asset_infos
+            .iter()
+            .find(|info| info.equal(asset_info))
+            .ok_or(StdError::generic_err(
+                "pair must trade babyTOKEN against the reflection token",
+            ))?;
+    }
+
+    DIRECT_REFLECTION_PAIR.save(deps.storage, &pair_contract)?;
+    Ok(Response::new()
+        .add_attribute("action", "set_direct_reflection_pair")
+        .add_attribute("pair_contract", pair_contract))
+}
+
+/// Replaces the router, emits a `set_router` event with the previous and the new router
+pub fn set_router(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let router = deps.api.addr_validate(&address)?;
+    let previous = ROUTER.may_load(deps.storage)?.unwrap_or_default();
+    ROUTER.save(deps.storage, &router.to_string())?;
+    Ok(Response::new()
+        .add_attribute("action", "set_router")
+        .add_event(
+            Event::new("set_router")
+                .add_attribute("previous_router", previous)
+                .add_attribute("router", router),
+        ))
+}
+
+/// Sets the recipient of the reflection token bought by the treasury, `None` keeps it in the treasury
+pub fn set_fee_collector(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    match address {
+        Some(address) => {
+            let address = deps.api.addr_validate(&address)?;
+            FEE_COLLECTOR.save(deps.storage, &address)?
+        }
+        None => FEE_COLLECTOR.remove(deps.storage),
+    }
+    Ok(Response::new().add_attribute("action", "set_fee_collector"))
+}
+
+/// Sets the recipients the reflection token bought by the treasury is split between, the weights
+/// must sum to 1. `None` sends it to the fee collector
+pub fn set_reflection_splits(
+    deps: DepsMut,
+    info: MessageInfo,
+    splits: Option<Vec<ReflectionSplitInput>>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let splits = match splits {
+        Some(splits) => splits,
+        None => {
+            REFLECTION_SPLITS.remove(deps.storage);
+            return Ok(Response::new().add_attribute("action", "set_reflection_splits"));
+        }
+    };
+    let total = splits
+        .iter()
+        .try_fold(Decimal::zero(), |total, split| {
+            total.checked_add(split.weight)
+        })
+        .map_err(StdError::overflow)?;
+    if total != Decimal::one() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "split weights must sum to 1",
+        )));
+    }
+    let splits = splits
+        .into_iter()
+        .map(|split| {
+            Ok(ReflectionSplit {
+                recipient: deps.api.addr_validate(&split.recipient)?,
+                weight: split.weight,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    REFLECTION_SPLITS.save(deps.storage, &splits)?;
+    Ok(Response::new()
+        .add_attribute("action", "set_reflection_splits")
+        .add_attribute("recipients", splits.len().to_string()))
+}
+
+/// Forwards reflections over IBC instead of keeping them on this chain
+pub fn set_ibc_reflection(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: Option<IbcReflectionInput>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let config = match config {
+        Some(config) => config,
+        None => {
+            IBC_REFLECTION.remove(deps.storage);
+            return Ok(Response::new().add_attribute("action", "set_ibc_reflection"));
+        }
+    };
+    if config.channel.is_empty() || config.remote_recipient.is_empty() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "channel and remote recipient are required",
+        )));
+    }
+    let timeout_seconds = config.timeout_seconds.unwrap_or(DEFAULT_IBC_TIMEOUT);
+    if timeout_seconds == 0 {
+        return Err(ContractError::Std(StdError::generic_err(
+            "timeout must be positive",
+        )));
+    }
+    let ics20_contract = config
+        .ics20_contract
+        .map(|contract| deps.api.addr_validate(&contract))
+        .transpose()?;
+    let cw20_reflection = REFLECTION_PAIR
+        .may_load(deps.storage)?
+        .is_some_and(|pair| matches!(pair[0], AssetInfo::Token { .. }));
+    if cw20_reflection && ics20_contract.is_none() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "cw20 reflection tokens need an ics20 contract",
+        )));
+    }
+
+    let config = IbcReflection {
+        channel: config.channel,
+        remote_recipient: config.remote_recipient,
+        timeout_seconds,
+        ics20_contract,
+    };
+    IBC_REFLECTION.save(deps.storage, &config)?;
+    Ok(Response::new()
+        .add_attribute("action", "set_ibc_reflection")
+        .add_attribute("channel", config.channel)
+        .add_attribute("remote_recipient", config.remote_recipient))
+}
+
+/// Replaces the tracked babyTOKEN. The balance of the current token is swept to `sweep_to`, the
+/// replacement is refused if there is a balance and no `sweep_to`
+pub fn set_token(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: Addr,
+    sweep_to: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let token = deps.api.addr_validate(address.as_str())?;
+    let previous = TOKEN.load(deps.storage)?;
+    let balance = query_balance(&deps.querier, previous.clone(), env.contract.address)?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "set_token")
+        .add_attribute("previous_token", previous.to_string())
+        .add_attribute("token", token.to_string());
+    if !balance.is_zero() {
+        let sweep_to = match sweep_to {
+            Some(sweep_to) => deps.api.addr_validate(&sweep_to)?,
+            None => {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "treasury holds the current token, set sweep_to to withdraw it",
+                )));
+            }
+        };
+        res = res
+            .add_message(WasmMsg::Execute {
+                contract_addr: previous.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: sweep_to.to_string(),
+                    amount: balance,
+                })?,
+                funds: vec![],
+            })
+            .add_attribute("swept", balance)
+            .add_attribute("sweep_to", sweep_to);
+    }
+
+    TOKEN.save(deps.storage, &token)?;
+    Ok(res)
+}
+
+/// Sets minimum babyTOKEN required to liquify
+pub fn set_min_liquify_amt(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
     min_liquify_amt: Uint128,
 ) -> Result<Response, ContractError> {
     ensure_admin(&deps, &info)?;
 
-    MIN_LIQUIFY_AMT.save(deps.storage, &min_liquify_amt)?;
-    Ok(Response::default())
+    MIN_LIQUIFY_AMT.save(deps.storage, &min_liquify_amt)?;
+    Ok(Response::new()
+        .add_attribute("action", "set_min_liquify_amt")
+        .add_attribute("min_liquify_amt", min_liquify_amt))
+}
+
+/// Sets maximum babyTOKEN liquified per call, `None` liquifies the whole balance
+pub fn set_max_liquify_amt(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_liquify_amt: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    match max_liquify_amt {
+        Some(max_liquify_amt) if max_liquify_amt.is_zero() => {
+            return Err(ContractError::Std(StdError::generic_err(
+                "max liquify amount must be greater than zero",
+            )));
+        }
+        Some(max_liquify_amt) => MAX_LIQUIFY_AMT.save(deps.storage, &max_liquify_amt)?,
+        None => MAX_LIQUIFY_AMT.remove(deps.storage),
+    }
+    Ok(Response::new().add_attribute("action", "set_max_liquify_amt"))
+}
+
+/// Sets the share of the babyTOKEN balance liquified per call, `None` liquifies the whole balance
+pub fn set_liquify_portion(
+    deps: DepsMut,
+    info: MessageInfo,
+    portion: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    match portion {
+        Some(portion) if portion.is_zero() || portion > Decimal::one() => {
+            return Err(ContractError::Std(StdError::generic_err(
+                "portion must be greater than 0 and at most 1",
+            )));
+        }
+        Some(portion) => LIQUIFY_PORTION.save(deps.storage, &portion)?,
+        None => LIQUIFY_PORTION.remove(deps.storage),
+    }
+    Ok(Response::new().add_attribute("action", "set_liquify_portion"))
+}
+
+/// Pauses or resumes liquifies
+pub fn set_liquify_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    LIQUIFY_PAUSED.save(deps.storage, &paused)?;
+    Ok(Response::new().add_attribute(
+        "action",
+        if paused {
+            "pause_liquify"
+        } else {
+            "resume_liquify"
+        },
+    ))
+}
+
+/// Schedules the LP staking contract liquifies bond into, `None` keeps the LP tokens in the treasury
+pub fn set_lp_staking(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staking: Option<LpStaking>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    if let Some(staking) = &staking {
+        deps.api.addr_validate(&staking.contract)?;
+    }
+    let destination = LpDestination::Staking(staking);
+    ensure_lp_destination_valid(deps.storage, &env, &destination)?;
+    schedule_lp_destination(deps, env, "set_lp_staking", destination)
+}
+
+/// Schedules the locker LP tokens received by liquifies are locked in, `None` keeps them in the
+/// treasury
+pub fn set_lp_locker(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    locker: Option<LpLocker>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    if let Some(locker) = &locker {
+        deps.api.addr_validate(&locker.contract)?;
+    }
+    let destination = LpDestination::Locker(locker);
+    ensure_lp_destination_valid(deps.storage, &env, &destination)?;
+    schedule_lp_destination(deps, env, "set_lp_locker", destination)
+}
+
+/// Replaces any pending LP destination change by `destination`, executable after the LP withdrawal
+/// delay like withdrawals, since a new destination can move the LP tokens of every future liquify
+fn schedule_lp_destination(
+    deps: DepsMut,
+    env: Env,
+    action: &str,
+    destination: LpDestination,
+) -> Result<Response, ContractError> {
+    let delay = LP_WITHDRAWAL_DELAY
+        .may_load(deps.storage)?
+        .unwrap_or(DEFAULT_LP_WITHDRAWAL_DELAY);
+    let pending = PendingLpDestination {
+        destination,
+        executable_at: env.block.time.seconds() + delay,
+    };
+    PENDING_LP_DESTINATION.save(deps.storage, &pending)?;
+
+    Ok(Response::new()
+        .add_attribute("action", action)
+        .add_attribute("executable_at", pending.executable_at.to_string()))
+}
+
+/// Errors if `destination` cannot be applied now: another destination is set, or the lock would
+/// already be over
+fn ensure_lp_destination_valid(
+    storage: &dyn Storage,
+    env: &Env,
+    destination: &LpDestination,
+) -> Result<(), ContractError> {
+    match destination {
+        LpDestination::Staking(Some(_)) => ensure_lp_destination_free(storage, "staked"),
+        LpDestination::BurnAddress(Some(_)) => ensure_lp_destination_free(storage, "burned"),
+        LpDestination::Locker(Some(locker)) => {
+            ensure_lp_destination_free(storage, "locked")?;
+            if locker.unlock_time <= env.block.time.seconds() {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "unlock time must be in the future",
+                )));
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Cancels the pending LP destination change
+pub fn cancel_lp_destination(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    PENDING_LP_DESTINATION.remove(deps.storage);
+    Ok(Response::new().add_attribute("action", "cancel_lp_destination"))
+}
+
+/// Applies the pending LP destination change once its delay has passed
+pub fn execute_lp_destination(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let pending = PENDING_LP_DESTINATION
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("no LP destination change is scheduled"))?;
+    if env.block.time.seconds() < pending.executable_at {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "LP destination change is executable at {}",
+            pending.executable_at
+        ))));
+    }
+    ensure_lp_destination_valid(deps.storage, &env, &pending.destination)?;
+    PENDING_LP_DESTINATION.remove(deps.storage);
+
+    let (destination, enabled) = match pending.destination {
+        LpDestination::Staking(staking) => {
+            match &staking {
+                Some(staking) => LP_STAKING.save(deps.storage, staking)?,
+                None => LP_STAKING.remove(deps.storage),
+            }
+            ("staking", staking.is_some())
+        }
+        LpDestination::Locker(locker) => {
+            match &locker {
+                Some(locker) => LP_LOCKER.save(deps.storage, locker)?,
+                None => LP_LOCKER.remove(deps.storage),
+            }
+            ("locker", locker.is_some())
+        }
+        LpDestination::BurnAddress(address) => {
+            match &address {
+                Some(address) => LP_BURN_ADDRESS.save(deps.storage, address)?,
+                None => LP_BURN_ADDRESS.remove(deps.storage),
+            }
+            ("burn_address", address.is_some())
+        }
+    };
+    Ok(Response::new()
+        .add_attribute("action", "execute_lp_destination")
+        .add_attribute("destination", destination)
+        .add_attribute("enabled", enabled.to_string()))
+}
+
+/// Errors if the LP tokens received by liquifies already go elsewhere than `destination`,
+/// they are either burned, locked or staked
+fn ensure_lp_destination_free(
+    storage: &dyn Storage,
+    destination: &str,
+) -> Result<(), ContractError> {
+    let destinations = [
+        ("burned", LP_BURN_ADDRESS.may_load(storage)?.is_some()),
+        ("locked", LP_LOCKER.may_load(storage)?.is_some()),
+        ("staked", LP_STAKING.may_load(storage)?.is_some()),
+    ];
+    for (name, set) in destinations {
+        if set && name != destination {
+            return Err(ContractError::Std(StdError::generic_err(format!(
+                "LP tokens are {}, unset it first",
+                name
+            ))));
+        }
+    }
+    Ok(())
+}
+
+/// Schedules the address LP tokens received by liquifies are sent to for good, `None` keeps them in
+/// the treasury
+pub fn set_lp_burn_address(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let address = address
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let destination = LpDestination::BurnAddress(address);
+    ensure_lp_destination_valid(deps.storage, &env, &destination)?;
+    schedule_lp_destination(deps, env, "set_lp_burn_address", destination)
+}
+
+/// Sets how the farming rewards are harvested, `None` disables `Harvest`.
+/// The route must swap the reward into babyTOKEN when compounding, or into the reflection token
+pub fn set_harvest(
+    deps: DepsMut,
+    info: MessageInfo,
+    harvest: Option<HarvestConfig>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let harvest = match harvest {
+        Some(harvest) => harvest,
+        None => {
+            HARVEST.remove(deps.storage);
+            return Ok(Response::new().add_attribute("action", "set_harvest"));
+        }
+    };
+
+    let ask = match harvest.mode {
+        HarvestMode::Compound => AssetInfo::Token {
+            contract_addr: TOKEN.load(deps.storage)?.to_string(),
+        },
+        HarvestMode::Reflect => REFLECTION_PAIR.load(deps.storage)?[0].clone(),
+    };
+    // rewards paid in the reflection token need no swap
+    if !(harvest.route.is_empty()
+        && harvest.reward.equal(&ask)
+        && harvest.mode == HarvestMode::Reflect)
+    {
+        validate_route(&deps, &harvest.route, &harvest.reward, &ask)?;
+    }
+
+    HARVEST.save(deps.storage, &harvest)?;
+    Ok(Response::new().add_attribute("action", "set_harvest"))
+}
+
+/// Sets the bounty paid to callers of `Liquify`, `None` disables it
+pub fn set_keeper_bounty(
+    deps: DepsMut,
+    info: MessageInfo,
+    bounty: Option<KeeperBounty>,
+    limits: KeeperBountyLimits,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    match &bounty {
+        Some(bounty) => KEEPER_BOUNTY.save(deps.storage, bounty)?,
+        None => KEEPER_BOUNTY.remove(deps.storage),
+    }
+    KEEPER_BOUNTY_LIMITS.save(deps.storage, &limits)?;
+    Ok(Response::new()
+        .add_attribute("action", "set_keeper_bounty")
+        .add_attribute("min_amount", limits.min_amount)
+        .add_attribute("cooldown", limits.cooldown.to_string()))
+}
+
+/// Sets the max spread of treasury swaps and the slippage tolerance of its liquidity provisions,
+/// `None` leaves the DEX default
+pub fn set_slippage(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_spread: Option<Decimal>,
+    slippage_tolerance: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    for (item, value) in [
+        (MAX_SPREAD, max_spread),
+        (SLIPPAGE_TOLERANCE, slippage_tolerance),
+    ] {
+        match value {
+            Some(value) if value > Decimal::one() => {
+                return Err(ContractError::Std(StdError::generic_err(
+                    "slippage must not exceed 1",
+                )));
+            }
+            Some(value) => item.save(deps.storage, &value)?,
+            None => item.remove(deps.storage),
+        }
+    }
+    Ok(Response::new().add_attribute("action", "set_slippage"))
+}
+
+/// Sets the price guard of liquifies, `None` disables it and forgets the average price
+pub fn set_price_guard(
+    deps: DepsMut,
+    info: MessageInfo,
+    guard: Option<PriceGuard>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    match &guard {
+        Some(guard) if guard.window == 0 => {
+            return Err(ContractError::Std(StdError::generic_err(
+                "window must be greater than zero",
+            )));
+        }
+        Some(guard) => PRICE_GUARD.save(deps.storage, guard)?,
+        None => {
+            PRICE_GUARD.remove(deps.storage);
+            PRICE_OBSERVATION.remove(deps.storage);
+        }
+    }
+    Ok(Response::new().add_attribute("action", "set_price_guard"))
+}
+
+/// Enables paying reflections to holders in the native quote denom instead of swapping into the reflection token
+pub fn set_native_reflection(
+    deps: DepsMut,
+    info: MessageInfo,
+    enable: bool,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    NATIVE_REFLECTION.save(deps.storage, &enable)?;
+    Ok(Response::new()
+        .add_attribute("action", "set_native_reflection")
+        .add_attribute("enable", enable.to_string()))
 }
 
-/// Withdraws a token of your choice from contract, but not allowed to withdraw LP
+/// Withdraws a token of your choice from contract, LP is only withdrawn through a scheduled
+/// LP withdrawal
 pub fn withdraw_token(
     deps: DepsMut,
     env: Env,
@@ -457,7 +2626,7 @@ pub fn withdraw_token(
 ) -> Result<Response, ContractError> {
     ensure_admin(&deps, &info)?;
     // Prevents liquidity token from being removed
-    if token.to_string() == LIQUIDTY_TOKEN.may_load(deps.storage)?.unwrap_or_default() {
+    if token == LIQUIDTY_TOKEN.may_load(deps.storage)?.unwrap_or_default() {
         return Err(ContractError::Std(StdError::generic_err(
             "Unauthorized: not allowed to withdraw LP",
         )));
@@ -471,6 +2640,7 @@ pub fn withdraw_token(
     )?;
 
     let res = Response::new()
+        .add_attribute("action", "withdraw_token")
         .add_attribute("withdraw_token", response.balance)
         .add_message(WasmMsg::Execute {
             contract_addr: token.to_string(),
@@ -484,19 +2654,374 @@ pub fn withdraw_token(
     Ok(res)
 }
 
-/// Ensures only admins can use this function
-pub fn ensure_admin(deps: &DepsMut, info: &MessageInfo) -> Result<Response, ContractError> {
-    let admin = ADMIN.may_load(deps.storage)?.unwrap_or_default();
-    if info.sender != admin {
+/// Withdraws the whole balance of a native denom, e.g. INJ left over by swaps or refunds
+pub fn withdraw_native(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+    if PROTECTED_DENOMS
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        .contains(&denom)
+    {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Unauthorized: denom is protected",
+        )));
+    }
+
+    let balance = deps.querier.query_balance(env.contract.address, &denom)?;
+    if balance.amount.is_zero() {
         return Err(ContractError::Std(StdError::generic_err(
-            "Unauthorized: not admin",
+            "nothing to withdraw",
         )));
     }
 
+    let res = Response::new()
+        .add_attribute("action", "withdraw_native")
+        .add_attribute("withdraw_native", balance.to_string())
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![balance],
+        });
+
+    Ok(res)
+}
+
+/// Sets the native denoms `withdraw_native` refuses to withdraw
+pub fn set_protected_denoms(
+    deps: DepsMut,
+    info: MessageInfo,
+    denoms: Vec<String>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    PROTECTED_DENOMS.save(deps.storage, &denoms)?;
+    Ok(Response::new().add_attribute("action", "set_protected_denoms"))
+}
+
+/// Schedules the withdrawal of `amount` LP tokens to `recipient`, the admin by default.
+/// Replaces any pending withdrawal and becomes executable after the LP withdrawal delay
+pub fn schedule_lp_withdrawal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => info.sender,
+    };
+    let delay = LP_WITHDRAWAL_DELAY
+        .may_load(deps.storage)?
+        .unwrap_or(DEFAULT_LP_WITHDRAWAL_DELAY);
+    let withdrawal = LpWithdrawal {
+        amount,
+        recipient,
+        executable_at: env.block.time.seconds() + delay,
+    };
+    PENDING_LP_WITHDRAWAL.save(deps.storage, &withdrawal)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "schedule_lp_withdrawal")
+        .add_attribute("amount", withdrawal.amount)
+        .add_attribute("recipient", withdrawal.recipient)
+        .add_attribute("executable_at", withdrawal.executable_at.to_string()))
+}
+
+/// Cancels the pending LP withdrawal
+pub fn cancel_lp_withdrawal(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    PENDING_LP_WITHDRAWAL.remove(deps.storage);
+    Ok(Response::new().add_attribute("action", "cancel_lp_withdrawal"))
+}
+
+/// Sends the LP tokens of the pending withdrawal once its delay has passed
+pub fn execute_lp_withdrawal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let withdrawal = PENDING_LP_WITHDRAWAL
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("no LP withdrawal is scheduled"))?;
+    if env.block.time.seconds() < withdrawal.executable_at {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "LP withdrawal is executable at {}",
+            withdrawal.executable_at
+        ))));
+    }
+    PENDING_LP_WITHDRAWAL.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: LIQUIDTY_TOKEN.load(deps.storage)?,
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: withdrawal.recipient.to_string(),
+                amount: withdrawal.amount,
+            })?,
+            funds: vec![],
+        })
+        .add_attribute("action", "execute_lp_withdrawal")
+        .add_attribute("amount", withdrawal.amount)
+        .add_attribute("recipient", withdrawal.recipient))
+}
+
+/// Schedules the withdrawal of every asset of the treasury to `recipient`. Emits an
+/// `emergency_withdraw` event so holders can be warned before it becomes executable
+pub fn schedule_emergency_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => info.sender,
+    };
+    let delay = EMERGENCY_WITHDRAWAL_DELAY
+        .may_load(deps.storage)?
+        .unwrap_or(DEFAULT_EMERGENCY_WITHDRAWAL_DELAY);
+    let withdrawal = EmergencyWithdrawal {
+        recipient,
+        executable_at: env.block.time.seconds() + delay,
+    };
+    PENDING_EMERGENCY_WITHDRAWAL.save(deps.storage, &withdrawal)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "schedule_emergency_withdraw")
+        .add_event(
+            Event::new("emergency_withdraw")
+                .add_attribute("recipient", withdrawal.recipient)
+                .add_attribute("executable_at", withdrawal.executable_at.to_string()),
+        ))
+}
+
+/// Cancels the pending emergency withdrawal
+pub fn cancel_emergency_withdraw(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    PENDING_EMERGENCY_WITHDRAWAL.remove(deps.storage);
+    Ok(Response::new().add_attribute("action", "cancel_emergency_withdraw"))
+}
+
+/// Sends every native coin and every known cw20 balance of the treasury, babyTOKEN, LP and the
+/// pair assets, once the delay of the pending emergency withdrawal has passed
+pub fn execute_emergency_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    ensure_admin(&deps, &info)?;
+
+    let withdrawal = PENDING_EMERGENCY_WITHDRAWAL
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("no emergency withdrawal is scheduled"))?;
+    if env.block.time.seconds() < withdrawal.executable_at {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "emergency withdrawal is executable at {}",
+            withdrawal.executable_at
+        ))));
+    }
+    PENDING_EMERGENCY_WITHDRAWAL.remove(deps.storage);
+
+    let mut tokens: Vec<Addr> = vec![TOKEN.load(deps.storage)?];
+    if let Some(liquidity_token) = LIQUIDTY_TOKEN.may_load(deps.storage)? {
+        tokens.push(Addr::unchecked(liquidity_token));
+    }
+    let pairs = [
+        LIQUIDITY_PAIR.may_load(deps.storage)?,
+        REFLECTION_PAIR.may_load(deps.storage)?,
+    ];
+    for info in pairs.iter().flatten().flatten() {
+        if let AssetInfo::Token { contract_addr } = info {
+            tokens.push(Addr::unchecked(contract_addr));
+        }
+    }
+    tokens.sort();
+    tokens.dedup();
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for token in tokens {
+        let balance = query_balance(&deps.querier, token.clone(), env.contract.address.clone())?;
+        if !balance.is_zero() {
+            messages.push(
+                WasmMsg::Execute {
+                    contract_addr: token.to_string(),
+                    msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: withdrawal.recipient.to_string(),
+                        amount: balance,
+                    })?,
+                    funds: vec![],
+                }
+                .into(),
+            );
+        }
+    }
+    let coins = deps.querier.query_all_balances(env.contract.address)?;
+    if !coins.is_empty() {
+        messages.push(
+            BankMsg::Send {
+                to_address: withdrawal.recipient.to_string(),
+                amount: coins,
+            }
+            .into(),
+        );
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "execute_emergency_withdraw")
+        .add_attribute("recipient", withdrawal.recipient))
+}
+
+/// Appends a privileged action to the audit log, described by the attributes of its response
+pub fn record_audit(
+    storage: &mut dyn Storage,
+    env: &Env,
+    sender: &Addr,
+    res: &Response,
+) -> StdResult<()> {
+    let id = AUDIT_LOG_NEXT_ID.may_load(storage)?.unwrap_or_default();
+    AUDIT_LOG.save(
+        storage,
+        id,
+        &AuditEntry {
+            sender: sender.clone(),
+            height: env.block.height,
+            time: env.block.time.seconds(),
+            attributes: res.attributes.clone(),
+        },
+    )?;
+    AUDIT_LOG_NEXT_ID.save(storage, &(id + 1))
+}
+
+/// Returns the audit log entries after `start_after`, oldest first
+pub fn query_audit_log(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<AuditLogResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let entries = AUDIT_LOG
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, entry)| AuditLogEntry { id, entry }))
+        .collect::<StdResult<_>>()?;
+
+    Ok(AuditLogResponse { entries })
+}
+
+/// Ensures only admins (the cw-ownable owner) can use this function
+pub fn ensure_admin(deps: &DepsMut, info: &MessageInfo) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)
+        .map_err(|err| ContractError::Std(StdError::generic_err(err.to_string())))?;
+
     Ok(Response::default())
 }
 
+/// Proposes, accepts or renounces the ownership of the contract
+pub fn update_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: cw_ownable::Action,
+) -> Result<Response, ContractError> {
+    let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)
+        .map_err(|err| ContractError::Std(StdError::generic_err(err.to_string())))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_ownership")
+        .add_attributes(ownership.into_attributes()))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
-    Ok(Response::default())
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored_version = ensure_upgrade(deps.storage)?;
+
+    // Transforms run oldest first, each one brings the state of its predecessors up to its version
+    if stored_version < Version::new(1, 1, 0) {
+        migrate_v1_1_0(deps.storage, deps.api)?;
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", stored_version.to_string())
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
+
+/// Returns the stored cw2 version, rejecting other contracts and downgrades
+pub fn ensure_upgrade(storage: &dyn Storage) -> StdResult<Version> {
+    let stored = get_contract_version(storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(StdError::generic_err(format!(
+            "Cannot migrate from contract {}",
+            stored.contract
+        )));
+    }
+    let stored_version = parse_version(&stored.version)?;
+    if stored_version > parse_version(CONTRACT_VERSION)? {
+        return Err(StdError::generic_err(format!(
+            "Cannot downgrade from version {} to {}",
+            stored_version, CONTRACT_VERSION
+        )));
+    }
+    Ok(stored_version)
+}
+
+fn parse_version(version: &str) -> StdResult<Version> {
+    version
+        .parse()
+        .map_err(|err: semver::Error| StdError::generic_err(err.to_string()))
+}
+
+/// 1.1.0 moved the admin into cw-ownable and made the withdrawal delays configurable
+fn migrate_v1_1_0(storage: &mut dyn Storage, api: &dyn Api) -> StdResult<()> {
+    if let Some(admin) = ADMIN.may_load(storage)? {
+        cw_ownable::initialize_owner(storage, api, Some(&admin))?;
+        ADMIN.remove(storage);
+    }
+    if LP_WITHDRAWAL_DELAY.may_load(storage)?.is_none() {
+        LP_WITHDRAWAL_DELAY.save(storage, &DEFAULT_LP_WITHDRAWAL_DELAY)?;
+    }
+    if EMERGENCY_WITHDRAWAL_DELAY.may_load(storage)?.is_none() {
+        EMERGENCY_WITHDRAWAL_DELAY.save(storage, &DEFAULT_EMERGENCY_WITHDRAWAL_DELAY)?;
+    }
+    Ok(())
+}
+
+/// Returns the cw2 version with the package and the crate features the contract was built with
+pub fn query_contract_version(storage: &dyn Storage) -> StdResult<ContractVersionResponse> {
+    let version = get_contract_version(storage)?;
+    let features = [
+        ("backtraces", cfg!(feature = "backtraces")),
+        ("library", cfg!(feature = "library")),
+    ]
+    .iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(feature, _)| feature.to_string())
+    .collect();
+
+    Ok(ContractVersionResponse {
+        contract: version.contract,
+        version: version.version,
+        package: env!("CARGO_PKG_NAME").to_string(),
+        features,
+    })
 }
@@ -0,0 +1,449 @@
+use cosmwasm_std::{
+    to_json_binary, Binary, Decimal, QuerierWrapper, QueryRequest, StdResult, Uint128, WasmQuery,
+};
+use dojoswap::asset::{Asset, AssetInfo};
+use dojoswap::pair::{PoolResponse, QueryMsg as PairQueryMsg, SimulationResponse};
+use dojoswap::router::{SimulateSwapOperationsResponse, SwapOperation};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::msg::{Dex, RouteHop};
+
+/// Builds the messages and queries the treasury sends to a DEX.
+/// Pairs and routers of the supported DEXes share their query interface and the asset encoding,
+/// they differ in the shape of the swap and liquidity messages
+pub trait DexAdapter {
+    /// Cw20 `Send` payload swapping the sent babyTOKEN on a pair, the output is sent to `to` or
+    /// back to the treasury
+    fn swap_hook(&self, max_spread: Option<Decimal>, to: Option<String>) -> StdResult<Binary>;
+
+    /// Pair message swapping a native `offer_asset`, sent along with the offered coins. The output
+    /// is sent to `to` or back to the treasury
+    fn swap(
+        &self,
+        offer_asset: Asset,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    ) -> StdResult<Binary>;
+
+    /// Pair message providing `assets` as liquidity
+    fn provide_liquidity(
+        &self,
+        assets: [Asset; 2],
+        slippage_tolerance: Option<Decimal>,
+    ) -> StdResult<Binary>;
+
+    /// Cw20 `Send` payload swapping the sent babyTOKEN through the router along `route`, the output
+    /// is sent to `to` or back to the treasury
+    fn swap_operations_hook(
+        &self,
+        route: &[RouteHop],
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+    ) -> StdResult<Binary>;
+
+    /// Router query simulating a swap along `route`
+    fn simulate_swap_operations_query(
+        &self,
+        offer_amount: Uint128,
+        route: &[RouteHop],
+    ) -> StdResult<Binary>;
+
+    /// Returns the assets and the liquidity token of a pair
+    fn query_pair(
+        &self,
+        querier: &QuerierWrapper,
+        pair_contract: String,
+    ) -> StdResult<PairResponse> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: pair_contract,
+            msg: to_json_binary(&PairQueryMsg::Pair {})?,
+        }))
+    }
+
+    /// Returns the reserves and the liquidity token supply of a pair
+    fn query_pool(
+        &self,
+        querier: &QuerierWrapper,
+        pair_contract: String,
+    ) -> StdResult<PoolResponse> {
+        querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: pair_contract,
+            msg: to_json_binary(&PairQueryMsg::Pool {})?,
+        }))
+    }
+
+    /// Returns the amount received by swapping `offer_asset` on a pair
+    fn simulate_swap(
+        &self,
+        querier: &QuerierWrapper,
+        pair_contract: String,
+        offer_asset: Asset,
+    ) -> StdResult<Uint128> {
+        let simulation: SimulationResponse =
+            querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: pair_contract,
+                msg: to_json_binary(&PairQueryMsg::Simulation { offer_asset })?,
+            }))?;
+        Ok(simulation.return_amount)
+    }
+
+    /// Returns the amount received by swapping `offer_amount` through the router along `route`
+    fn simulate_swap_operations(
+        &self,
+        querier: &QuerierWrapper,
+        router: String,
+        offer_amount: Uint128,
+        route: &[RouteHop],
+    ) -> StdResult<Uint128> {
+        let simulation: SimulateSwapOperationsResponse =
+            querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: router,
+                msg: self.simulate_swap_operations_query(offer_amount, route)?,
+            }))?;
+        Ok(simulation.amount)
+    }
+}
+
+/// The fields of a pair info shared by the supported DEXes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PairResponse {
+    pub asset_infos: [AssetInfo; 2],
+    pub liquidity_token: String,
+}
+
+impl Dex {
+    pub fn adapter(&self) -> Box<dyn DexAdapter> {
+        match self {
+            Dex::DojoSwap => Box::new(DojoSwap),
+            Dex::Astroport => Box::new(Astroport),
+            Dex::TerraSwap => Box::new(TerraSwap),
+        }
+    }
+}
+
+pub struct DojoSwap;
+
+impl DexAdapter for DojoSwap {
+    fn swap_hook(&self, max_spread: Option<Decimal>, to: Option<String>) -> StdResult<Binary> {
+        to_json_binary(&dojoswap::pair::Cw20HookMsg::Swap {
+            belief_price: None,
+            max_spread,
+            to,
+            deadline: None,
+        })
+    }
+
+    fn swap(
+        &self,
+        offer_asset: Asset,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    ) -> StdResult<Binary> {
+        to_json_binary(&dojoswap::pair::ExecuteMsg::Swap {
+            offer_asset,
+            belief_price: None,
+            max_spread,
+            to,
+            deadline: None,
+        })
+    }
+
+    fn provide_liquidity(
+        &self,
+        assets: [Asset; 2],
+        slippage_tolerance: Option<Decimal>,
+    ) -> StdResult<Binary> {
+        to_json_binary(&dojoswap::pair::ExecuteMsg::ProvideLiquidity {
+            assets,
+            receiver: None,
+            deadline: None,
+            slippage_tolerance,
+        })
+    }
+
+    fn swap_operations_hook(
+        &self,
+        route: &[RouteHop],
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+    ) -> StdResult<Binary> {
+        to_json_binary(&dojoswap::router::Cw20HookMsg::ExecuteSwapOperations {
+            operations: dojoswap_operations(route),
+            minimum_receive,
+            to,
+            deadline: None,
+        })
+    }
+
+    fn simulate_swap_operations_query(
+        &self,
+        offer_amount: Uint128,
+        route: &[RouteHop],
+    ) -> StdResult<Binary> {
+        to_json_binary(&dojoswap::router::QueryMsg::SimulateSwapOperations {
+            offer_amount,
+            operations: dojoswap_operations(route),
+        })
+    }
+}
+
+fn dojoswap_operations(route: &[RouteHop]) -> Vec<SwapOperation> {
+    route
+        .iter()
+        .map(|hop| SwapOperation::DojoSwap {
+            offer_asset_info: hop.offer_asset_info.clone(),
+            ask_asset_info: hop.ask_asset_info.clone(),
+        })
+        .collect()
+}
+
+pub struct Astroport;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum AstroportPairMsg {
+    Swap {
+        offer_asset: Asset,
+        ask_asset_info: Option<AssetInfo>,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    },
+    ProvideLiquidity {
+        assets: Vec<Asset>,
+        slippage_tolerance: Option<Decimal>,
+        auto_stake: Option<bool>,
+        receiver: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum AstroportCw20HookMsg {
+    Swap {
+        ask_asset_info: Option<AssetInfo>,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum AstroportSwapOperation {
+    AstroSwap {
+        offer_asset_info: AssetInfo,
+        ask_asset_info: AssetInfo,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum AstroportRouterMsg {
+    ExecuteSwapOperations {
+        operations: Vec<AstroportSwapOperation>,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+        max_spread: Option<Decimal>,
+    },
+    SimulateSwapOperations {
+        offer_amount: Uint128,
+        operations: Vec<AstroportSwapOperation>,
+    },
+}
+
+fn astroport_operations(route: &[RouteHop]) -> Vec<AstroportSwapOperation> {
+    route
+        .iter()
+        .map(|hop| AstroportSwapOperation::AstroSwap {
+            offer_asset_info: hop.offer_asset_info.clone(),
+            ask_asset_info: hop.ask_asset_info.clone(),
+        })
+        .collect()
+}
+
+impl DexAdapter for Astroport {
+    fn swap_hook(&self, max_spread: Option<Decimal>, to: Option<String>) -> StdResult<Binary> {
+        to_json_binary(&AstroportCw20HookMsg::Swap {
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread,
+            to,
+        })
+    }
+
+    fn swap(
+        &self,
+        offer_asset: Asset,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    ) -> StdResult<Binary> {
+        to_json_binary(&AstroportPairMsg::Swap {
+            offer_asset,
+            ask_asset_info: None,
+            belief_price: None,
+            max_spread,
+            to,
+        })
+    }
+
+    fn provide_liquidity(
+        &self,
+        assets: [Asset; 2],
+        slippage_tolerance: Option<Decimal>,
+    ) -> StdResult<Binary> {
+        to_json_binary(&AstroportPairMsg::ProvideLiquidity {
+            assets: assets.to_vec(),
+            slippage_tolerance,
+            auto_stake: None,
+            receiver: None,
+        })
+    }
+
+    fn swap_operations_hook(
+        &self,
+        route: &[RouteHop],
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+    ) -> StdResult<Binary> {
+        to_json_binary(&AstroportRouterMsg::ExecuteSwapOperations {
+            operations: astroport_operations(route),
+            minimum_receive,
+            to,
+            max_spread: None,
+        })
+    }
+
+    fn simulate_swap_operations_query(
+        &self,
+        offer_amount: Uint128,
+        route: &[RouteHop],
+    ) -> StdResult<Binary> {
+        to_json_binary(&AstroportRouterMsg::SimulateSwapOperations {
+            offer_amount,
+            operations: astroport_operations(route),
+        })
+    }
+}
+
+pub struct TerraSwap;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum TerraSwapPairMsg {
+    Swap {
+        offer_asset: Asset,
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    },
+    ProvideLiquidity {
+        assets: [Asset; 2],
+        slippage_tolerance: Option<Decimal>,
+        receiver: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum TerraSwapCw20HookMsg {
+    Swap {
+        belief_price: Option<Decimal>,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum TerraSwapSwapOperation {
+    TerraSwap {
+        offer_asset_info: AssetInfo,
+        ask_asset_info: AssetInfo,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum TerraSwapRouterMsg {
+    ExecuteSwapOperations {
+        operations: Vec<TerraSwapSwapOperation>,
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+    },
+    SimulateSwapOperations {
+        offer_amount: Uint128,
+        operations: Vec<TerraSwapSwapOperation>,
+    },
+}
+
+fn terraswap_operations(route: &[RouteHop]) -> Vec<TerraSwapSwapOperation> {
+    route
+        .iter()
+        .map(|hop| TerraSwapSwapOperation::TerraSwap {
+            offer_asset_info: hop.offer_asset_info.clone(),
+            ask_asset_info: hop.ask_asset_info.clone(),
+        })
+        .collect()
+}
+
+impl DexAdapter for TerraSwap {
+    fn swap_hook(&self, max_spread: Option<Decimal>, to: Option<String>) -> StdResult<Binary> {
+        to_json_binary(&TerraSwapCw20HookMsg::Swap {
+            belief_price: None,
+            max_spread,
+            to,
+        })
+    }
+
+    fn swap(
+        &self,
+        offer_asset: Asset,
+        max_spread: Option<Decimal>,
+        to: Option<String>,
+    ) -> StdResult<Binary> {
+        to_json_binary(&TerraSwapPairMsg::Swap {
+            offer_asset,
+            belief_price: None,
+            max_spread,
+            to,
+        })
+    }
+
+    fn provide_liquidity(
+        &self,
+        assets: [Asset; 2],
+        slippage_tolerance: Option<Decimal>,
+    ) -> StdResult<Binary> {
+        to_json_binary(&TerraSwapPairMsg::ProvideLiquidity {
+            assets,
+            slippage_tolerance,
+            receiver: None,
+        })
+    }
+
+    fn swap_operations_hook(
+        &self,
+        route: &[RouteHop],
+        minimum_receive: Option<Uint128>,
+        to: Option<String>,
+    ) -> StdResult<Binary> {
+        to_json_binary(&TerraSwapRouterMsg::ExecuteSwapOperations {
+            operations: terraswap_operations(route),
+            minimum_receive,
+            to,
+        })
+    }
+
+    fn simulate_swap_operations_query(
+        &self,
+        offer_amount: Uint128,
+        route: &[RouteHop],
+    ) -> StdResult<Binary> {
+        to_json_binary(&TerraSwapRouterMsg::SimulateSwapOperations {
+            offer_amount,
+            operations: terraswap_operations(route),
+        })
+    }
+}
@@ -1,2 +1,3 @@
 pub mod contract;
+pub mod dex;
 pub mod msg;
\ No newline at end of file
@@ -1,5 +1,7 @@
 use cosmwasm_std::Addr;
+use cosmwasm_std::Attribute;
 use cosmwasm_std::Binary;
+use cosmwasm_std::Decimal;
 use cosmwasm_std::Uint128;
 use dojoswap::asset::AssetInfo;
 use schemars::JsonSchema;
@@ -10,30 +12,239 @@ pub struct InstantiateMsg {
     pub admin: String,
     pub router: String,
     pub token: Addr,
+    /// Seconds between scheduling an LP withdrawal and executing it, 30 days by default
+    pub lp_withdrawal_delay: Option<u64>,
+    /// Seconds between scheduling an emergency withdrawal and executing it, 90 days by default.
+    /// Can't be changed after instantiation
+    pub emergency_withdrawal_delay: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     Receive(Cw20ReceiveMsg),
+    /// `dex` selects the messages sent to the pair, defaults to DojoSwap
     SetLiquidityPair {
         asset_infos: [AssetInfo; 2],
         pair_contract: String,
+        dex: Option<Dex>,
     },
+    /// `dex` selects the messages sent to the pair and the router, defaults to DojoSwap
     SetReflectionPair {
         asset_infos: [AssetInfo; 2],
         pair_contract: String,
+        dex: Option<Dex>,
     },
     SetMinLiquify {
         min_liquify_amt: Uint128,
     },
-    // SetToken {
-    //     address: Addr,
-    // },
+    /// Only with the admin role. Replaces the router, e.g. after the DEX migrated it
+    SetRouter {
+        address: String,
+    },
+    /// Only with the admin role. Sends the reflection token bought by the treasury to the fee
+    /// collector. None keeps it in the treasury
+    SetFeeCollector {
+        address: Option<String>,
+    },
+    /// Only with the admin role. Splits the reflection token bought by the treasury between weighted
+    /// recipients, e.g. a staking rewards contract, marketing and dev. Weights must sum to 1 and
+    /// the fee collector is unused while set. None disables the split
+    SetReflectionSplits {
+        splits: Option<Vec<ReflectionSplitInput>>,
+    },
+    /// Only with the admin role. Forwards the reflection token bought by the treasury over IBC to a
+    /// recipient on another chain, e.g. a staking rewards contract. Takes precedence over the
+    /// reflection splits and the fee collector. None keeps reflections on this chain
+    SetIbcReflection {
+        config: Option<IbcReflectionInput>,
+    },
+    /// Internal use
+    /// Forwards the reflection token received from a reflection swap over IBC
+    ForwardIbcReflection {
+        balance_before: Uint128,
+    },
+    /// Internal use
+    /// Splits the reflection token received from a reflection swap between the split recipients
+    SplitReflection {
+        balance_before: Uint128,
+    },
+    /// Internal use
+    /// Counts the reflection token received by the fee collector from a reflection swap
+    RecordReflection {
+        recipient: Addr,
+        balance_before: Uint128,
+    },
+    /// Internal use
+    /// Counts the LP tokens minted by a liquify
+    RecordLiquidity {
+        lp_balance_before: Uint128,
+    },
+    /// Only with the admin role. Sets the router swaps from babyTOKEN to the reflection token, for
+    /// reflection tokens that are not paired with INJ. None restores babyTOKEN -> INJ -> DOJO
+    SetReflectionRoute {
+        operations: Option<Vec<RouteHop>>,
+    },
+    /// Only with the admin role. Swaps reflections on a babyTOKEN <> reflection token pair of the
+    /// reflection DEX instead of along the route. None swaps along the route
+    SetDirectReflectionPair {
+        pair_contract: Option<String>,
+    },
+    /// Only with the admin role. Caps the babyTOKEN liquified per call, the rest of the balance
+    /// is carried over to the next liquifies. None liquifies the whole balance
+    SetMaxLiquify {
+        max_liquify_amt: Option<Uint128>,
+    },
+    /// Only with the admin role. Sets the share of the babyTOKEN balance liquified per call,
+    /// e.g. 0.25 for 25%. Applied before the max liquify amount. None liquifies the whole balance
+    SetLiquifyPortion {
+        portion: Option<Decimal>,
+    },
+    /// Only with the admin role. Suspends liquifies, e.g. during migrations or pool incidents.
+    /// Transfers keep working and taxes accumulate in the treasury
+    PauseLiquify {},
+    /// Only with the admin role. Resumes liquifies
+    ResumeLiquify {},
+    /// Only with the admin role. Replaces the tracked babyTOKEN, e.g. after a misconfigured
+    /// deployment. Refused while the treasury holds the current token unless `sweep_to` is set,
+    /// which receives that balance. The pairs and routes must be set again for the new token
+    SetToken {
+        address: Addr,
+        sweep_to: Option<String>,
+    },
     WithdrawToken {
         token: Addr,
     },
+    /// Only with the admin role. Withdraws the whole `denom` balance of the treasury, protected
+    /// denoms excluded
+    WithdrawNative {
+        denom: String,
+    },
+    /// Only with the admin role. Sets the native denoms `WithdrawNative` refuses to withdraw
+    SetProtectedDenoms {
+        denoms: Vec<String>,
+    },
+    /// Only with the admin role. Schedules the withdrawal of LP tokens to `recipient`, the admin
+    /// by default. Executable after the LP withdrawal delay, replaces any pending withdrawal
+    ScheduleLpWithdrawal {
+        amount: Uint128,
+        recipient: Option<String>,
+    },
+    /// Only with the admin role. Cancels the pending LP withdrawal
+    CancelLpWithdrawal {},
+    /// Only with the admin role. Executes the pending LP withdrawal once its delay has passed
+    ExecuteLpWithdrawal {},
+    /// Only with the admin role. Schedules the withdrawal of every asset of the treasury to
+    /// `recipient`, the admin by default. Executable after the emergency withdrawal delay, which
+    /// leaves holders time to exit. Replaces any pending emergency withdrawal
+    ScheduleEmergencyWithdraw {
+        recipient: Option<String>,
+    },
+    /// Only with the admin role. Cancels the pending emergency withdrawal
+    CancelEmergencyWithdraw {},
+    /// Only with the admin role. Executes the pending emergency withdrawal once its delay has passed
+    ExecuteEmergencyWithdraw {},
+    /// Liquifies the treasury. Callers other than the token are paid the keeper bounty when the
+    /// treasury balance reaches the min liquify amount
     Liquify {},
+    /// Only with the admin role. Sets the bounty paid to callers of `Liquify`, None disables it.
+    /// `limits` bound how much and how often keepers are paid
+    SetKeeperBounty {
+        bounty: Option<KeeperBounty>,
+        limits: KeeperBountyLimits,
+    },
+    /// Only with the admin role. Skips liquifies while the pool price deviates from its time
+    /// weighted average by more than the guard allows. None disables the guard
+    SetPriceGuard {
+        guard: Option<PriceGuard>,
+    },
+    /// Only with the admin role. Sets the max spread passed to treasury swaps and the slippage
+    /// tolerance passed to its liquidity provisions. None leaves the DEX default.
+    /// The max spread also sets the minimum receive of reflection swaps through the router
+    SetSlippage {
+        max_spread: Option<Decimal>,
+        slippage_tolerance: Option<Decimal>,
+    },
+    /// Only with the admin role. Schedules bonding the LP tokens received by liquifies into an LP
+    /// staking contract so protocol owned liquidity earns farming rewards. None keeps them in the
+    /// treasury. Applied by `ExecuteLpDestination` after the LP withdrawal delay
+    SetLpStaking {
+        staking: Option<LpStaking>,
+    },
+    /// Internal use
+    /// Bonds the LP tokens minted since `lp_balance_before` into the LP staking contract
+    StakeLiquidity {
+        lp_balance_before: Uint128,
+    },
+    /// Only with the admin role. Schedules sending the LP tokens received by liquifies to a burn
+    /// address, so the protocol owned liquidity is locked for good. Exclusive with LP staking. None
+    /// keeps them. Applied by `ExecuteLpDestination` after the LP withdrawal delay
+    SetLpBurnAddress {
+        address: Option<String>,
+    },
+    /// Internal use
+    /// Sends the LP tokens minted since `lp_balance_before` to the LP burn address
+    BurnLiquidity {
+        lp_balance_before: Uint128,
+    },
+    /// Only with the admin role. Schedules locking the LP tokens received by liquifies in a third
+    /// party locker until the unlock time, the treasury being the beneficiary. Exclusive with LP
+    /// burning and staking. None keeps them in the treasury. Applied by `ExecuteLpDestination`
+    /// after the LP withdrawal delay
+    SetLpLocker {
+        locker: Option<LpLocker>,
+    },
+    /// Internal use
+    /// Locks the LP tokens minted since `lp_balance_before` in the LP locker
+    LockLiquidity {
+        lp_balance_before: Uint128,
+    },
+    /// Only with the admin role. Cancels the pending LP destination change
+    CancelLpDestination {},
+    /// Only with the admin role. Applies the pending LP destination change once the LP withdrawal
+    /// delay has passed
+    ExecuteLpDestination {},
+    /// Only with the admin role. Sets how `Harvest` claims and processes the farming rewards of the
+    /// bonded LP tokens. None disables it
+    SetHarvest {
+        harvest: Option<HarvestConfig>,
+    },
+    /// Claims the farming rewards from the LP staking contract, then compounds them into more LP
+    /// or swaps them into the reflection token
+    Harvest {},
+    /// Internal use
+    /// Swaps the rewards claimed by `Harvest` along the harvest route
+    ProcessHarvest {
+        reward_balance_before: Uint128,
+    },
+    /// Internal use
+    /// Provides liquidity with the babyTOKEN bought with the rewards
+    CompoundHarvest {
+        token_balance_before: Uint128,
+    },
+    /// Zaps the attached coins of the native quote denom, e.g. INJ donations, into protocol owned
+    /// liquidity. Half is swapped into babyTOKEN and both sides are provided as liquidity
+    ZapLiquidity {},
+    /// Internal use
+    /// Provides liquidity with the babyTOKEN bought by a zap and the quote kept aside
+    ProvideZapLiquidity {
+        token_balance_before: Uint128,
+        quote_amount: Uint128,
+    },
+    /// Proposes, accepts or renounces the ownership of the contract. The owner is the admin role.
+    /// The owner proposes a new owner with `transfer_ownership`, which only takes effect once the
+    /// new owner sends `accept_ownership`. `renounce_ownership` leaves the treasury without admin
+    UpdateOwnership(cw_ownable::Action),
+    /// Pays the reflection share to holders in the native quote denom through the token contract
+    SetNativeReflection {
+        enable: bool,
+    },
+    /// Internal use
+    /// Forwards the native funds received from the reflection swap to the token
+    ForwardNativeReflection {
+        denom: String,
+        balance_before: Uint128,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -42,6 +253,390 @@ pub enum QueryMsg {
     /// Returns the current balance of the given address, 0 if unset.
     /// Return type: BalanceResponse.
     Balance {},
+    /// Returns the lifetime counters of the treasury.
+    /// Return type: TreasuryStats.
+    Stats {},
+    /// Returns how the whole babyTOKEN balance of the treasury splits under the current rates.
+    /// Return type: PendingTaxesResponse.
+    PendingTaxes {},
+    /// Returns the amounts a liquify called now by a keeper would process, its swaps simulated
+    /// against the current reserves. Nothing is executed.
+    /// Return type: SimulateLiquifyResponse.
+    SimulateLiquify {},
+    /// Returns the owner of the contract and any pending ownership transfer.
+    /// Return type: cw_ownable::Ownership<Addr>.
+    Ownership {},
+    /// Returns the cw2 name and version of the contract, with the package and features it was
+    /// built with.
+    /// Return type: ContractVersionResponse.
+    ContractVersion {},
+    /// Returns the router the treasury swaps through.
+    /// Return type: String.
+    Router {},
+    /// Returns the recipient of the reflection token bought by the treasury, null for the treasury.
+    /// Return type: Option<Addr>.
+    FeeCollector {},
+    /// Returns the native denoms that can't be withdrawn.
+    /// Return type: Vec<String>.
+    ProtectedDenoms {},
+    /// Returns the recipients the reflection token bought by the treasury is split between.
+    /// Return type: Vec<ReflectionSplit>.
+    ReflectionSplits {},
+    /// Returns the privileged actions of the owner, oldest first. Supports pagination by entry id.
+    /// Return type: AuditLogResponse.
+    AuditLog {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns where the reflection token bought by the treasury is forwarded over IBC, null if
+    /// reflections stay on this chain.
+    /// Return type: Option<IbcReflection>.
+    IbcReflection {},
+    /// Returns the router swaps from babyTOKEN to the reflection token.
+    /// Return type: Vec<RouteHop>.
+    ReflectionRoute {},
+    /// Returns the babyTOKEN <> reflection token pair reflections are swapped on, null if unset.
+    /// Return type: Option<String>.
+    DirectReflectionPair {},
+    /// Returns the babyTOKEN amounts bounding a liquify.
+    /// Return type: LiquifyConfigResponse.
+    LiquifyConfig {},
+    /// Returns the LP staking contract liquifies bond into, null if unset.
+    /// Return type: Option<LpStaking>.
+    LpStaking {},
+    /// Returns the scheduled LP withdrawal, null if none.
+    /// Return type: Option<LpWithdrawal>.
+    PendingLpWithdrawal {},
+    /// Returns the scheduled LP destination change, null if none.
+    /// Return type: Option<PendingLpDestination>.
+    PendingLpDestination {},
+    /// Returns the pending emergency withdrawal, if any.
+    /// Return type: Option<EmergencyWithdrawal>.
+    PendingEmergencyWithdraw {},
+    /// Returns the LP locker and the lock of the treasury in it, if any.
+    /// Return type: LpLockResponse.
+    LpLock {},
+    /// Returns the address LP tokens are burned to, null if unset.
+    /// Return type: Option<Addr>.
+    LpBurnAddress {},
+    /// Returns how the farming rewards are harvested, null if unset.
+    /// Return type: Option<HarvestConfig>.
+    Harvest {},
+    /// Returns the bounty paid to callers of `Liquify`, null if disabled.
+    /// Return type: Option<KeeperBounty>.
+    KeeperBounty {},
+    /// Returns the limits of the keeper bounty and the time it was last paid.
+    /// Return type: KeeperBountyLimitsResponse.
+    KeeperBountyLimits {},
+    /// Returns the max spread and slippage tolerance applied to treasury DEX interactions.
+    /// Return type: SlippageResponse.
+    Slippage {},
+    /// Returns the liquify price guard and the last price observation, if any.
+    /// Return type: PriceGuardResponse.
+    PriceGuard {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct TreasuryStats {
+    /// babyTOKEN processed by liquifies
+    pub total_liquified: Uint128,
+    /// babyTOKEN burned by liquifies
+    pub total_burned: Uint128,
+    /// Reflection token bought by liquifies and harvests
+    pub total_reflected: Uint128,
+    pub total_lp_minted: Uint128,
+    pub liquify_count: u64,
+    /// Timestamp in seconds of the last liquify
+    pub last_liquify: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingTaxesResponse {
+    /// babyTOKEN held by the treasury
+    pub balance: Uint128,
+    pub liquidity_amount: Uint128,
+    pub reflection_amount: Uint128,
+    pub burn_amount: Uint128,
+    pub min_liquify_amt: Uint128,
+    /// Whether the balance reaches the min liquify amount, i.e. the next liquify processes it
+    pub above_min_liquify_amt: bool,
+}
+
+/// Reason a liquify does nothing
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LiquifySkip {
+    Paused,
+    /// The balance to liquify is zero or below the min liquify amount
+    BelowMinimum,
+    /// The pool price moved away from its average past the price guard
+    PriceDeviation,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct SimulateLiquifyResponse {
+    pub skipped: Option<LiquifySkip>,
+    /// babyTOKEN processed, the token keeper bounty excluded
+    pub amount: Uint128,
+    /// babyTOKEN paid to the keeper
+    pub token_bounty: Uint128,
+    /// babyTOKEN added as liquidity
+    pub liquidity_amount: Uint128,
+    /// Half of the liquidity amount, swapped into INJ
+    pub swap_amount: Uint128,
+    /// INJ received by the swap and added as liquidity
+    pub quote_amount: Uint128,
+    /// LP tokens minted, against the reserves after the swap
+    pub lp_amount: Uint128,
+    /// babyTOKEN swapped into the reflection token
+    pub reflection_amount: Uint128,
+    /// Reflection token received, or INJ with native reflection
+    pub reflection_output: Uint128,
+    pub burn_amount: Uint128,
+}
+
+/// A privileged action recorded in the audit log
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuditEntry {
+    pub sender: Addr,
+    pub height: u64,
+    /// Block time in seconds
+    pub time: u64,
+    /// Attributes of the response, starting with the action
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuditLogEntry {
+    pub id: u64,
+    pub entry: AuditEntry,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuditLogResponse {
+    pub entries: Vec<AuditLogEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IbcReflectionInput {
+    /// Channel on this chain the reflection token is transferred over
+    pub channel: String,
+    /// Recipient on the counterparty chain
+    pub remote_recipient: String,
+    /// Defaults to an hour
+    pub timeout_seconds: Option<u64>,
+    /// cw20-ics20 contract cw20 reflection tokens are sent through. Native reflection tokens use a
+    /// plain ICS20 transfer
+    pub ics20_contract: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IbcReflection {
+    pub channel: String,
+    pub remote_recipient: String,
+    pub timeout_seconds: u64,
+    pub ics20_contract: Option<Addr>,
+}
+
+/// Transfer message of the cw20-ics20 contract, sent with `Cw20ExecuteMsg::Send`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Ics20TransferMsg {
+    pub channel: String,
+    pub remote_address: String,
+    pub timeout: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReflectionSplitInput {
+    pub recipient: String,
+    pub weight: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReflectionSplit {
+    pub recipient: Addr,
+    pub weight: Decimal,
+}
+
+/// Swap of a router route
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RouteHop {
+    pub offer_asset_info: AssetInfo,
+    pub ask_asset_info: AssetInfo,
+}
+
+/// DEX a pair belongs to. Astroport and TerraSwap cover the pairs compatible with them
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Dex {
+    #[default]
+    DojoSwap,
+    Astroport,
+    TerraSwap,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LpStaking {
+    pub contract: String,
+    /// Cw20 `Send` payload bonding the LP tokens, e.g. `{"bond":{}}` for DojoSwap staking
+    pub bond_msg: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LpLocker {
+    pub contract: String,
+    /// Timestamp in seconds the LP tokens are locked until
+    pub unlock_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LpLockResponse {
+    pub locker: Option<LpLocker>,
+    pub lock: Option<LockInfoResponse>,
+}
+
+/// Cw20 receive hook of the LP locker
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LockerHookMsg {
+    Lock {
+        unlock_time: u64,
+        beneficiary: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LockerQueryMsg {
+    LockInfo { owner: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockInfoResponse {
+    pub amount: Uint128,
+    pub unlock_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EmergencyWithdrawal {
+    pub recipient: Addr,
+    /// Timestamp in seconds from which the withdrawal can be executed
+    pub executable_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LpWithdrawal {
+    pub amount: Uint128,
+    pub recipient: Addr,
+    /// Timestamp in seconds from which the withdrawal can be executed
+    pub executable_at: u64,
+}
+
+/// Where liquifies send their LP tokens, None keeping them in the treasury
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LpDestination {
+    Staking(Option<LpStaking>),
+    Locker(Option<LpLocker>),
+    BurnAddress(Option<Addr>),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingLpDestination {
+    pub destination: LpDestination,
+    /// Timestamp in seconds from which the change can be executed
+    pub executable_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HarvestConfig {
+    /// Message claiming the rewards from the LP staking contract, e.g. `{"withdraw":{}}`
+    pub claim_msg: Binary,
+    pub reward: AssetInfo,
+    pub mode: HarvestMode,
+    /// Router swaps from the reward into babyTOKEN when compounding, or into the reflection token.
+    /// Empty when the reward is the reflection token
+    pub route: Vec<RouteHop>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HarvestMode {
+    /// Buys babyTOKEN with the rewards and provides liquidity with it
+    Compound,
+    /// Swaps the rewards into the reflection token
+    Reflect,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum KeeperBounty {
+    /// Paid in babyTOKEN out of the liquified balance. The transfer is taxed unless the treasury is tax exempt
+    Token { amount: Uint128 },
+    /// Paid in a native denom held by the treasury, skipped if the treasury cannot afford it
+    Native { denom: String, amount: Uint128 },
+}
+
+/// Keepers are only paid for liquifies that process at least `min_amount` babyTOKEN, the token
+/// bounty excluded, and at most once every `cooldown` seconds
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct KeeperBountyLimits {
+    pub min_amount: Uint128,
+    pub cooldown: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct KeeperBountyLimitsResponse {
+    pub min_amount: Uint128,
+    pub cooldown: u64,
+    /// Block time in seconds of the last bounty paid
+    pub last_paid_at: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct LiquifyConfigResponse {
+    pub min_liquify_amt: Uint128,
+    pub max_liquify_amt: Option<Uint128>,
+    /// Share of the balance liquified per call
+    pub portion: Decimal,
+    pub paused: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct SlippageResponse {
+    pub max_spread: Option<Decimal>,
+    pub slippage_tolerance: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PriceGuard {
+    /// Max relative deviation of the spot price from the average, e.g. 0.05 for 5%
+    pub max_deviation: Decimal,
+    /// Seconds over which the average is weighted
+    pub window: u64,
+}
+
+/// babyTOKEN price in INJ, sampled on each guarded liquify
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PriceObservation {
+    pub price: Decimal,
+    pub twap: Decimal,
+    pub timestamp: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PriceGuardResponse {
+    pub guard: Option<PriceGuard>,
+    pub observation: Option<PriceObservation>,
+}
+
+/// Liquidity step of a liquify waiting for its swap to complete
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct PendingLiquidity {
+    /// babyTOKEN kept aside to be paired with the swap output
+    pub token_amount: Uint128,
+    /// INJ held by the treasury before the swap
+    pub quote_balance_before: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
@@ -63,6 +658,21 @@ pub enum TokenQueryMsg {
     QueryRates {},
 }
 
+/// Response of the token `QueryRates`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RatesResponse {
+    pub tax_rate: Decimal,
+    pub reflection_rate: Decimal,
+    pub burn_rate: Decimal,
+    pub max_transfer_rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenExecuteMsg {
+    DepositNativeReflection {},
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct Cw20ReceiveMsg {
@@ -74,5 +684,17 @@ pub struct Cw20ReceiveMsg {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Cw20HookMsg {
-    Liquify {}
-}
\ No newline at end of file
+    Liquify {},
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ContractVersionResponse {
+    /// cw2 contract name
+    pub contract: String,
+    /// cw2 contract version
+    pub version: String,
+    /// Cargo package the contract was built from, e.g. `qtum-reflection-token`
+    pub package: String,
+    /// Crate features enabled in the build
+    pub features: Vec<String>,
+}
@@ -0,0 +1,138 @@
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, QuerierWrapper, StdResult, Uint128, WasmMsg, WasmQuery,
+};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::msg::{
+    ExecuteMsg, PendingReflectionResponse, QueryMsg, QueryTaxResponse, RatesResponse,
+};
+
+/// ReflectionToken is a wrapper around the address of a reflection token that builds its
+/// messages and queries
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReflectionToken(pub Addr);
+
+impl ReflectionToken {
+    pub fn addr(&self) -> Addr {
+        self.0.clone()
+    }
+
+    pub fn call<T: Into<ExecuteMsg>>(&self, msg: T) -> StdResult<WasmMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: self.addr().into(),
+            msg: to_json_binary(&msg.into())?,
+            funds: vec![],
+        })
+    }
+
+    /// Transfers `amount` to `recipient`, who receives it minus the taxes
+    pub fn transfer(&self, recipient: impl Into<String>, amount: Uint128) -> StdResult<WasmMsg> {
+        self.call(ExecuteMsg::Transfer {
+            recipient: recipient.into(),
+            amount,
+        })
+    }
+
+    /// Sends `amount` to `contract` and calls its cw20 receive hook with `msg`
+    pub fn send(
+        &self,
+        contract: impl Into<String>,
+        amount: Uint128,
+        msg: Binary,
+    ) -> StdResult<WasmMsg> {
+        self.call(ExecuteMsg::Send {
+            contract: contract.into(),
+            amount,
+            msg,
+        })
+    }
+
+    pub fn burn(&self, amount: Uint128) -> StdResult<WasmMsg> {
+        self.call(ExecuteMsg::Burn { amount })
+    }
+
+    /// Claims the reflections accrued by the sender
+    pub fn claim_reflection(&self) -> StdResult<WasmMsg> {
+        self.call(ExecuteMsg::ClaimReflection {})
+    }
+
+    pub fn query<T: DeserializeOwned>(
+        &self,
+        querier: &QuerierWrapper,
+        msg: &QueryMsg,
+    ) -> StdResult<T> {
+        querier.query(
+            &WasmQuery::Smart {
+                contract_addr: self.addr().into(),
+                msg: to_json_binary(msg)?,
+            }
+            .into(),
+        )
+    }
+
+    pub fn balance(
+        &self,
+        querier: &QuerierWrapper,
+        address: impl Into<String>,
+    ) -> StdResult<Uint128> {
+        let res: cw20::BalanceResponse = self.query(
+            querier,
+            &QueryMsg::Balance {
+                address: address.into(),
+            },
+        )?;
+        Ok(res.balance)
+    }
+
+    /// Tax of a sell of `amount` on `pair`, or of a transfer between `sender` and `recipient`
+    pub fn query_tax(
+        &self,
+        querier: &QuerierWrapper,
+        amount: Uint128,
+        pair: Option<String>,
+        sender: Option<String>,
+        recipient: Option<String>,
+    ) -> StdResult<QueryTaxResponse> {
+        self.query(
+            querier,
+            &QueryMsg::QueryTax {
+                amount,
+                pair,
+                sender,
+                recipient,
+            },
+        )
+    }
+
+    pub fn rates(&self, querier: &QuerierWrapper) -> StdResult<RatesResponse> {
+        self.query(querier, &QueryMsg::QueryRates {})
+    }
+
+    pub fn is_tax_exempt(
+        &self,
+        querier: &QuerierWrapper,
+        address: impl Into<String>,
+    ) -> StdResult<bool> {
+        self.query(
+            querier,
+            &QueryMsg::IsTaxExempt {
+                address: address.into(),
+            },
+        )
+    }
+
+    pub fn pending_reflection(
+        &self,
+        querier: &QuerierWrapper,
+        address: impl Into<String>,
+    ) -> StdResult<PendingReflectionResponse> {
+        self.query(
+            querier,
+            &QueryMsg::PendingReflection {
+                address: address.into(),
+            },
+        )
+    }
+}
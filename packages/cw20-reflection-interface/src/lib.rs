@@ -0,0 +1,4 @@
+mod helpers;
+pub mod msg;
+
+pub use crate::helpers::ReflectionToken;
@@ -0,0 +1,1454 @@
+use cosmwasm_std::{Addr, Attribute, Binary};
+use cosmwasm_std::{Decimal, StdError, StdResult, Uint128};
+use cw20::{Cw20Coin, Expiration, Logo, MarketingInfoResponse, MinterResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct InstantiateMarketingInfo {
+    pub project: Option<String>,
+    pub description: Option<String>,
+    pub marketing: Option<String>,
+    pub logo: Option<Logo>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct InstantiateMsg {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub initial_balances: Vec<InitialBalance>,
+    pub admin: String,
+    pub mint: Option<MinterResponse>,
+    pub marketing: Option<InstantiateMarketingInfo>,
+    /// If set, the treasury is instantiated together with the token and stored automatically
+    pub treasury: Option<InstantiateTreasuryInfo>,
+    /// Penalizes addresses buying within the first blocks after trading is enabled
+    pub launch_protection: Option<LaunchProtection>,
+    /// Allows the admin to force-move balances with `Clawback`. Can only be enabled here
+    pub enable_clawback: Option<bool>,
+    /// Ceiling of the total tax rate of any transfer that can never be changed, defaults to 1
+    pub max_total_tax: Option<Decimal>,
+    /// Declining launch tax, e.g. 20% to 5% over 7 days
+    pub tax_decay: Option<TaxDecay>,
+    /// Compounds holder reflections into balances, so balances grow on every taxed transfer without claims.
+    /// Can only be chosen here
+    pub rebase: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct LaunchProtection {
+    /// Number of blocks after `EnableTrading` during which buyers are flagged as snipers
+    pub blocks: u64,
+    pub penalty: SniperPenalty,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SniperPenalty {
+    /// Flagged addresses pay this rate on top of the regular tax
+    Tax { rate: Decimal },
+    /// Flagged addresses cannot transfer
+    Freeze {},
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SellLimit {
+    /// Fixed amount per 24 hours
+    Amount { amount: Uint128 },
+    /// Fraction of the balance of the seller at its first sell in the window
+    BalanceRate { rate: Decimal },
+}
+
+/// How taxes are charged on transfers
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxMode {
+    /// The recipient receives the amount minus taxes
+    Inclusive,
+    /// The sender is debited the amount plus taxes, and the recipient receives exactly the amount
+    Exclusive,
+}
+
+/// Share of the taxes receiving the rounding dust of the split
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxBucket {
+    Reflection,
+    Burn,
+    Liquidity,
+    HolderReflection,
+}
+
+impl LaunchProtection {
+    pub fn validate(&self) -> StdResult<()> {
+        if let SniperPenalty::Tax { rate } = self.penalty {
+            if rate > Decimal::one() {
+                return Err(StdError::generic_err("Sniper tax rate must be <= 1"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Initial balance of an address, optionally vesting
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct InitialBalance {
+    pub address: String,
+    pub amount: Uint128,
+    /// If set, the balance is held by the contract and released with `ClaimVested`
+    pub vesting: Option<VestingTerms>,
+}
+
+/// Vesting terms relative to the instantiation time, in seconds
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct VestingTerms {
+    /// Nothing vests before the cliff
+    pub cliff: u64,
+    /// The balance vests linearly over the duration, starting at instantiation
+    pub duration: u64,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct InstantiateTreasuryInfo {
+    /// Code id of the uploaded treasury contract
+    pub code_id: u64,
+    pub router: String,
+    /// Treasury admin, defaults to the `admin` of the token
+    pub admin: Option<String>,
+    pub label: String,
+    /// Seconds between scheduling an LP withdrawal and executing it, 30 days by default
+    pub lp_withdrawal_delay: Option<u64>,
+    /// Seconds between scheduling an emergency withdrawal and executing it, 90 days by default
+    pub emergency_withdrawal_delay: Option<u64>,
+    /// Instantiates the treasury with `Instantiate2` and a salt derived from the token address, so
+    /// its address is known in advance. See `QueryMsg::PredictTreasuryAddress`
+    pub deterministic: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct TreasuryInstantiateMsg {
+    pub admin: String,
+    pub router: String,
+    pub token: Addr,
+    pub lp_withdrawal_delay: Option<u64>,
+    pub emergency_withdrawal_delay: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct TreasuryMigrateMsg {
+    pub msg: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BatchTransferItem {
+    pub recipient: String,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Transfer is a base message to move tokens to another account without triggering actions
+    Transfer {
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Transfers to several recipients at once, each leg is taxed like a single transfer
+    BatchTransfer {
+        transfers: Vec<BatchTransferItem>,
+    },
+    /// Only with the admin role. Distributes tokens from the admin balance without taxes,
+    /// e.g. to migrate holders from a previous token
+    Airdrop {
+        recipients: Vec<(String, Uint128)>,
+    },
+    /// Only with the admin role. Registers a merkle airdrop stage funded with `total_amount` from the
+    /// admin balance. Leaves are `sha256("{index},{address},{amount}")`, `expiration` is a unix time in seconds
+    RegisterMerkleRoot {
+        /// Hex encoded sha256 merkle root
+        merkle_root: String,
+        total_amount: Uint128,
+        expiration: Option<u64>,
+    },
+    /// Claims the airdrop of leaf `index` in `stage` for the sender. `proof` is the list of hex encoded
+    /// sibling hashes
+    Claim {
+        stage: u8,
+        index: u32,
+        amount: Uint128,
+        proof: Vec<String>,
+    },
+    /// Only with the admin role. Returns the unclaimed tokens of an expired stage to `recipient`,
+    /// defaulting to the sender
+    ClawbackAirdrop {
+        stage: u8,
+        recipient: Option<String>,
+    },
+    /// Only with the admin role. Moves `amount` from the admin balance to `beneficiary` and locks it
+    /// there until `unlock_time`, a unix time in seconds. Locked tokens do not count towards the max wallet
+    LockTokens {
+        beneficiary: String,
+        amount: Uint128,
+        unlock_time: u64,
+    },
+    /// Delegates the voting power of the sender's balance to `delegate`, or back to the sender if it is
+    /// the sender's own address
+    Delegate {
+        delegate: String,
+    },
+    /// Releases the vested initial balance of the sender
+    ClaimVested {},
+    /// Burn is a base message to destroy tokens forever
+    Burn {
+        amount: Uint128,
+    },
+    /// Send is a base message to transfer tokens to a contract and trigger an action
+    /// on the receiving contract.
+    Send {
+        contract: String,
+        amount: Uint128,
+        msg: Binary,
+    },
+    /// Only with "approval" extension. Allows spender to access an additional amount tokens
+    /// from the owner's (env.sender) account. If expires is Some(), overwrites current allowance
+    /// expiration with this one.
+    IncreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// Caps the amount `spender` can move from the sender's allowance in each period, e.g. for subscriptions.
+    /// None removes the cap
+    SetAllowanceLimit {
+        spender: String,
+        limit: Option<AllowanceLimit>,
+    },
+    /// Registers the secp256k1 public key the sender signs permits with. None disables permits
+    SetPermitKey {
+        pubkey: Option<Binary>,
+    },
+    /// Sets the allowance of `spender` over the tokens of `owner` to `amount`, authorized by a signature
+    /// of `owner` over the sha256 hash of the JSON encoded `PermitPayload`. Anyone can submit it before
+    /// `deadline` (unix time in seconds), and `nonce` must be the next nonce of `owner`. Rejected if
+    /// `expires` has already passed
+    Permit {
+        owner: String,
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+        nonce: u64,
+        deadline: u64,
+        signature: Binary,
+    },
+    /// Only with "approval" extension. Lowers the spender's access of tokens
+    /// from the owner's (env.sender) account by amount. If expires is Some(), overwrites current
+    /// allowance expiration with this one.
+    DecreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// Only with "approval" extension. Transfers amount tokens from owner -> recipient
+    /// if `env.sender` has sufficient pre-approval.
+    TransferFrom {
+        owner: String,
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Only with "approval" extension. Sends amount tokens from owner -> contract
+    /// if `env.sender` has sufficient pre-approval.
+    SendFrom {
+        owner: String,
+        contract: String,
+        amount: Uint128,
+        msg: Binary,
+    },
+    /// Only with "approval" extension. Destroys tokens forever
+    BurnFrom {
+        owner: String,
+        amount: Uint128,
+    },
+    /// Only with the "mintable" extension. If authorized, creates amount new tokens
+    /// and adds to the recipient balance.
+    Mint {
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Only with the "mintable" extension. The current minter may set a new minter.
+    /// Setting the minter to None will remove the token's minter forever.
+    UpdateMinter {
+        new_minter: Option<String>,
+    },
+    /// Only with the "marketing" extension. If authorized, updates marketing metadata.
+    /// Setting None/null for any of these will leave it unchanged.
+    /// Setting Some("") will clear this field on the contract storage
+    UpdateMarketing {
+        /// A URL pointing to the project behind this token.
+        project: Option<String>,
+        /// A longer description of the token and it's utility. Designed for tooltips or such
+        description: Option<String>,
+        /// The address (if any) who can update this data structure
+        marketing: Option<String>,
+    },
+    /// If set as the "marketing" role on the contract, upload a new URL, SVG, or PNG for the token
+    UploadLogo(Logo),
+    SetBuyBack {
+        enable: bool,
+    },
+    /// Only with the admin role. Sets the seconds that must pass after a liquify before a transfer
+    /// triggers the next one, 1 by default
+    SetLiquifyCooldown {
+        seconds: u64,
+    },
+    /// Only with the admin role. Sets the taxes that must accumulate since the last liquify before a
+    /// transfer triggers the next one, zero by default
+    SetLiquifyThreshold {
+        amount: Uint128,
+    },
+    /// Only with the admin role. When enabled, the burn share of the taxes is burned during the transfer,
+    /// reducing the total supply, and only the remainder goes to the treasury
+    SetInstantBurn {
+        enable: bool,
+    },
+    /// Only with the admin role. When enabled, transfers report the full amount debited from the sender in
+    /// `amount`, as the cw20 standard expects, and the taxes in `tax_amount`, `burn_amount` and
+    /// `reflection_amount`. Otherwise `amount` is the amount received
+    SetStandardAttributes {
+        enable: bool,
+    },
+    /// Only with the admin role. Sets the share of the taxes receiving the rounding dust, liquidity by default
+    SetDustBucket {
+        bucket: TaxBucket,
+    },
+    SetTaxRate {
+        global_rate: Decimal,
+        reflection_rate: Decimal,
+        burn_rate: Decimal,
+    },
+    /// Sets separate tax rates for buys from a pair, sells into a pair, and wallet-to-wallet transfers
+    SetTaxRates {
+        buy_rate: Decimal,
+        sell_rate: Decimal,
+        transfer_rate: Decimal,
+    },
+    /// Only with the admin role. Sets the sell tax that moves between bounds with the recent sell volume,
+    /// None disables it. Replaces the sell rate while enabled, pairs with a tax override keep charging it
+    SetDynamicTax {
+        config: Option<DynamicTax>,
+    },
+    /// Only with the admin role. Sets the declining launch tax, None disables it.
+    /// It is charged on every taxed buy and sell, and on wallet transfers with a transfer tax
+    SetTaxDecay {
+        decay: Option<TaxDecay>,
+    },
+    /// Only with the admin role. Cancels the pending rate change
+    CancelPendingRates {},
+    /// Only with the admin role. Cancels every pending tax setting change
+    CancelPendingTaxChanges {},
+    /// Only with the admin role. Sets the delay in seconds before `SetTaxRate` and `SetTaxRates`
+    /// take effect, and the other setters of the taxes listed in `TaxChange`. The delay can only be
+    /// increased
+    SetTaxRateDelay {
+        seconds: u64,
+    },
+    /// Only with the admin role. Opens trading to everyone, can only be called once.
+    /// Until then only the admin and tax exempt addresses can transfer
+    EnableTrading {},
+    /// Only with the admin role. Sets the launch protection applied after trading is enabled,
+    /// None disables it
+    SetLaunchProtection {
+        protection: Option<LaunchProtection>,
+    },
+    /// Only with the admin role. Clears the sniper flag of an address
+    ClearSniper {
+        address: String,
+    },
+    /// Only with the admin role. Sets the largest fraction of the total supply that can be moved
+    /// in a single transfer, 1 disables the limit. Tax exempt addresses and the treasury are not limited
+    SetMaxTransferRate {
+        rate: Decimal,
+    },
+    /// Only with the admin role and if enabled at instantiate. Force-moves `amount` from `from` to `to`
+    /// without taxes or transfer restrictions
+    Clawback {
+        from: String,
+        to: String,
+        amount: Uint128,
+    },
+    /// Proposes, accepts or renounces the ownership of the contract. The owner is the admin role
+    UpdateOwnership(cw_ownable::Action),
+    /// Only with the admin role. Halts every balance and allowance change: transfers, sends, mints,
+    /// burns, allowances, permits, airdrops, locks, claims and clawbacks. Only `ImportState` stays open
+    Pause {},
+    /// Only with the admin role. Resumes transfers, sends, mints and burns
+    Unpause {},
+    /// Only with the admin role. Blocks outbound transfers from an account, it can still receive tokens
+    Freeze {
+        address: String,
+    },
+    /// Only with the admin role. Lifts the freeze of an account
+    Unfreeze {
+        address: String,
+    },
+    /// Only with the admin role. When enabled, an address cannot sell into a pair in the same block
+    /// it bought from one
+    SetSandwichGuard {
+        enable: bool,
+    },
+    /// Only with the admin role. Exempt addresses can buy and sell in the same block
+    SetSandwichExempt {
+        address: String,
+        exempt: bool,
+    },
+    /// Only with the admin role. Sets the amount each address can sell into pairs per 24 hours,
+    /// None disables the limit. Tax exempt addresses have no sell limit
+    SetSellLimit {
+        limit: Option<SellLimit>,
+    },
+    /// Only with the admin role. Sets the minimum seconds between two transfers from the same address,
+    /// zero disables the cooldown. Pairs have no cooldown
+    SetTransferCooldown {
+        seconds: u64,
+    },
+    /// Only with the admin role. Exempt addresses have no transfer cooldown
+    SetCooldownExempt {
+        address: String,
+        exempt: bool,
+    },
+    /// Only with the admin role. Registers a cw20-ics20 contract or channel escrow address the token is
+    /// bridged through. Bridges are not taxed and have no max transfer limit, so IBC transfers arrive
+    /// in full
+    SetBridge {
+        address: String,
+        enable: bool,
+    },
+    /// Only with the admin role. When disabled, transfers from and to bridges are taxed like any other
+    /// transfer without removing them from the registry. Enabled by default
+    SetBridgeExemptionEnabled {
+        enabled: bool,
+    },
+    /// Only with the admin role. When enabled, `Send` and `SendFrom` only accept pairs and the contracts
+    /// added with `SetSendAllowed`, e.g. the DEX router
+    SetSendAllowlistEnabled {
+        enabled: bool,
+    },
+    /// Only with the admin role. Adds or removes a contract from the `Send` allowlist
+    SetSendAllowed {
+        contract: String,
+        allowed: bool,
+    },
+    /// Only with the admin role. Sets the largest fraction of the total supply a wallet can hold,
+    /// None disables the limit
+    SetMaxWalletRate {
+        rate: Option<Decimal>,
+    },
+    /// Only with the admin role. Exempt addresses can hold more than the max wallet,
+    /// e.g. pairs, treasury and lockers
+    SetMaxWalletExempt {
+        address: String,
+        exempt: bool,
+    },
+    /// Only with the admin role. Replaces the tax tier schedule, an empty list disables tiers.
+    /// Tiers replace the base rate of taxed transfers depending on the transfer size
+    SetTaxTiers {
+        tiers: Vec<TaxTier>,
+    },
+    /// Only with the admin role. Sets the external contract that computes the base tax rate of taxed
+    /// transfers through `TaxStrategyQueryMsg`, None removes it. Surtaxes and the max total tax still apply
+    SetTaxStrategy {
+        contract: Option<String>,
+    },
+    /// Only with the admin role. Sets the surtax that scales with the price impact of sells into a pair,
+    /// on top of the sell tax. None disables it
+    SetPriceImpactTax {
+        config: Option<PriceImpactTax>,
+    },
+    /// Only with the admin role. Sets the surtax on sells into a pair, on top of the sell tax.
+    /// The surtax is the sold fraction of the total supply times `surtax_multiplier`, capped at `max_surtax`.
+    /// A `max_surtax` of zero disables it
+    SetAntiWhaleTax {
+        surtax_multiplier: Decimal,
+        max_surtax: Decimal,
+    },
+    /// Only with the admin role. Sets whether taxes are taken out of transfers (the default) or charged to
+    /// the sender on top of the amount
+    SetTaxMode {
+        mode: TaxMode,
+    },
+    /// Only with the admin role. Sets the share of newly minted tokens that goes to the treasury instead of
+    /// the mint recipient. Capped by the max total tax
+    SetMintTax {
+        rate: Decimal,
+    },
+    /// Sets the share of each tax that is distributed to holders pro-rata to their balance
+    SetHolderReflectionRate {
+        rate: Decimal,
+    },
+    /// Claims the reflections accrued by the sender
+    ClaimReflection {},
+    /// Distributes the attached native funds to all holders pro-rata. Used by the treasury
+    /// to pay reflections in the native quote denom
+    DepositNativeReflection {},
+    /// Claims the native reflections accrued by the sender
+    ClaimNative {},
+    /// Only with the admin role. Sets the denom of native reflections, can only be set once
+    SetNativeReflectionDenom {
+        denom: String,
+    },
+    /// Only with the admin role. Excluded addresses do not accrue reflections and do not dilute other holders
+    SetReflectionExclusion {
+        address: String,
+        excluded: bool,
+    },
+    SetPair {
+        contract: String,
+        enable: bool,
+        /// Overrides the buy and sell tax rates for this pair
+        tax_rate: Option<Decimal>,
+    },
+    /// Only with the admin role. Sets the treasury contract that receives all taxes
+    SetTreasury {
+        contract: String,
+    },
+    /// Only with the admin role. Tax exempt addresses never pay taxes, even when transacting with pairs
+    SetTaxExempt {
+        address: String,
+        exempt: bool,
+    },
+    MigrateTreasury {
+        code_id: u64,
+    },
+    /// Only with the admin role. Credits the balances and allowances exported with `ExportState`
+    /// from a previous deployment. Only allowed on a deployment instantiated without initial
+    /// balances, until an import sets `finish`
+    ImportState {
+        balances: Vec<Cw20Coin>,
+        allowances: Vec<ExportedAllowance>,
+        finish: bool,
+    },
+}
+
+impl InstantiateMsg {
+    pub fn get_cap(&self) -> Option<Uint128> {
+        self.mint.as_ref().and_then(|v| v.cap)
+    }
+
+    pub fn validate(&self) -> StdResult<()> {
+        // Check name, symbol, decimals
+        if !is_valid_name(&self.name) {
+            return Err(StdError::generic_err(
+                "Name is not in the expected format (3-50 UTF-8 bytes)",
+            ));
+        }
+        if !is_valid_symbol(&self.symbol) {
+            return Err(StdError::generic_err(
+                "Ticker symbol is not in expected format [a-zA-Z\\-]{3,12}",
+            ));
+        }
+        if self.decimals > 18 {
+            return Err(StdError::generic_err("Decimals must not exceed 18"));
+        }
+        Ok(())
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    if bytes.len() < 3 || bytes.len() > 50 {
+        return false;
+    }
+    true
+}
+
+fn is_valid_symbol(symbol: &str) -> bool {
+    let bytes = symbol.as_bytes();
+    if bytes.len() < 3 || bytes.len() > 12 {
+        return false;
+    }
+    for byte in bytes.iter() {
+        if (*byte != 45) && (*byte < 65 || *byte > 90) && (*byte < 97 || *byte > 122) {
+            return false;
+        }
+    }
+    true
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the current balance of the given address, 0 if unset.
+    /// In rebase mode it includes the reflections not compounded yet, and the balance of the token
+    /// contract leaves out the reflections it holds for the holders.
+    /// Return type: BalanceResponse.
+    Balance {
+        address: String,
+    },
+    /// Returns metadata on the contract - name, decimals, supply, etc.
+    /// Return type: TokenInfoResponse.
+    TokenInfo {},
+    /// Only with "mintable" extension.
+    /// Returns who can mint and the hard cap on maximum tokens after minting.
+    /// Return type: MinterResponse.
+    Minter {},
+    /// Only with "allowance" extension.
+    /// Returns how much spender can use from owner account, 0 if unset.
+    /// Return type: AllowanceResponse.
+    Allowance {
+        owner: String,
+        spender: String,
+    },
+    /// Only with "enumerable" extension (and "allowances")
+    /// Returns all allowances this owner has approved. Supports pagination.
+    /// Return type: AllAllowancesResponse.
+    AllAllowances {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Only with "enumerable" extension
+    /// Returns all accounts that have balances. Supports pagination.
+    /// Return type: AllAccountsResponse.
+    AllAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Only with "marketing" extension
+    /// Returns more metadata on the contract to display in the client:
+    /// - description, logo, project url, etc.
+    ///
+    /// Return type: MarketingInfoResponse
+    MarketingInfo {},
+    /// Only with "marketing" extension
+    /// Downloads the embedded logo data (if stored on chain). Errors if no logo data is stored for this
+    /// contract.
+    /// Return type: DownloadLogoResponse.
+    DownloadLogo {},
+    /// Returns the tax breakdown of selling `amount`, using the override of `pair` if it has one.
+    /// Includes the anti-whale surtax, and the price impact surtax if `pair` is set.
+    /// With `sender` or `recipient`, returns the tax a transfer of `amount` between them would pay
+    /// instead, accounting for pairs, exemptions and the sniper penalty. `pair` is then ignored and
+    /// a missing side is priced as a plain wallet.
+    /// Return type: QueryTaxResponse.
+    QueryTax {
+        amount: Uint128,
+        pair: Option<String>,
+        sender: Option<String>,
+        recipient: Option<String>,
+    },
+    /// Returns the reflection, burn, liquidity and holder reflection shares of the sell tax of `amount`
+    /// and the dust bucket. Fails if the shares do not sum to the taxed amount.
+    /// Return type: TaxSplitResponse.
+    TaxSplit {
+        amount: Uint128,
+        pair: Option<String>,
+    },
+    /// Returns the sell tax, reflection, burn and max transfer rates.
+    /// Return type: RatesResponse.
+    QueryRates {},
+    /// Returns the buy, sell, and wallet-to-wallet tax rates, the max total tax and the mint tax.
+    /// Return type: TaxRatesResponse.
+    QueryTaxRates {},
+    /// Returns the dynamic sell tax, its current rate and the recent sell volume.
+    /// Return type: DynamicTaxResponse.
+    DynamicTax {},
+    /// Returns the launch tax decay and its current rate.
+    /// Return type: TaxDecayResponse.
+    TaxDecay {},
+    /// Returns the scheduled rate change, null if there is none.
+    /// Return type: Option<PendingRates>.
+    PendingRates {},
+    /// Returns the scheduled tax setting changes.
+    /// Return type: PendingTaxChangesResponse.
+    PendingTaxChanges {},
+    /// Returns the privileged actions of the owner, oldest first. Supports pagination by entry id.
+    /// Return type: AuditLogResponse.
+    AuditLog {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the last 100 `SetTaxRate` and `SetTaxRates` calls, oldest first. Supports
+    /// pagination by entry id.
+    /// Return type: RateHistoryResponse.
+    RateHistory {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Returns the delay in seconds before rate changes take effect.
+    /// Return type: u64.
+    TaxRateDelay {},
+    /// Returns the tax tier schedule, sorted by transfer size.
+    /// Return type: TaxTiersResponse.
+    TaxTiers {},
+    /// Returns the token locks of the given address and its currently locked amount.
+    /// Return type: LocksResponse.
+    Locks {
+        address: String,
+    },
+    /// Returns the spending cap on the allowance of `spender` over the tokens of `owner`.
+    /// Return type: AllowanceLimitResponse.
+    AllowanceLimit {
+        owner: String,
+        spender: String,
+    },
+    /// Returns the nonce the next permit of the given owner must use.
+    /// Return type: u64.
+    PermitNonce {
+        address: String,
+    },
+    /// Returns the public key the given owner signs permits with, null if not registered.
+    /// Return type: Option<Binary>.
+    PermitKey {
+        address: String,
+    },
+    /// Returns the vesting schedule of the given address and its claimable amount.
+    /// Return type: VestingResponse.
+    Vesting {
+        address: String,
+    },
+    /// Returns the latest merkle airdrop stage, 0 if none was registered.
+    /// Return type: u8.
+    LatestMerkleStage {},
+    /// Returns a merkle airdrop stage.
+    /// Return type: MerkleStage.
+    MerkleStage {
+        stage: u8,
+    },
+    /// Returns whether leaf `index` of `stage` was claimed.
+    /// Return type: bool.
+    IsClaimed {
+        stage: u8,
+        index: u32,
+    },
+    /// Returns whether trading is enabled.
+    /// Return type: bool.
+    TradingEnabled {},
+    /// Returns the launch protection settings, null if disabled.
+    /// Return type: Option<LaunchProtection>.
+    LaunchProtection {},
+    /// Returns whether the given address is flagged as a sniper.
+    /// Return type: bool.
+    IsSniper {
+        address: String,
+    },
+    /// Returns all addresses flagged as snipers. Supports pagination.
+    /// Return type: AddressListResponse.
+    Snipers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns whether clawback was enabled at instantiate.
+    /// Return type: bool.
+    ClawbackEnabled {},
+    /// Returns the owner of the contract and any pending ownership transfer.
+    /// Return type: cw_ownable::Ownership<Addr>.
+    Ownership {},
+    /// Returns the cw2 name and version of the contract, with the package and features it was
+    /// built with.
+    /// Return type: ContractVersionResponse.
+    ContractVersion {},
+    /// Returns the balance of the given address at the start of block `height`, zero if unknown.
+    /// History starts when balance checkpoints were introduced. Rebased like `Balance`.
+    /// Return type: BalanceResponse.
+    BalanceAt {
+        address: String,
+        height: u64,
+    },
+    /// Returns the taxes collected since the token was instantiated, or migrated to a version
+    /// tracking them, and where they went.
+    /// Return type: TaxStats.
+    TaxStats {},
+    /// Returns the taxes the given address paid over its lifetime. Buys are paid by the buyer, sells
+    /// and transfers by the sender.
+    /// Return type: AccountTaxPaid.
+    AccountTaxPaid {
+        address: String,
+    },
+    /// Returns the number of accounts with a nonzero balance, the treasury included. The token
+    /// contract, which holds vesting balances and undistributed reflections, is not counted.
+    /// Accounts of deployments migrated from a version without balance history are counted once
+    /// their balance changes.
+    /// Return type: u64.
+    HolderCount {},
+    /// Returns the total supply at the start of block `height`, zero if unknown.
+    /// Return type: Uint128.
+    TotalSupplyAt {
+        height: u64,
+    },
+    /// Returns the address voting with the balance of the given address.
+    /// Return type: Addr.
+    Delegation {
+        address: String,
+    },
+    /// Returns the voting power of the given address at the start of block `height`: the balances
+    /// delegated to it, including its own unless delegated elsewhere. Total power is `TotalSupplyAt`.
+    /// Return type: VotingPowerResponse.
+    VotingPowerAt {
+        address: String,
+        height: u64,
+    },
+    /// Returns whether holder reflections are compounded into balances.
+    /// Return type: bool.
+    Rebase {},
+    /// Returns whether taxes are taken out of transfers or charged on top.
+    /// Return type: TaxMode.
+    TaxMode {},
+    /// Returns whether transfers report the full amount and the tax breakdown.
+    /// Return type: bool.
+    StandardAttributes {},
+    /// Returns how transfers trigger the treasury liquify.
+    /// Return type: LiquifyConfigResponse.
+    LiquifyConfig {},
+    /// Returns whether the burn share of the taxes is burned during transfers.
+    /// Return type: bool.
+    InstantBurn {},
+    /// Returns whether the contract is paused.
+    /// Return type: bool.
+    Paused {},
+    /// Returns whether the given account is frozen.
+    /// Return type: bool.
+    IsFrozen {
+        address: String,
+    },
+    /// Returns all frozen accounts. Supports pagination.
+    /// Return type: AddressListResponse.
+    FrozenAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns whether the same-block buy/sell guard is enabled.
+    /// Return type: bool.
+    SandwichGuard {},
+    /// Returns all addresses exempt from the same-block buy/sell guard. Supports pagination.
+    /// Return type: AddressListResponse.
+    SandwichExemptions {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the daily sell limit, null if disabled.
+    /// Return type: Option<SellLimit>.
+    SellLimit {},
+    /// Returns the amount sold by the given address in its current 24 hour window.
+    /// Return type: SellAllowanceResponse.
+    SellAllowance {
+        address: String,
+    },
+    /// Returns the transfer cooldown in seconds, zero if disabled.
+    /// Return type: u64.
+    TransferCooldown {},
+    /// Returns all addresses exempt from the transfer cooldown. Supports pagination.
+    /// Return type: AddressListResponse.
+    CooldownExemptions {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns all recognized bridges. Supports pagination.
+    /// Return type: AddressListResponse.
+    Bridges {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns whether transfers from and to bridges are untaxed.
+    /// Return type: bool.
+    BridgeExemptionEnabled {},
+    /// Returns whether `Send` is restricted to pairs and the allowlist.
+    /// Return type: bool.
+    SendAllowlistEnabled {},
+    /// Returns all contracts allowed to receive tokens with `Send`. Supports pagination.
+    /// Return type: AddressListResponse.
+    SendAllowlist {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the max wallet rate, null if disabled.
+    /// Return type: Option<Decimal>.
+    MaxWalletRate {},
+    /// Returns all addresses exempt from the max wallet limit. Supports pagination.
+    /// Return type: AddressListResponse.
+    MaxWalletExemptions {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the tax strategy contract, null if there is none.
+    /// Return type: Option<Addr>.
+    TaxStrategy {},
+    /// Returns the price impact surtax settings, null if disabled.
+    /// Return type: Option<PriceImpactTax>.
+    PriceImpactTax {},
+    /// Returns the anti-whale surtax settings.
+    /// Return type: AntiWhaleTax.
+    AntiWhaleTax {},
+    GetWhitelist {
+        address: String,
+    },
+    /// Returns the pair config of the given address.
+    /// Return type: PairConfig.
+    GetPair {
+        address: String,
+    },
+    /// Returns whether the given address is exempt from taxes, including recognized bridges.
+    /// Return type: bool.
+    IsTaxExempt {
+        address: String,
+    },
+    /// Returns all tax exempt addresses. Supports pagination.
+    /// Return type: AddressListResponse.
+    TaxExemptions {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns every access list flag of the given address.
+    /// Return type: AddressStatusResponse.
+    AddressStatus {
+        address: String,
+    },
+    /// Returns the treasury address that receives taxes.
+    /// Return type: TreasuryResponse.
+    GetTreasury {},
+    /// Returns the address of a treasury of `code_id` instantiated by the token with
+    /// `deterministic` set.
+    /// Return type: TreasuryResponse.
+    PredictTreasuryAddress {
+        code_id: u64,
+    },
+    /// Returns the unclaimed and lifetime reflections of the given address along with its share weight.
+    /// Return type: PendingReflectionResponse.
+    PendingReflection {
+        address: String,
+    },
+    /// Returns all addresses excluded from reflections. Supports pagination.
+    /// Return type: AddressListResponse.
+    ReflectionExclusions {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns a page of balances, rebased like `Balance`, with the allowances their owners granted,
+    /// and the config on the first page, to be imported with `ImportState` by a new deployment. Supports pagination.
+    /// Storage is public, so unlike the import the export is open to anyone.
+    /// Return type: ExportStateResponse.
+    ExportState {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Sums the stored balances page by page to check they add up to the total supply, for audits.
+    /// Pass the `running_sum` and `last_address` of a page to sum the next one, the last page
+    /// reports the discrepancy if any. Supports pagination.
+    /// Return type: SupplyCheckResponse.
+    SupplyCheck {
+        start_after: Option<String>,
+        running_sum: Option<Uint128>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct QueryTaxResponse {
+    pub taxed_amount: Uint128,
+    pub after_tax: Uint128,
+    pub reflection_amount: Uint128,
+    pub burn_amount: Uint128,
+    pub liquidity_amount: Uint128,
+    /// Share of the taxes distributed to holders
+    pub holder_reflection_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct LiquifyConfigResponse {
+    /// Whether taxed transfers trigger the treasury liquify
+    pub buyback_enabled: bool,
+    /// Seconds between two triggered liquify calls
+    pub cooldown: u64,
+    /// Time of the last triggered liquify
+    pub last_liquify: u64,
+    /// Taxes that must accumulate before a transfer triggers the liquify
+    pub threshold: Uint128,
+    /// Taxes sent to the treasury since the last triggered liquify
+    pub accumulated_tax: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TaxSplitResponse {
+    pub taxed_amount: Uint128,
+    pub reflection_amount: Uint128,
+    pub burn_amount: Uint128,
+    pub liquidity_amount: Uint128,
+    /// Share distributed to holders
+    pub holder_reflection_amount: Uint128,
+    /// Share that received the rounding dust
+    pub dust_bucket: TaxBucket,
+}
+
+/// Rates the treasury splits the taxes with
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct RatesResponse {
+    /// Rate charged when tokens are sold into a pair
+    pub tax_rate: Decimal,
+    pub reflection_rate: Decimal,
+    /// Zero with instant burn, as the burn share never reaches the treasury
+    pub burn_rate: Decimal,
+    /// Largest fraction of the total supply a single transfer can move
+    pub max_transfer_rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct TaxRatesResponse {
+    pub buy_rate: Decimal,
+    pub sell_rate: Decimal,
+    pub transfer_rate: Decimal,
+    /// Ceiling of the total tax rate, set at instantiate
+    pub max_total_tax: Decimal,
+    /// Share of newly minted tokens sent to the treasury
+    pub mint_tax_rate: Decimal,
+}
+
+/// Sell tax that grows linearly from `min_rate` to `max_rate` as the recent sell volume reaches
+/// `volume_threshold`. Recent volume is the larger of the current and previous epochs
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct DynamicTax {
+    pub min_rate: Decimal,
+    pub max_rate: Decimal,
+    pub epoch_seconds: u64,
+    pub volume_threshold: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct SellVolume {
+    pub epoch: u64,
+    /// Volume sold into pairs in the current epoch
+    pub volume: Uint128,
+    /// Volume sold into pairs in the previous epoch
+    pub previous_volume: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct DynamicTaxResponse {
+    pub config: Option<DynamicTax>,
+    /// Current sell rate, null if disabled
+    pub current_rate: Option<Decimal>,
+    pub volume: SellVolume,
+}
+
+/// Launch tax that declines linearly from `start_rate` to `end_rate` over `duration` seconds.
+/// While active, taxed transfers pay at least the decayed rate
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct TaxDecay {
+    pub start_rate: Decimal,
+    pub end_rate: Decimal,
+    /// Unix time in seconds at which the decay starts
+    pub start_time: u64,
+    pub duration: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct TaxDecayResponse {
+    pub decay: Option<TaxDecay>,
+    /// Launch tax at the current time, null once it has fully decayed
+    pub current_rate: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct PendingRates {
+    pub buy_rate: Decimal,
+    pub sell_rate: Decimal,
+    pub transfer_rate: Decimal,
+    pub reflection_rate: Decimal,
+    pub burn_rate: Decimal,
+    /// Time at which the rates take effect, applied by the first execute after it
+    pub effective_at: u64,
+}
+
+/// Change of a tax setting other than the base rates, delayed like them by the tax rate delay.
+/// Each variant carries the arguments of its setter
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxChange {
+    TaxTiers {
+        tiers: Vec<TaxTier>,
+    },
+    DynamicTax {
+        config: Option<DynamicTax>,
+    },
+    TaxDecay {
+        decay: Option<TaxDecay>,
+    },
+    PriceImpactTax {
+        config: Option<PriceImpactTax>,
+    },
+    AntiWhaleTax {
+        surtax_multiplier: Decimal,
+        max_surtax: Decimal,
+    },
+    TaxStrategy {
+        contract: Option<Addr>,
+    },
+    Pair {
+        contract: String,
+        config: PairConfig,
+    },
+    HolderReflectionRate {
+        rate: Decimal,
+    },
+    MintTax {
+        rate: Decimal,
+    },
+    TaxMode {
+        mode: TaxMode,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PendingTaxChange {
+    pub change: TaxChange,
+    /// Time at which the change takes effect, applied by the first execute after it
+    pub effective_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct PendingTaxChangesResponse {
+    pub changes: Vec<PendingTaxChange>,
+}
+
+/// A privileged action recorded in the audit log
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct AuditEntry {
+    pub sender: Addr,
+    pub height: u64,
+    /// Block time in seconds
+    pub time: u64,
+    /// Attributes of the response, starting with the action
+    pub attributes: Vec<Attribute>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct AuditLogEntry {
+    pub id: u64,
+    pub entry: AuditEntry,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct AuditLogResponse {
+    pub entries: Vec<AuditLogEntry>,
+}
+
+/// A rate change recorded in the rate history
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RateChange {
+    /// Rates scheduled before the change, the current rates if none were pending
+    pub old_rates: PendingRates,
+    pub new_rates: PendingRates,
+    pub height: u64,
+    pub sender: Addr,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct RateHistoryEntry {
+    pub id: u64,
+    pub change: RateChange,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct RateHistoryResponse {
+    pub changes: Vec<RateHistoryEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct TaxTier {
+    /// Minimum transfer size as a fraction of the total supply
+    pub min_supply_rate: Decimal,
+    pub tax_rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct TaxTiersResponse {
+    pub tiers: Vec<TaxTier>,
+}
+
+/// Sell surtax of the estimated price impact `amount / (reserve + amount)` times `multiplier`,
+/// capped at `max_rate`
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct PriceImpactTax {
+    pub multiplier: Decimal,
+    pub max_rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct AntiWhaleTax {
+    /// Surtax per unit of the total supply sold at once
+    pub surtax_multiplier: Decimal,
+    pub max_surtax: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct SellAllowanceResponse {
+    /// Amount sold in the current window
+    pub sold: Uint128,
+    /// Amount that can still be sold in the current window, null if there is no limit
+    pub remaining: Option<Uint128>,
+    /// Time at which the window resets
+    pub window_end: u64,
+}
+
+/// Largest amount a spender can move with its allowance per period
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct AllowanceLimit {
+    pub amount: Uint128,
+    /// Length of a period in seconds, starting at the first spend
+    pub period: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct AllowanceLimitResponse {
+    /// None if the allowance is not capped
+    pub limit: Option<AllowanceLimit>,
+    /// Amount that can still be spent in the current period
+    pub remaining: Uint128,
+    pub period_end: u64,
+}
+
+/// Message signed by the owner for `Permit`
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PermitPayload {
+    pub chain_id: String,
+    /// Address of this token contract
+    pub contract: String,
+    pub owner: String,
+    pub spender: String,
+    pub amount: Uint128,
+    pub expires: Option<Expiration>,
+    pub nonce: u64,
+    pub deadline: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct VotingPowerResponse {
+    pub power: Uint128,
+    pub height: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct TokenLock {
+    pub amount: Uint128,
+    /// Unix time in seconds at which the tokens unlock
+    pub unlock_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct LocksResponse {
+    pub locks: Vec<TokenLock>,
+    /// Sum of the locks that did not unlock yet
+    pub locked: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct VestingSchedule {
+    pub total: Uint128,
+    pub released: Uint128,
+    /// Unix time in seconds at which vesting started
+    pub start: u64,
+    pub cliff: u64,
+    pub duration: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct VestingResponse {
+    pub schedule: Option<VestingSchedule>,
+    /// Amount vested to date, including released tokens
+    pub vested: Uint128,
+    /// Amount vested but not released yet
+    pub claimable: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct MerkleStage {
+    pub merkle_root: String,
+    pub total_amount: Uint128,
+    pub claimed_amount: Uint128,
+    /// Unix time in seconds after which the stage can no longer be claimed
+    pub expiration: Option<u64>,
+    /// Set once the unclaimed tokens are clawed back
+    pub closed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct PairConfig {
+    pub enabled: bool,
+    /// Overrides the buy and sell tax rates for this pair
+    pub tax_rate: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct TreasuryResponse {
+    pub treasury: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct PendingReflectionResponse {
+    /// Reflections accrued but not claimed yet
+    pub pending: Uint128,
+    /// Total reflections claimed to date
+    pub claimed: Uint128,
+    /// Native reflections accrued but not claimed yet
+    pub native_pending: Uint128,
+    /// Total native reflections claimed to date
+    pub native_claimed: Uint128,
+    /// Lifetime reflections, claimed or not
+    pub earned: Uint128,
+    /// Lifetime native reflections, claimed or not
+    pub native_earned: Uint128,
+    pub native_denom: Option<String>,
+    /// Balance counted towards reflections, zero if excluded
+    pub shares: Uint128,
+    /// Sum of the shares of all holders
+    pub total_shares: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ExportedAllowance {
+    pub owner: String,
+    pub spender: String,
+    pub allowance: Uint128,
+    pub expires: Expiration,
+}
+
+/// Settings a new deployment is instantiated and configured with, they are not imported
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ExportedConfig {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub total_supply: Uint128,
+    pub minter: Option<MinterResponse>,
+    pub marketing: Option<MarketingInfoResponse>,
+    pub owner: Option<Addr>,
+    pub treasury: Option<String>,
+    pub buy_tax_rate: Decimal,
+    pub sell_tax_rate: Decimal,
+    pub transfer_tax_rate: Decimal,
+    pub reflection_rate: Decimal,
+    pub burn_rate: Decimal,
+    pub holder_reflection_rate: Decimal,
+    pub trading_enabled: bool,
+}
+
+/// Balances are exported as stored, unclaimed reflections, vesting and airdrop funds stay in the
+/// balance of the token contract itself
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ExportStateResponse {
+    /// Set on the first page only
+    pub config: Option<ExportedConfig>,
+    pub balances: Vec<Cw20Coin>,
+    /// Allowances granted by the owners of `balances`
+    pub allowances: Vec<ExportedAllowance>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SupplyCheckResponse {
+    /// Balances summed so far, this page included
+    pub running_sum: Uint128,
+    /// Last address summed, to pass as `start_after`. None once every balance is summed
+    pub last_address: Option<String>,
+    pub total_supply: Uint128,
+    /// Set on the last page when the balances don't add up to the total supply
+    pub discrepancy: Option<SupplyDiscrepancy>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SupplyDiscrepancy {
+    /// The balances exceed the total supply by `amount`
+    Excess { amount: Uint128 },
+    /// The balances fall short of the total supply by `amount`
+    Shortfall { amount: Uint128 },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct TaxStats {
+    /// Taxes charged on transfers and mints
+    pub total_taxed: Uint128,
+    /// Burned in the transfer with instant burn. Burns by the treasury are not included
+    pub total_burned: Uint128,
+    /// Credited to the treasury, liquified later
+    pub total_to_treasury: Uint128,
+    /// Distributed to holders
+    pub total_holder_reflections: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct AccountTaxPaid {
+    pub buy: Uint128,
+    pub sell: Uint128,
+    /// Wallet-to-wallet transfers
+    pub transfer: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct AddressStatusResponse {
+    /// Enabled pair
+    pub pair: bool,
+    /// Never taxed, either exempt or a recognized bridge
+    pub tax_exempt: bool,
+    pub bridge: bool,
+    pub max_wallet_exempt: bool,
+    pub cooldown_exempt: bool,
+    pub sandwich_exempt: bool,
+    pub send_allowed: bool,
+    pub sniper: bool,
+    pub frozen: bool,
+    pub reflection_excluded: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct AddressListResponse {
+    pub addresses: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrateMsg {
+    /// Upgrades an older version of the reflection token
+    Upgrade {},
+    /// Converts a cw20-base token into the reflection token in place. Balances, allowances, minter
+    /// and marketing info are kept, taxes start disabled and trading stays enabled
+    FromCw20Base {
+        /// Owner of the reflection token
+        admin: String,
+    },
+}
+
+/// Interface that tax strategy contracts must implement
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxStrategyQueryMsg {
+    /// Returns the base tax rate of a transfer. `base_rate` is the rate the token would apply itself.
+    /// Return type: TaxStrategyResponse.
+    TaxRate {
+        from: String,
+        to: String,
+        amount: Uint128,
+        base_rate: Decimal,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct TaxStrategyResponse {
+    pub tax_rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TreasuryExecuteMsg {
+    Liquify {},
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ContractVersionResponse {
+    /// cw2 contract name
+    pub contract: String,
+    /// cw2 contract version
+    pub version: String,
+    /// Cargo package the contract was built from, e.g. `qtum-reflection-token`
+    pub package: String,
+    /// Crate features enabled in the build
+    pub features: Vec<String>,
+}
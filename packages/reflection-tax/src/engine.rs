@@ -0,0 +1,155 @@
+use std::ops::Mul;
+
+use cosmwasm_std::{Addr, Decimal, StdError, StdResult, Uint128};
+use cw20_reflection_interface::msg::{PairConfig, QueryTaxResponse, TaxBucket};
+
+use crate::rates::split_tax;
+
+/// How the taxed amount is shared between reflections, burn and liquidity
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaxShares {
+    pub reflection_rate: Decimal,
+    pub burn_rate: Decimal,
+    /// Share distributed to holders, the reflection, burn and liquidity rates split the rest
+    pub holder_reflection_rate: Decimal,
+    /// Share receiving the rounding dust
+    pub dust_bucket: TaxBucket,
+}
+
+/// Direction of a transfer relative to the pairlist
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransferKind {
+    /// From a pair to a wallet
+    Buy(PairConfig),
+    /// Into a pair
+    Sell(PairConfig),
+    /// Between wallets
+    Transfer,
+}
+
+/// Reflection tax mechanics over the configuration of a cw20 contract
+/// Implementors provide the pairlist, the exemptions and the rates, usually from storage, and get
+/// the rate selection, the tax split and the transfer limits of the reflection token
+pub trait TaxEngine {
+    /// Returns the config of `address` if it is an enabled pair
+    fn pair(&self, address: &Addr) -> StdResult<Option<PairConfig>>;
+
+    /// Tax exempt addresses never pay taxes
+    fn is_tax_exempt(&self, address: &Addr) -> StdResult<bool>;
+
+    fn buy_tax_rate(&self) -> StdResult<Decimal>;
+
+    fn sell_tax_rate(&self) -> StdResult<Decimal>;
+
+    /// Rate of wallet-to-wallet transfers
+    fn transfer_tax_rate(&self) -> StdResult<Decimal>;
+
+    fn tax_shares(&self) -> StdResult<TaxShares>;
+
+    fn total_supply(&self) -> StdResult<Uint128>;
+
+    /// Ceiling of the total tax rate, surtaxes included
+    fn max_total_tax(&self) -> StdResult<Decimal> {
+        Ok(Decimal::one())
+    }
+
+    /// Max transfer amount as a fraction of the total supply
+    fn max_transfer_rate(&self) -> StdResult<Decimal> {
+        Ok(Decimal::one())
+    }
+
+    /// Max balance as a fraction of the total supply, `None` if unlimited
+    fn max_wallet_rate(&self) -> StdResult<Option<Decimal>> {
+        Ok(None)
+    }
+
+    /// Addresses whose transfers are not limited by the max transfer rate
+    fn is_transfer_limit_exempt(&self, address: &Addr) -> StdResult<bool> {
+        self.is_tax_exempt(address)
+    }
+
+    fn is_max_wallet_exempt(&self, _address: &Addr) -> StdResult<bool> {
+        Ok(false)
+    }
+
+    /// Selling into a pair takes precedence, so a transfer between two pairs is a sell
+    fn transfer_kind(&self, from: &Addr, to: &Addr) -> StdResult<TransferKind> {
+        if let Some(pair) = self.pair(to)? {
+            return Ok(TransferKind::Sell(pair));
+        }
+        if let Some(pair) = self.pair(from)? {
+            return Ok(TransferKind::Buy(pair));
+        }
+
+        Ok(TransferKind::Transfer)
+    }
+
+    /// Returns the tax rate applied to a transfer between `from` and `to`
+    /// Buying from a pair uses the buy rate, selling into a pair uses the sell rate, anything else uses the transfer rate
+    /// Transfers from or to a tax exempt address are never taxed
+    fn transfer_rate(&self, from: &Addr, to: &Addr) -> StdResult<Decimal> {
+        if self.is_tax_exempt(from)? || self.is_tax_exempt(to)? {
+            return Ok(Decimal::zero());
+        }
+
+        // a pair with a tax override uses it for both buys and sells
+        match self.transfer_kind(from, to)? {
+            TransferKind::Sell(pair) => pair.tax_rate.map_or_else(|| self.sell_tax_rate(), Ok),
+            TransferKind::Buy(pair) => pair.tax_rate.map_or_else(|| self.buy_tax_rate(), Ok),
+            TransferKind::Transfer => self.transfer_tax_rate(),
+        }
+    }
+
+    /// Returns the tax breakdown of `amount` at `tax_rate` plus `surtax`, capped at the max total tax
+    fn tax(
+        &self,
+        amount: Uint128,
+        tax_rate: Decimal,
+        surtax: Decimal,
+    ) -> StdResult<QueryTaxResponse> {
+        let tax_rate = std::cmp::min(tax_rate + surtax, self.max_total_tax()?);
+        split_tax(amount, tax_rate, &self.tax_shares()?)
+    }
+
+    /// Rejects transfers above the max transfer rate of the total supply
+    fn check_max_transfer(&self, from: &Addr, to: &Addr, amount: Uint128) -> StdResult<()> {
+        let max_rate = self.max_transfer_rate()?;
+        if max_rate >= Decimal::one()
+            || self.is_transfer_limit_exempt(from)?
+            || self.is_transfer_limit_exempt(to)?
+        {
+            return Ok(());
+        }
+
+        let max_amount = self.total_supply()?.mul(max_rate);
+        if amount > max_amount {
+            return Err(StdError::generic_err(format!(
+                "Transfer amount exceeds the max transfer amount of {}",
+                max_amount
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Rejects balances above the max wallet rate of the total supply, unless the holder is exempt
+    fn check_max_wallet(&self, holder: &Addr, balance: Uint128) -> StdResult<()> {
+        let max_rate = match self.max_wallet_rate()? {
+            Some(max_rate) => max_rate,
+            None => return Ok(()),
+        };
+        if self.is_max_wallet_exempt(holder)? {
+            return Ok(());
+        }
+
+        let max_balance = self.total_supply()?.mul(max_rate);
+        if balance > max_balance {
+            return Err(StdError::generic_err(format!(
+                "Recipient balance would exceed the max wallet amount of {}",
+                max_balance
+            )));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,4 @@
+mod engine;
+pub mod rates;
+
+pub use crate::engine::{TaxEngine, TaxShares, TransferKind};
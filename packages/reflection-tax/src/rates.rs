@@ -0,0 +1,112 @@
+use std::ops::{Mul, Sub};
+
+use cosmwasm_std::{Decimal, StdError, StdResult, Uint128};
+use cw20_reflection_interface::msg::{
+    AntiWhaleTax, DynamicTax, PriceImpactTax, QueryTaxResponse, SellVolume, TaxBucket, TaxDecay,
+    TaxTier,
+};
+
+use crate::engine::TaxShares;
+
+/// Splits the taxes of `amount` at `tax_rate` into the holder reflection share, and the reflection, burn
+/// and liquidity shares of the rest
+/// Every share is rounded down, and the dust is added to the dust bucket so the shares sum to the taxed amount
+pub fn split_tax(
+    amount: Uint128,
+    tax_rate: Decimal,
+    shares: &TaxShares,
+) -> StdResult<QueryTaxResponse> {
+    let taxed_amount = amount.mul(tax_rate);
+    let after_tax = amount.sub(taxed_amount);
+
+    let mut holder_reflection_amount = taxed_amount.mul(shares.holder_reflection_rate);
+    let treasury_amount = taxed_amount
+        .checked_sub(holder_reflection_amount)
+        .map_err(|_| StdError::generic_err("Holder reflection rate exceeds 100%"))?;
+    let mut reflection_amount = treasury_amount.mul(shares.reflection_rate);
+    let mut burn_amount = treasury_amount.mul(shares.burn_rate);
+    let liquidity_rate = Decimal::one()
+        .checked_sub(shares.reflection_rate + shares.burn_rate)
+        .map_err(|_| StdError::generic_err("Reflection and burn rates exceed 100%"))?;
+    let mut liquidity_amount = treasury_amount.mul(liquidity_rate);
+    let dust = taxed_amount
+        .checked_sub(reflection_amount + burn_amount + liquidity_amount + holder_reflection_amount)
+        .map_err(|_| StdError::generic_err("Tax shares exceed the taxed amount"))?;
+    match shares.dust_bucket {
+        TaxBucket::Reflection => reflection_amount += dust,
+        TaxBucket::Burn => burn_amount += dust,
+        TaxBucket::Liquidity => liquidity_amount += dust,
+        TaxBucket::HolderReflection => holder_reflection_amount += dust,
+    }
+
+    Ok(QueryTaxResponse {
+        taxed_amount,
+        after_tax,
+        reflection_amount,
+        burn_amount,
+        liquidity_amount,
+        holder_reflection_amount,
+    })
+}
+
+/// Returns the dynamic sell tax at the recent sell `volume`
+/// The rate grows linearly from `min_rate` to `max_rate` as the recent sell volume reaches `volume_threshold`,
+/// recent volume being the larger of the current and previous epochs
+pub fn dynamic_rate(config: &DynamicTax, volume: &SellVolume) -> Decimal {
+    let recent_volume = std::cmp::max(volume.volume, volume.previous_volume);
+    let pressure = std::cmp::min(
+        Decimal::from_ratio(recent_volume, config.volume_threshold),
+        Decimal::one(),
+    );
+
+    config.min_rate + (config.max_rate - config.min_rate) * pressure
+}
+
+/// Returns the launch tax at `now` in seconds, `None` once it has fully decayed
+/// The rate declines linearly from `start_rate` to `end_rate` over `duration` seconds
+pub fn decay_rate(decay: &TaxDecay, now: u64) -> Option<Decimal> {
+    if now >= decay.start_time + decay.duration {
+        return None;
+    }
+
+    let elapsed = now.saturating_sub(decay.start_time);
+    let decayed =
+        (decay.start_rate - decay.end_rate) * Decimal::from_ratio(elapsed, decay.duration);
+    Some(decay.start_rate - decayed)
+}
+
+/// Returns the rate of the largest tax tier reached by `amount`, relative to the total supply
+pub fn tier_rate(tiers: &[TaxTier], amount: Uint128, total_supply: Uint128) -> Option<Decimal> {
+    if tiers.is_empty() || total_supply.is_zero() {
+        return None;
+    }
+    let supply_rate = Decimal::from_ratio(amount, total_supply);
+
+    tiers
+        .iter()
+        .rev()
+        .find(|tier| tier.min_supply_rate <= supply_rate)
+        .map(|tier| tier.tax_rate)
+}
+
+/// Returns the anti-whale surtax of selling `amount` into a pair
+/// The surtax is the sold fraction of the total supply times `surtax_multiplier`, capped at `max_surtax`
+pub fn sell_surtax(config: &AntiWhaleTax, amount: Uint128, total_supply: Uint128) -> Decimal {
+    if config.max_surtax.is_zero() || total_supply.is_zero() {
+        return Decimal::zero();
+    }
+    let surtax = Decimal::from_ratio(amount, total_supply) * config.surtax_multiplier;
+
+    std::cmp::min(surtax, config.max_surtax)
+}
+
+/// Returns the price impact surtax of selling `amount` into a pair holding `reserve` tokens
+/// The impact is estimated as `amount / (reserve + amount)`, times `multiplier` and capped at `max_rate`
+pub fn price_impact_surtax(config: &PriceImpactTax, reserve: Uint128, amount: Uint128) -> Decimal {
+    if amount.is_zero() {
+        return Decimal::zero();
+    }
+    let impact = Decimal::from_ratio(amount, reserve + amount);
+
+    std::cmp::min(impact * config.multiplier, config.max_rate)
+}
@@ -0,0 +1,628 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use anyhow::{anyhow, bail, Result as AnyResult};
+use cosmwasm_std::testing::{mock_env, MockApi, MockStorage};
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, AllBalanceResponse, BalanceResponse, BankMsg, BankQuery,
+    Binary, BlockInfo, Coin, ContractInfo, ContractResult, CosmosMsg, Deps, DepsMut, Empty, Env,
+    Event, MessageInfo, Order, Querier, QuerierResult, QuerierWrapper, QueryRequest, Reply,
+    ReplyOn, Response, Storage, SubMsgResponse, SubMsgResult, SystemError, SystemResult,
+    TransactionInfo, Uint128, WasmMsg, WasmQuery,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Entry points of a contract stored in the `App`, with the messages still serialized
+pub trait Contract {
+    fn instantiate(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: &[u8],
+    ) -> AnyResult<Response>;
+
+    fn execute(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: &[u8],
+    ) -> AnyResult<Response>;
+
+    fn query(&self, deps: Deps, env: Env, msg: &[u8]) -> AnyResult<Binary>;
+
+    fn reply(&self, deps: DepsMut, env: Env, msg: Reply) -> AnyResult<Response>;
+
+    fn migrate(&self, deps: DepsMut, env: Env, msg: &[u8]) -> AnyResult<Response>;
+}
+
+type ExecuteFn = Box<dyn Fn(DepsMut, Env, MessageInfo, &[u8]) -> AnyResult<Response>>;
+type QueryFn = Box<dyn Fn(Deps, Env, &[u8]) -> AnyResult<Binary>>;
+type ReplyFn = Box<dyn Fn(DepsMut, Env, Reply) -> AnyResult<Response>>;
+type MigrateFn = Box<dyn Fn(DepsMut, Env, &[u8]) -> AnyResult<Response>>;
+
+/// Wraps the entry points of a contract crate, e.g.
+/// `ContractWrapper::new(contract::execute, contract::instantiate, contract::query)`
+pub struct ContractWrapper {
+    execute_fn: ExecuteFn,
+    instantiate_fn: ExecuteFn,
+    query_fn: QueryFn,
+    reply_fn: Option<ReplyFn>,
+    migrate_fn: Option<MigrateFn>,
+}
+
+impl ContractWrapper {
+    pub fn new<T1, T2, T3, E1, E2, E3>(
+        execute_fn: fn(DepsMut, Env, MessageInfo, T1) -> Result<Response, E1>,
+        instantiate_fn: fn(DepsMut, Env, MessageInfo, T2) -> Result<Response, E2>,
+        query_fn: fn(Deps, Env, T3) -> Result<Binary, E3>,
+    ) -> Self
+    where
+        T1: DeserializeOwned + 'static,
+        T2: DeserializeOwned + 'static,
+        T3: DeserializeOwned + 'static,
+        E1: Display + 'static,
+        E2: Display + 'static,
+        E3: Display + 'static,
+    {
+        ContractWrapper {
+            execute_fn: Box::new(move |deps, env, info, msg| {
+                execute_fn(deps, env, info, from_json(msg)?).map_err(|err| anyhow!("{}", err))
+            }),
+            instantiate_fn: Box::new(move |deps, env, info, msg| {
+                instantiate_fn(deps, env, info, from_json(msg)?).map_err(|err| anyhow!("{}", err))
+            }),
+            query_fn: Box::new(move |deps, env, msg| {
+                query_fn(deps, env, from_json(msg)?).map_err(|err| anyhow!("{}", err))
+            }),
+            reply_fn: None,
+            migrate_fn: None,
+        }
+    }
+
+    pub fn with_reply<E4: Display + 'static>(
+        mut self,
+        reply_fn: fn(DepsMut, Env, Reply) -> Result<Response, E4>,
+    ) -> Self {
+        self.reply_fn = Some(Box::new(move |deps, env, msg| {
+            reply_fn(deps, env, msg).map_err(|err| anyhow!("{}", err))
+        }));
+        self
+    }
+
+    pub fn with_migrate<T4: DeserializeOwned + 'static, E5: Display + 'static>(
+        mut self,
+        migrate_fn: fn(DepsMut, Env, T4) -> Result<Response, E5>,
+    ) -> Self {
+        self.migrate_fn = Some(Box::new(move |deps, env, msg| {
+            migrate_fn(deps, env, from_json(msg)?).map_err(|err| anyhow!("{}", err))
+        }));
+        self
+    }
+}
+
+impl Contract for ContractWrapper {
+    fn instantiate(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: &[u8],
+    ) -> AnyResult<Response> {
+        (self.instantiate_fn)(deps, env, info, msg)
+    }
+
+    fn execute(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: &[u8],
+    ) -> AnyResult<Response> {
+        (self.execute_fn)(deps, env, info, msg)
+    }
+
+    fn query(&self, deps: Deps, env: Env, msg: &[u8]) -> AnyResult<Binary> {
+        (self.query_fn)(deps, env, msg)
+    }
+
+    fn reply(&self, deps: DepsMut, env: Env, msg: Reply) -> AnyResult<Response> {
+        match &self.reply_fn {
+            Some(reply_fn) => reply_fn(deps, env, msg),
+            None => bail!("Contract does not handle replies"),
+        }
+    }
+
+    fn migrate(&self, deps: DepsMut, env: Env, msg: &[u8]) -> AnyResult<Response> {
+        match &self.migrate_fn {
+            Some(migrate_fn) => migrate_fn(deps, env, msg),
+            None => bail!("Contract does not handle migrations"),
+        }
+    }
+}
+
+/// Events and data of an executed message, its sub messages included
+#[derive(Clone, Debug, Default)]
+pub struct AppResponse {
+    pub events: Vec<Event>,
+    pub data: Option<Binary>,
+}
+
+impl AppResponse {
+    /// Returns the first event of type `ty`, custom events being prefixed with `wasm-`
+    pub fn event(&self, ty: &str) -> Option<&Event> {
+        self.events.iter().find(|event| event.ty == ty)
+    }
+}
+
+struct ContractData {
+    code_id: u64,
+    storage: MockStorage,
+}
+
+impl Clone for ContractData {
+    fn clone(&self) -> Self {
+        ContractData {
+            code_id: self.code_id,
+            storage: copy_storage(&self.storage),
+        }
+    }
+}
+
+fn copy_storage(storage: &MockStorage) -> MockStorage {
+    let mut copy = MockStorage::new();
+    for (key, value) in storage.range(None, None, Order::Ascending) {
+        copy.set(&key, &value);
+    }
+    copy
+}
+
+/// Everything a failed message rolls back
+#[derive(Clone, Default)]
+struct State {
+    contracts: BTreeMap<Addr, ContractData>,
+    balances: BTreeMap<(Addr, String), Uint128>,
+}
+
+/// Multi-contract executor modeled on cw-multi-test: contracts are called in-process, their
+/// messages and sub messages are dispatched in order with replies, and a failed message rolls
+/// back everything it changed. Supports wasm execute, instantiate and migrate, bank send and
+/// burn, and smart, raw and bank queries. Contracts have no admin, anyone can migrate them
+pub struct App {
+    api: MockApi,
+    block: BlockInfo,
+    codes: Vec<Box<dyn Contract>>,
+    state: State,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        App {
+            api: MockApi::default(),
+            block: mock_env().block,
+            codes: vec![],
+            state: State::default(),
+        }
+    }
+}
+
+impl App {
+    pub fn store_code(&mut self, code: Box<dyn Contract>) -> u64 {
+        self.codes.push(code);
+        self.codes.len() as u64
+    }
+
+    pub fn instantiate_contract<T: Serialize>(
+        &mut self,
+        code_id: u64,
+        sender: Addr,
+        msg: &T,
+        funds: &[Coin],
+    ) -> AnyResult<Addr> {
+        let msg = to_json_binary(msg)?;
+        self.transact(|app| app.instantiate(&sender, code_id, &msg, funds))
+            .map(|(address, _)| address)
+    }
+
+    pub fn execute_contract<T: Serialize>(
+        &mut self,
+        sender: Addr,
+        contract: Addr,
+        msg: &T,
+        funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        let msg = to_json_binary(msg)?;
+        self.transact(|app| app.execute(&sender, &contract, &msg, funds))
+    }
+
+    /// Moves `contract` to `new_code_id` and calls the migrate entry point of the new code
+    pub fn migrate_contract<T: Serialize>(
+        &mut self,
+        sender: Addr,
+        contract: Addr,
+        new_code_id: u64,
+        msg: &T,
+    ) -> AnyResult<AppResponse> {
+        let msg = to_json_binary(msg)?;
+        self.transact(|app| app.migrate(&sender, &contract, new_code_id, &msg))
+    }
+
+    pub fn wrap(&self) -> QuerierWrapper<'_, Empty> {
+        QuerierWrapper::new(self)
+    }
+
+    pub fn block_info(&self) -> BlockInfo {
+        self.block.clone()
+    }
+
+    pub fn update_block<F: FnOnce(&mut BlockInfo)>(&mut self, update: F) {
+        update(&mut self.block);
+    }
+
+    /// Moves to the next block, 5 seconds later
+    pub fn next_block(&mut self) {
+        self.block.height += 1;
+        self.block.time = self.block.time.plus_seconds(5);
+    }
+
+    /// Mints native coins to `address`
+    pub fn init_balance(&mut self, address: &Addr, coins: Vec<Coin>) {
+        for coin in coins {
+            *self
+                .state
+                .balances
+                .entry((address.clone(), coin.denom))
+                .or_default() += coin.amount;
+        }
+    }
+
+    fn transact<T, F: FnOnce(&mut Self) -> AnyResult<T>>(&mut self, f: F) -> AnyResult<T> {
+        let snapshot = self.state.clone();
+        let res = f(self);
+        if res.is_err() {
+            self.state = snapshot;
+        }
+        res
+    }
+
+    fn env(&self, contract: &Addr) -> Env {
+        Env {
+            block: self.block.clone(),
+            transaction: Some(TransactionInfo { index: 0 }),
+            contract: ContractInfo {
+                address: contract.clone(),
+            },
+        }
+    }
+
+    fn balance(&self, address: &Addr, denom: &str) -> Uint128 {
+        self.state
+            .balances
+            .get(&(address.clone(), denom.to_string()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn all_balances(&self, address: &Addr) -> Vec<Coin> {
+        self.state
+            .balances
+            .iter()
+            .filter(|((owner, _), amount)| owner == address && !amount.is_zero())
+            .map(|((_, denom), amount)| Coin {
+                denom: denom.clone(),
+                amount: *amount,
+            })
+            .collect()
+    }
+
+    fn send(&mut self, from: &Addr, to: &Addr, coins: &[Coin]) -> AnyResult<()> {
+        for coin in coins {
+            let from_balance = self.balance(from, &coin.denom);
+            let from_balance = from_balance.checked_sub(coin.amount).map_err(|_| {
+                anyhow!(
+                    "{} has {}{}, cannot send {}",
+                    from,
+                    from_balance,
+                    coin.denom,
+                    coin
+                )
+            })?;
+            self.state
+                .balances
+                .insert((from.clone(), coin.denom.clone()), from_balance);
+            *self
+                .state
+                .balances
+                .entry((to.clone(), coin.denom.clone()))
+                .or_default() += coin.amount;
+        }
+        Ok(())
+    }
+
+    fn burn(&mut self, from: &Addr, coins: &[Coin]) -> AnyResult<()> {
+        for coin in coins {
+            let balance = self
+                .balance(from, &coin.denom)
+                .checked_sub(coin.amount)
+                .map_err(|_| anyhow!("{} cannot burn {}", from, coin))?;
+            self.state
+                .balances
+                .insert((from.clone(), coin.denom.clone()), balance);
+        }
+        Ok(())
+    }
+
+    /// Calls an entry point of `contract` on a copy of its storage, the copy is kept if it succeeds.
+    /// Queries made by the contract see the state before the call
+    fn call<F>(&mut self, contract: &Addr, f: F) -> AnyResult<Response>
+    where
+        F: FnOnce(&dyn Contract, DepsMut, Env) -> AnyResult<Response>,
+    {
+        let data = self
+            .state
+            .contracts
+            .get(contract)
+            .ok_or_else(|| anyhow!("No contract at {}", contract))?;
+        let mut storage = copy_storage(&data.storage);
+        let code = &self.codes[data.code_id as usize - 1];
+        let deps = DepsMut {
+            storage: &mut storage,
+            api: &self.api,
+            querier: QuerierWrapper::new(self),
+        };
+        let res = f(code.as_ref(), deps, self.env(contract))?;
+
+        if let Some(data) = self.state.contracts.get_mut(contract) {
+            data.storage = storage;
+        }
+        Ok(res)
+    }
+
+    fn instantiate(
+        &mut self,
+        sender: &Addr,
+        code_id: u64,
+        msg: &[u8],
+        funds: &[Coin],
+    ) -> AnyResult<(Addr, AppResponse)> {
+        if code_id == 0 || code_id as usize > self.codes.len() {
+            bail!("No code with id {}", code_id);
+        }
+        let address = Addr::unchecked(format!("contract{}", self.state.contracts.len()));
+        self.state.contracts.insert(
+            address.clone(),
+            ContractData {
+                code_id,
+                storage: MockStorage::new(),
+            },
+        );
+        self.send(sender, &address, funds)?;
+
+        let info = MessageInfo {
+            sender: sender.clone(),
+            funds: funds.to_vec(),
+        };
+        let res = self.call(&address, |code, deps, env| {
+            code.instantiate(deps, env, info, msg)
+        })?;
+        let mut res = self.process_response(&address, "instantiate", res)?;
+        res.data = Some(instantiate_response_data(&address, res.data.as_ref()));
+        Ok((address, res))
+    }
+
+    fn execute(
+        &mut self,
+        sender: &Addr,
+        contract: &Addr,
+        msg: &[u8],
+        funds: &[Coin],
+    ) -> AnyResult<AppResponse> {
+        self.send(sender, contract, funds)?;
+
+        let info = MessageInfo {
+            sender: sender.clone(),
+            funds: funds.to_vec(),
+        };
+        let res = self.call(contract, |code, deps, env| {
+            code.execute(deps, env, info, msg)
+        })?;
+        self.process_response(contract, "execute", res)
+    }
+
+    fn migrate(
+        &mut self,
+        _sender: &Addr,
+        contract: &Addr,
+        new_code_id: u64,
+        msg: &[u8],
+    ) -> AnyResult<AppResponse> {
+        if new_code_id == 0 || new_code_id as usize > self.codes.len() {
+            bail!("No code with id {}", new_code_id);
+        }
+        match self.state.contracts.get_mut(contract) {
+            Some(data) => data.code_id = new_code_id,
+            None => bail!("No contract at {}", contract),
+        }
+
+        let res = self.call(contract, |code, deps, env| code.migrate(deps, env, msg))?;
+        self.process_response(contract, "migrate", res)
+    }
+
+    fn reply(&mut self, contract: &Addr, reply: Reply) -> AnyResult<AppResponse> {
+        let res = self.call(contract, |code, deps, env| code.reply(deps, env, reply))?;
+        self.process_response(contract, "reply", res)
+    }
+
+    /// Collects the events of `res` and dispatches its sub messages in order
+    fn process_response(
+        &mut self,
+        contract: &Addr,
+        ty: &str,
+        res: Response,
+    ) -> AnyResult<AppResponse> {
+        let mut events = vec![Event::new(ty).add_attribute("_contract_address", contract)];
+        if !res.attributes.is_empty() {
+            events.push(
+                Event::new("wasm")
+                    .add_attribute("_contract_address", contract)
+                    .add_attributes(res.attributes),
+            );
+        }
+        events.extend(res.events.into_iter().map(|event| {
+            Event::new(format!("wasm-{}", event.ty))
+                .add_attribute("_contract_address", contract)
+                .add_attributes(event.attributes)
+        }));
+        let mut data = res.data;
+
+        for msg in res.messages {
+            let snapshot = self.state.clone();
+            let result = match self.dispatch(contract, msg.msg) {
+                Ok(sub) => {
+                    events.extend(sub.events.clone());
+                    if !matches!(msg.reply_on, ReplyOn::Always | ReplyOn::Success) {
+                        continue;
+                    }
+                    SubMsgResult::Ok(SubMsgResponse {
+                        events: sub.events,
+                        data: sub.data,
+                    })
+                }
+                Err(err) => {
+                    self.state = snapshot;
+                    if !matches!(msg.reply_on, ReplyOn::Always | ReplyOn::Error) {
+                        return Err(err);
+                    }
+                    SubMsgResult::Err(err.to_string())
+                }
+            };
+
+            let reply = self.reply(contract, Reply { id: msg.id, result })?;
+            events.extend(reply.events);
+            if reply.data.is_some() {
+                data = reply.data;
+            }
+        }
+
+        Ok(AppResponse { events, data })
+    }
+
+    fn dispatch(&mut self, sender: &Addr, msg: CosmosMsg) -> AnyResult<AppResponse> {
+        match msg {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                msg,
+                funds,
+            }) => self.execute(sender, &Addr::unchecked(contract_addr), &msg, &funds),
+            CosmosMsg::Wasm(WasmMsg::Instantiate {
+                code_id,
+                msg,
+                funds,
+                ..
+            }) => self
+                .instantiate(sender, code_id, &msg, &funds)
+                .map(|(_, res)| res),
+            CosmosMsg::Wasm(WasmMsg::Migrate {
+                contract_addr,
+                new_code_id,
+                msg,
+            }) => self.migrate(sender, &Addr::unchecked(contract_addr), new_code_id, &msg),
+            CosmosMsg::Bank(BankMsg::Send { to_address, amount }) => {
+                self.send(sender, &Addr::unchecked(to_address), &amount)?;
+                Ok(AppResponse::default())
+            }
+            CosmosMsg::Bank(BankMsg::Burn { amount }) => {
+                self.burn(sender, &amount)?;
+                Ok(AppResponse::default())
+            }
+            msg => bail!("Unsupported message: {:?}", msg),
+        }
+    }
+
+    fn query_wasm(&self, contract: &Addr, msg: &[u8]) -> AnyResult<Binary> {
+        let data = self
+            .state
+            .contracts
+            .get(contract)
+            .ok_or_else(|| anyhow!("No contract at {}", contract))?;
+        let deps = Deps {
+            storage: &data.storage,
+            api: &self.api,
+            querier: QuerierWrapper::new(self),
+        };
+        self.codes[data.code_id as usize - 1].query(deps, self.env(contract), msg)
+    }
+}
+
+/// Protobuf encoded `MsgInstantiateContractResponse`, the data of an instantiate sub message
+fn instantiate_response_data(address: &Addr, data: Option<&Binary>) -> Binary {
+    let mut encoded = vec![];
+    encode_field(&mut encoded, 1, address.as_bytes());
+    if let Some(data) = data {
+        encode_field(&mut encoded, 2, data.as_slice());
+    }
+    encoded.into()
+}
+
+fn encode_field(encoded: &mut Vec<u8>, field: u8, value: &[u8]) {
+    // length delimited wire type
+    encoded.push(field << 3 | 2);
+    let mut len = value.len();
+    while len >= 0x80 {
+        encoded.push((len as u8 & 0x7f) | 0x80);
+        len >>= 7;
+    }
+    encoded.push(len as u8);
+    encoded.extend_from_slice(value);
+}
+
+impl Querier for App {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        let request: QueryRequest<Empty> = match from_json(bin_request) {
+            Ok(request) => request,
+            Err(err) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: err.to_string(),
+                    request: bin_request.into(),
+                })
+            }
+        };
+
+        let res = match request {
+            QueryRequest::Bank(BankQuery::Balance { address, denom }) => {
+                let amount = self.balance(&Addr::unchecked(address), &denom);
+                to_json_binary(&BalanceResponse {
+                    amount: Coin { denom, amount },
+                })
+                .map_err(Into::into)
+            }
+            QueryRequest::Bank(BankQuery::AllBalances { address }) => {
+                to_json_binary(&AllBalanceResponse {
+                    amount: self.all_balances(&Addr::unchecked(address)),
+                })
+                .map_err(Into::into)
+            }
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg }) => {
+                self.query_wasm(&Addr::unchecked(contract_addr), &msg)
+            }
+            QueryRequest::Wasm(WasmQuery::Raw { contract_addr, key }) => {
+                match self.state.contracts.get(&Addr::unchecked(&contract_addr)) {
+                    Some(data) => Ok(data.storage.get(&key).unwrap_or_default().into()),
+                    None => Err(anyhow!("No contract at {}", contract_addr)),
+                }
+            }
+            request => {
+                return SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: format!("{:?}", request),
+                })
+            }
+        };
+
+        SystemResult::Ok(match res {
+            Ok(res) => ContractResult::Ok(res),
+            Err(err) => ContractResult::Err(err.to_string()),
+        })
+    }
+}
@@ -0,0 +1,108 @@
+//! Storage of a deployed 1.0.0 reflection token, to test migrations. Instantiating it writes the
+//! 1.0.0 layout: the admin as a plain item, a single tax rate and the pairlist as flags. It only
+//! executes the 1.0.0 `SetTreasury`, the state is upgraded by migrating it to the current token code
+
+use cosmwasm_std::{
+    Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128,
+};
+use cw20_base::state::{TokenInfo, BALANCES, TOKEN_INFO};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::app::ContractWrapper;
+
+// name and version the 1.0.0 token stored in cw2
+pub const CONTRACT_NAME: &str = "qtum:reflection";
+pub const CONTRACT_VERSION: &str = "1.0.0";
+
+pub const TAX_RATE: Item<Decimal> = Item::new("tax_rate");
+pub const REFLECTION_RATE: Item<Decimal> = Item::new("reflection_rate");
+pub const BURN_RATE: Item<Decimal> = Item::new("burn_rate");
+pub const ADMIN: Item<String> = Item::new("admin");
+pub const LAST_LIQUIFY: Item<u64> = Item::new("last_liquify");
+pub const TREASURY: Item<String> = Item::new("treasury");
+pub const PAIRLIST: Map<String, bool> = Map::new("pairlist");
+pub const BUYBACK_ENABLE: Item<bool> = Item::new("buyback_enable");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub admin: String,
+    pub balances: Vec<(String, Uint128)>,
+    /// Pairlist entries, disabled pairs included
+    pub pairs: Vec<(String, bool)>,
+    pub tax_rate: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    SetTreasury { contract: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {}
+
+pub fn contract() -> Box<ContractWrapper> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let mut total_supply = Uint128::zero();
+    for (address, amount) in msg.balances {
+        let address = deps.api.addr_validate(&address)?;
+        BALANCES.save(deps.storage, &address, &amount)?;
+        total_supply += amount;
+    }
+    TOKEN_INFO.save(
+        deps.storage,
+        &TokenInfo {
+            name: "Baby Token".to_string(),
+            symbol: "BABY".to_string(),
+            decimals: 6,
+            total_supply,
+            mint: None,
+        },
+    )?;
+
+    for (pair, enabled) in msg.pairs {
+        PAIRLIST.save(deps.storage, pair, &enabled)?;
+    }
+    ADMIN.save(deps.storage, &msg.admin)?;
+    TAX_RATE.save(deps.storage, &msg.tax_rate)?;
+    REFLECTION_RATE.save(deps.storage, &Decimal::zero())?;
+    BURN_RATE.save(deps.storage, &Decimal::zero())?;
+    LAST_LIQUIFY.save(deps.storage, &env.block.time.seconds())?;
+    BUYBACK_ENABLE.save(deps.storage, &true)?;
+    Ok(Response::default())
+}
+
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::SetTreasury { contract } => {
+            if info.sender != ADMIN.load(deps.storage)? {
+                return Err(StdError::generic_err("Unauthorized"));
+            }
+            deps.api.addr_validate(&contract)?;
+            TREASURY.save(deps.storage, &contract)?;
+            Ok(Response::default())
+        }
+    }
+}
+
+pub fn query(_deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {}
+}
@@ -0,0 +1,8 @@
+pub mod app;
+pub mod legacy_token;
+pub mod pair;
+pub mod router;
+pub mod treasury;
+
+#[cfg(test)]
+mod testing;
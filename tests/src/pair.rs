@@ -0,0 +1,276 @@
+//! Constant product pair with the DojoSwap pair interface and no fees.
+//! The reserves are the balances of the pair, so a pool is seeded by sending it both assets. The
+//! pair is its own liquidity token, answering the cw20 `Balance` query and `Transfer` for its shares
+
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult, Uint128, WasmMsg,
+};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::{Item, Map};
+use dojoswap::asset::{Asset, AssetInfo};
+use dojoswap::pair::{Cw20HookMsg, ExecuteMsg, PoolResponse, SimulationResponse};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::app::ContractWrapper;
+
+pub const ASSET_INFOS: Item<[AssetInfo; 2]> = Item::new("asset_infos");
+pub const SHARES: Map<&Addr, Uint128> = Map::new("shares");
+pub const TOTAL_SHARE: Item<Uint128> = Item::new("total_share");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub asset_infos: [AssetInfo; 2],
+}
+
+/// The DojoSwap pair messages and the cw20 transfer of the liquidity token
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(untagged)]
+pub enum PairExecuteMsg {
+    Pair(ExecuteMsg),
+    Lp(LpExecuteMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LpExecuteMsg {
+    Transfer { recipient: String, amount: Uint128 },
+}
+
+/// The DojoSwap pair queries and the cw20 balance of the liquidity token
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Pair {},
+    Pool {},
+    Simulation { offer_asset: Asset },
+    Balance { address: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PairResponse {
+    pub asset_infos: [AssetInfo; 2],
+    pub contract_addr: String,
+    pub liquidity_token: String,
+}
+
+pub fn contract() -> Box<ContractWrapper> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    ASSET_INFOS.save(deps.storage, &msg.asset_infos)?;
+    TOTAL_SHARE.save(deps.storage, &Uint128::zero())?;
+    Ok(Response::default())
+}
+
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: PairExecuteMsg,
+) -> StdResult<Response> {
+    let msg = match msg {
+        PairExecuteMsg::Pair(msg) => msg,
+        PairExecuteMsg::Lp(LpExecuteMsg::Transfer { recipient, amount }) => {
+            let recipient = deps.api.addr_validate(&recipient)?;
+            SHARES.update(deps.storage, &info.sender, |shares| -> StdResult<_> {
+                Ok(shares.unwrap_or_default().checked_sub(amount)?)
+            })?;
+            SHARES.update(deps.storage, &recipient, |shares| -> StdResult<_> {
+                Ok(shares.unwrap_or_default() + amount)
+            })?;
+            return Ok(Response::new()
+                .add_attribute("action", "transfer")
+                .add_attribute("amount", amount));
+        }
+    };
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::Swap {
+            offer_asset, to, ..
+        } => {
+            offer_asset.assert_sent_native_token_balance(&info)?;
+            let to = to.unwrap_or_else(|| info.sender.to_string());
+            swap(deps.as_ref(), env, offer_asset, to)
+        }
+        ExecuteMsg::ProvideLiquidity {
+            assets, receiver, ..
+        } => provide_liquidity(deps, env, info, assets, receiver),
+        ExecuteMsg::AdminConfigure { .. } => Err(StdError::generic_err("Unsupported")),
+    }
+}
+
+fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> StdResult<Response> {
+    match from_json(&msg.msg)? {
+        Cw20HookMsg::Swap { to, .. } => {
+            let offer_asset = Asset {
+                info: AssetInfo::Token {
+                    contract_addr: info.sender.to_string(),
+                },
+                amount: msg.amount,
+            };
+            swap(deps.as_ref(), env, offer_asset, to.unwrap_or(msg.sender))
+        }
+        Cw20HookMsg::WithdrawLiquidity { .. } => Err(StdError::generic_err("Unsupported")),
+    }
+}
+
+/// Returns the reserves of the pair, without the assets offered in the message being executed
+fn reserves(deps: Deps, env: &Env, offered: &[Asset]) -> StdResult<[Asset; 2]> {
+    let asset_infos = ASSET_INFOS.load(deps.storage)?;
+    let reserve = |info: &AssetInfo| -> StdResult<Asset> {
+        let balance = info.query_pool(&deps.querier, deps.api, env.contract.address.clone())?;
+        let offered: Uint128 = offered
+            .iter()
+            .filter(|asset| asset.info.equal(info))
+            .map(|asset| asset.amount)
+            .sum();
+        Ok(Asset {
+            info: info.clone(),
+            amount: balance.checked_sub(offered)?,
+        })
+    };
+    Ok([reserve(&asset_infos[0])?, reserve(&asset_infos[1])?])
+}
+
+/// Returns the reserves of the offer and ask assets of a swap of `offer_asset`
+fn swap_reserves(reserves: [Asset; 2], offer_asset: &Asset) -> StdResult<(Asset, Asset)> {
+    let [first, second] = reserves;
+    if first.info.equal(&offer_asset.info) {
+        Ok((first, second))
+    } else if second.info.equal(&offer_asset.info) {
+        Ok((second, first))
+    } else {
+        Err(StdError::generic_err("Offer asset is not in the pair"))
+    }
+}
+
+fn return_amount(offer_reserve: Uint128, ask_reserve: Uint128, offer_amount: Uint128) -> Uint128 {
+    ask_reserve.multiply_ratio(offer_amount, offer_reserve + offer_amount)
+}
+
+/// Swaps `offer_asset`, already received by the pair, and sends the ask asset to `to`
+fn swap(deps: Deps, env: Env, offer_asset: Asset, to: String) -> StdResult<Response> {
+    let reserves = reserves(deps, &env, std::slice::from_ref(&offer_asset))?;
+    let (offer_reserve, ask_reserve) = swap_reserves(reserves, &offer_asset)?;
+    let amount = return_amount(offer_reserve.amount, ask_reserve.amount, offer_asset.amount);
+
+    let return_asset = Asset {
+        info: ask_reserve.info,
+        amount,
+    };
+    Ok(Response::new()
+        .add_message(return_asset.into_msg(deps.api.addr_validate(&to)?)?)
+        .add_attribute("action", "swap")
+        .add_attribute("offer_amount", offer_asset.amount)
+        .add_attribute("return_amount", amount))
+}
+
+/// Pulls the cw20 assets with `TransferFrom`, native assets must be attached. The first deposit
+/// mints as many shares as the first asset deposited
+fn provide_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    assets: [Asset; 2],
+    receiver: Option<String>,
+) -> StdResult<Response> {
+    let mut messages = vec![];
+    let mut offered = vec![];
+    for asset in assets.iter() {
+        match &asset.info {
+            AssetInfo::NativeToken { .. } => {
+                asset.assert_sent_native_token_balance(&info)?;
+                offered.push(asset.clone());
+            }
+            AssetInfo::Token { contract_addr } => messages.push(WasmMsg::Execute {
+                contract_addr: contract_addr.clone(),
+                msg: to_json_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: info.sender.to_string(),
+                    recipient: env.contract.address.to_string(),
+                    amount: asset.amount,
+                })?,
+                funds: vec![],
+            }),
+        }
+    }
+
+    let reserves = reserves(deps.as_ref(), &env, &offered)?;
+    let deposit = |reserve: &Asset| -> StdResult<Uint128> {
+        assets
+            .iter()
+            .find(|asset| asset.info.equal(&reserve.info))
+            .map(|asset| asset.amount)
+            .ok_or_else(|| StdError::generic_err("Asset is not in the pair"))
+    };
+    let deposits = [deposit(&reserves[0])?, deposit(&reserves[1])?];
+
+    let total_share = TOTAL_SHARE.load(deps.storage)?;
+    let share = if total_share.is_zero() {
+        deposits[0]
+    } else {
+        std::cmp::min(
+            deposits[0].multiply_ratio(total_share, reserves[0].amount),
+            deposits[1].multiply_ratio(total_share, reserves[1].amount),
+        )
+    };
+
+    let receiver = match receiver {
+        Some(receiver) => deps.api.addr_validate(&receiver)?,
+        None => info.sender,
+    };
+    TOTAL_SHARE.save(deps.storage, &(total_share + share))?;
+    SHARES.update(deps.storage, &receiver, |shares| -> StdResult<_> {
+        Ok(shares.unwrap_or_default() + share)
+    })?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "provide_liquidity")
+        .add_attribute("share", share))
+}
+
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Pair {} => to_json_binary(&PairResponse {
+            asset_infos: ASSET_INFOS.load(deps.storage)?,
+            contract_addr: env.contract.address.to_string(),
+            liquidity_token: env.contract.address.to_string(),
+        }),
+        QueryMsg::Pool {} => to_json_binary(&PoolResponse {
+            assets: reserves(deps, &env, &[])?,
+            total_share: TOTAL_SHARE.load(deps.storage)?,
+        }),
+        QueryMsg::Simulation { offer_asset } => {
+            let reserves = reserves(deps, &env, &[])?;
+            let (offer_reserve, ask_reserve) = swap_reserves(reserves, &offer_asset)?;
+            to_json_binary(&SimulationResponse {
+                return_amount: return_amount(
+                    offer_reserve.amount,
+                    ask_reserve.amount,
+                    offer_asset.amount,
+                ),
+                spread_amount: Uint128::zero(),
+                commission_amount: Uint128::zero(),
+            })
+        }
+        QueryMsg::Balance { address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(&BalanceResponse {
+                balance: SHARES.may_load(deps.storage, &address)?.unwrap_or_default(),
+            })
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Router with the DojoSwap router interface, swapping along the pairs it is instantiated with.
+//! Like the DojoSwap router, each hop swaps the whole router balance of the offer asset
+
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError,
+    StdResult, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::Item;
+use dojoswap::asset::{Asset, AssetInfo};
+use dojoswap::pair::{Cw20HookMsg as PairCw20HookMsg, ExecuteMsg as PairExecuteMsg};
+use dojoswap::router::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, QueryMsg, SimulateSwapOperationsResponse,
+    SwapOperation,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::app::ContractWrapper;
+use crate::pair::{PairResponse, QueryMsg as PairQueryMsg};
+
+pub const PAIRS: Item<Vec<Addr>> = Item::new("pairs");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub pairs: Vec<String>,
+}
+
+pub fn contract() -> Box<ContractWrapper> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    let pairs = msg
+        .pairs
+        .iter()
+        .map(|pair| deps.api.addr_validate(pair))
+        .collect::<StdResult<Vec<_>>>()?;
+    PAIRS.save(deps.storage, &pairs)?;
+    Ok(Response::default())
+}
+
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, msg),
+        ExecuteMsg::ExecuteSwapOperations {
+            operations,
+            minimum_receive,
+            to,
+            ..
+        } => {
+            let to = to.unwrap_or_else(|| info.sender.to_string());
+            execute_swap_operations(deps.as_ref(), env, operations, minimum_receive, to)
+        }
+        ExecuteMsg::ExecuteSwapOperation { operation, to, .. } => {
+            if info.sender != env.contract.address {
+                return Err(StdError::generic_err("unauthorized"));
+            }
+            execute_swap_operation(deps.as_ref(), env, operation, to)
+        }
+        ExecuteMsg::AssertMinimumReceive {
+            asset_info,
+            prev_balance,
+            minimum_receive,
+            receiver,
+        } => {
+            let receiver = deps.api.addr_validate(&receiver)?;
+            let balance = asset_info.query_pool(&deps.querier, deps.api, receiver)?;
+            if balance < prev_balance + minimum_receive {
+                return Err(StdError::generic_err(format!(
+                    "assertion failed; minimum receive amount: {}, swap amount: {}",
+                    minimum_receive,
+                    balance.saturating_sub(prev_balance)
+                )));
+            }
+            Ok(Response::default())
+        }
+    }
+}
+
+fn receive_cw20(deps: DepsMut, env: Env, msg: Cw20ReceiveMsg) -> StdResult<Response> {
+    match from_json(&msg.msg)? {
+        Cw20HookMsg::ExecuteSwapOperations {
+            operations,
+            minimum_receive,
+            to,
+            ..
+        } => {
+            let to = to.unwrap_or(msg.sender);
+            execute_swap_operations(deps.as_ref(), env, operations, minimum_receive, to)
+        }
+    }
+}
+
+/// Swaps hop by hop with internal messages, the last hop sends its output to `to`
+fn execute_swap_operations(
+    deps: Deps,
+    env: Env,
+    operations: Vec<SwapOperation>,
+    minimum_receive: Option<Uint128>,
+    to: String,
+) -> StdResult<Response> {
+    let last = match operations.last() {
+        Some(last) => last.get_target_asset_info(),
+        None => return Err(StdError::generic_err("must provide operations")),
+    };
+
+    let count = operations.len();
+    let mut messages = operations
+        .into_iter()
+        .enumerate()
+        .map(|(i, operation)| {
+            Ok(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_json_binary(&ExecuteMsg::ExecuteSwapOperation {
+                    operation,
+                    to: Some(to.clone()).filter(|_| i + 1 == count),
+                    deadline: None,
+                })?,
+                funds: vec![],
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    if let Some(minimum_receive) = minimum_receive {
+        let receiver = deps.api.addr_validate(&to)?;
+        messages.push(WasmMsg::Execute {
+            contract_addr: env.contract.address.to_string(),
+            msg: to_json_binary(&ExecuteMsg::AssertMinimumReceive {
+                prev_balance: last.query_pool(&deps.querier, deps.api, receiver)?,
+                asset_info: last,
+                minimum_receive,
+                receiver: to,
+            })?,
+            funds: vec![],
+        });
+    }
+
+    Ok(Response::new().add_messages(messages))
+}
+
+/// Swaps the whole router balance of the offer asset on the pair of the operation
+fn execute_swap_operation(
+    deps: Deps,
+    env: Env,
+    operation: SwapOperation,
+    to: Option<String>,
+) -> StdResult<Response> {
+    let SwapOperation::DojoSwap {
+        offer_asset_info,
+        ask_asset_info,
+    } = operation;
+    let pair = find_pair(deps, &offer_asset_info, &ask_asset_info)?;
+    let amount = offer_asset_info.query_pool(&deps.querier, deps.api, env.contract.address)?;
+
+    let msg = match &offer_asset_info {
+        AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+            contract_addr: contract_addr.clone(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Send {
+                contract: pair.to_string(),
+                amount,
+                msg: to_json_binary(&PairCw20HookMsg::Swap {
+                    belief_price: None,
+                    max_spread: None,
+                    to,
+                    deadline: None,
+                })?,
+            })?,
+            funds: vec![],
+        },
+        AssetInfo::NativeToken { denom } => WasmMsg::Execute {
+            contract_addr: pair.to_string(),
+            msg: to_json_binary(&PairExecuteMsg::Swap {
+                offer_asset: Asset {
+                    info: offer_asset_info.clone(),
+                    amount,
+                },
+                belief_price: None,
+                max_spread: None,
+                to,
+                deadline: None,
+            })?,
+            funds: vec![cosmwasm_std::coin(amount.u128(), denom)],
+        },
+    };
+
+    Ok(Response::new().add_message(msg))
+}
+
+fn find_pair(deps: Deps, offer: &AssetInfo, ask: &AssetInfo) -> StdResult<Addr> {
+    for pair in PAIRS.load(deps.storage)? {
+        let info: PairResponse = deps
+            .querier
+            .query_wasm_smart(pair.clone(), &PairQueryMsg::Pair {})?;
+        let assets = &info.asset_infos;
+        if assets.iter().any(|info| info.equal(offer)) && assets.iter().any(|info| info.equal(ask))
+        {
+            return Ok(pair);
+        }
+    }
+
+    Err(StdError::generic_err(format!(
+        "No pair for {} and {}",
+        offer, ask
+    )))
+}
+
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&ConfigResponse {
+            dojoswap_factory: String::new(),
+        }),
+        QueryMsg::SimulateSwapOperations {
+            offer_amount,
+            operations,
+        } => {
+            let mut amount = offer_amount;
+            for operation in operations {
+                let SwapOperation::DojoSwap {
+                    offer_asset_info,
+                    ask_asset_info,
+                } = operation;
+                let pair = find_pair(deps, &offer_asset_info, &ask_asset_info)?;
+                let simulation: dojoswap::pair::SimulationResponse =
+                    deps.querier.query_wasm_smart(
+                        pair,
+                        &PairQueryMsg::Simulation {
+                            offer_asset: Asset {
+                                info: offer_asset_info,
+                                amount,
+                            },
+                        },
+                    )?;
+                amount = simulation.return_amount;
+            }
+            to_json_binary(&SimulateSwapOperationsResponse { amount })
+        }
+        QueryMsg::ReverseSimulateSwapOperations { .. } => Err(StdError::generic_err("Unsupported")),
+    }
+}
@@ -0,0 +1,50 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cw2::{get_contract_version, set_contract_version};
+use qtum_reflection_denom::contract as denom_contract;
+use qtum_reflection_denom::msg::{InstantiateMsg, MigrateMsg};
+
+#[test]
+fn migrate_only_upgrades_the_reflection_denom() {
+    let mut deps = mock_dependencies();
+    let msg = InstantiateMsg {
+        subdenom: "baby".to_string(),
+        initial_balances: vec![],
+        treasury: None,
+    };
+    denom_contract::instantiate(deps.as_mut(), mock_env(), mock_info("admin", &[]), msg).unwrap();
+    let version = get_contract_version(&deps.storage).unwrap().version;
+
+    // an older reflection denom is upgraded
+    set_contract_version(&mut deps.storage, "qtum:reflection-denom", "1.0.0").unwrap();
+    let res = denom_contract::migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+    let attribute = |key: &str| {
+        res.attributes
+            .iter()
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.clone())
+    };
+    assert_eq!(attribute("from_version"), Some("1.0.0".to_string()));
+    assert_eq!(attribute("to_version"), Some(version.clone()));
+    assert_eq!(
+        get_contract_version(&deps.storage).unwrap().version,
+        version
+    );
+
+    // a newer version is not downgraded
+    set_contract_version(&mut deps.storage, "qtum:reflection-denom", "9.0.0").unwrap();
+    let err = denom_contract::migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert!(err.to_string().contains("Cannot downgrade"), "{}", err);
+
+    // neither is another contract
+    set_contract_version(&mut deps.storage, "qtum:reflection", "1.0.0").unwrap();
+    let err = denom_contract::migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+    assert!(
+        err.to_string().contains("Cannot migrate from contract"),
+        "{}",
+        err
+    );
+    assert_eq!(
+        get_contract_version(&deps.storage).unwrap().contract,
+        "qtum:reflection"
+    );
+}
@@ -0,0 +1,156 @@
+use cosmwasm_std::{Addr, Uint128};
+use cw20::BalanceResponse;
+use qtum_reflection_token::msg::{QueryMsg as TokenQueryMsg, SupplyCheckResponse};
+use qtum_treasury::msg::ExecuteMsg as TreasuryExecuteMsg;
+
+use crate::pair;
+use crate::testing::{MockSuite, Suite, REFLECTION_DENOM};
+use crate::treasury::ExecuteMsg as MockExecuteMsg;
+
+#[test]
+fn transfer_tax_is_liquified() {
+    let mut suite = Suite::new();
+    let supply = suite.total_supply();
+
+    let res = suite.transfer("alice", "bob", 1_000_000);
+    assert!(res.event("wasm-liquify_failed").is_none());
+    let liquify = res.event("wasm-liquify").unwrap();
+    assert!(liquify
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "amount" && attr.value == "50000"));
+
+    // bob receives the transfer minus the 5% tax
+    assert_eq!(
+        suite.balance(&Addr::unchecked("bob")),
+        Uint128::new(950_000)
+    );
+
+    // the 50000 tax is split into 20000 reflected, 5000 burned and 25000 paired
+    let stats = suite.stats();
+    assert_eq!(stats.liquify_count, 1);
+    assert_eq!(stats.total_liquified, Uint128::new(50_000));
+    assert_eq!(stats.total_burned, Uint128::new(5_000));
+    assert!(!stats.total_lp_minted.is_zero());
+    assert!(!stats.total_reflected.is_zero());
+    assert_eq!(suite.total_supply(), supply - Uint128::new(5_000));
+
+    // the reflection token bought is sent to the fee collector
+    let collected = suite
+        .app
+        .wrap()
+        .query_balance("collector", REFLECTION_DENOM)
+        .unwrap();
+    assert_eq!(collected.amount, stats.total_reflected);
+
+    // the treasury is left with the LP tokens only
+    assert_eq!(suite.balance(&suite.treasury), Uint128::zero());
+    let lp: BalanceResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            suite.liquidity_pair.clone(),
+            &pair::QueryMsg::Balance {
+                address: suite.treasury.to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(lp.balance, stats.total_lp_minted);
+}
+
+#[test]
+fn failed_liquify_keeps_taxes_in_treasury() {
+    let mut suite = Suite::new();
+
+    // the reflection swap fails without a router to send the babyTOKEN to
+    suite
+        .app
+        .execute_contract(
+            suite.admin.clone(),
+            suite.treasury.clone(),
+            &TreasuryExecuteMsg::SetRouter {
+                address: "nowhere".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+    let res = suite.transfer("alice", "bob", 1_000_000);
+    assert!(res.event("wasm-liquify_failed").is_some());
+
+    // the transfer went through and the whole tax waits for the next liquify
+    assert_eq!(
+        suite.balance(&Addr::unchecked("bob")),
+        Uint128::new(950_000)
+    );
+    assert_eq!(suite.balance(&suite.treasury), Uint128::new(50_000));
+    assert_eq!(suite.stats().liquify_count, 0);
+}
+
+#[test]
+fn transfer_tax_triggers_liquify() {
+    let mut suite = MockSuite::new();
+
+    suite.transfer("alice", "bob", 1_000_000).unwrap();
+
+    // the treasury is credited before it is called
+    let res = suite.liquify_calls();
+    assert_eq!(res.calls.len(), 1);
+    assert_eq!(res.calls[0].sender, suite.token);
+    assert_eq!(res.calls[0].balance, Uint128::new(50_000));
+
+    // the cooldown skips the liquify of a transfer in the same block
+    suite.transfer("alice", "bob", 1_000_000).unwrap();
+    assert_eq!(suite.liquify_calls().calls.len(), 1);
+}
+
+#[test]
+fn failed_liquify_does_not_fail_transfer() {
+    let mut suite = MockSuite::new();
+    suite
+        .app
+        .execute_contract(
+            suite.admin.clone(),
+            suite.treasury.clone(),
+            &MockExecuteMsg::SetFailing { failing: true },
+            &[],
+        )
+        .unwrap();
+
+    let res = suite.transfer("alice", "bob", 1_000_000).unwrap();
+    assert!(res.event("wasm-liquify_failed").is_some());
+    assert!(suite.liquify_calls().calls.is_empty());
+    assert_eq!(suite.balance(suite.treasury.as_str()), Uint128::new(50_000));
+}
+
+#[test]
+fn balances_add_up_to_total_supply() {
+    let mut suite = MockSuite::new();
+    suite.transfer("alice", "bob", 1_000_000).unwrap();
+
+    // admin, alice, bob and the treasury, summed one per page
+    let mut pages = 0;
+    let mut start_after = None;
+    let mut running_sum = None;
+    let res = loop {
+        let res: SupplyCheckResponse = suite.query(&TokenQueryMsg::SupplyCheck {
+            start_after,
+            running_sum,
+            limit: Some(1),
+        });
+        pages += 1;
+        match res.last_address.clone() {
+            Some(last_address) => {
+                assert_eq!(res.discrepancy, None);
+                start_after = Some(last_address);
+                running_sum = Some(res.running_sum);
+            }
+            None => break res,
+        }
+    };
+
+    assert_eq!(pages, 4);
+    assert_eq!(res.running_sum, Uint128::new(110_000_000));
+    assert_eq!(res.running_sum, res.total_supply);
+    assert_eq!(res.discrepancy, None);
+}
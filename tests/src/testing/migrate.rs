@@ -0,0 +1,150 @@
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw20::BalanceResponse;
+use cw_ownable::Ownership;
+use qtum_reflection_token::msg::{
+    ExecuteMsg as TokenExecuteMsg, MigrateMsg, PairConfig, QueryMsg as TokenQueryMsg,
+    TaxRatesResponse, TreasuryInstantiateMsg, VotingPowerResponse,
+};
+use serde::de::DeserializeOwned;
+
+use super::store_token;
+use crate::app::App;
+use crate::legacy_token;
+use crate::treasury as mock_treasury;
+
+fn query<T: DeserializeOwned>(app: &App, token: &Addr, msg: &TokenQueryMsg) -> T {
+    app.wrap().query_wasm_smart(token.clone(), msg).unwrap()
+}
+
+fn balance(app: &App, token: &Addr, address: &str) -> Uint128 {
+    let res: BalanceResponse = query(
+        app,
+        token,
+        &TokenQueryMsg::Balance {
+            address: address.to_string(),
+        },
+    );
+    res.balance
+}
+
+#[test]
+fn upgrade_from_v1_0_0_keeps_taxing_pairs() {
+    let mut app = App::default();
+    let admin = Addr::unchecked("admin");
+    let legacy_code = app.store_code(legacy_token::contract());
+    let token_code = store_token(&mut app);
+    let treasury_code = app.store_code(mock_treasury::contract());
+
+    let token = app
+        .instantiate_contract(
+            legacy_code,
+            admin.clone(),
+            &legacy_token::InstantiateMsg {
+                admin: admin.to_string(),
+                balances: vec![
+                    (admin.to_string(), Uint128::new(100_000_000)),
+                    ("alice".to_string(), Uint128::new(10_000_000)),
+                    ("pair".to_string(), Uint128::new(50_000_000)),
+                ],
+                pairs: vec![("pair".to_string(), true), ("old_pair".to_string(), false)],
+                tax_rate: Decimal::percent(10),
+            },
+            &[],
+        )
+        .unwrap();
+    let treasury = app
+        .instantiate_contract(
+            treasury_code,
+            admin.clone(),
+            &TreasuryInstantiateMsg {
+                admin: admin.to_string(),
+                router: "router".to_string(),
+                token: token.clone(),
+                lp_withdrawal_delay: None,
+                emergency_withdrawal_delay: None,
+            },
+            &[],
+        )
+        .unwrap();
+    app.execute_contract(
+        admin.clone(),
+        token.clone(),
+        &legacy_token::ExecuteMsg::SetTreasury {
+            contract: treasury.to_string(),
+        },
+        &[],
+    )
+    .unwrap();
+
+    app.migrate_contract(
+        admin.clone(),
+        token.clone(),
+        token_code,
+        &MigrateMsg::Upgrade {},
+    )
+    .unwrap();
+    app.next_block();
+
+    let ownership: Ownership<Addr> = query(&app, &token, &TokenQueryMsg::Ownership {});
+    assert_eq!(ownership.owner, Some(admin));
+    let rates: TaxRatesResponse = query(&app, &token, &TokenQueryMsg::QueryTaxRates {});
+    assert_eq!(rates.buy_rate, Decimal::percent(10));
+    assert_eq!(rates.sell_rate, Decimal::percent(10));
+    assert_eq!(rates.transfer_rate, Decimal::zero());
+    let pair: PairConfig = query(
+        &app,
+        &token,
+        &TokenQueryMsg::GetPair {
+            address: "pair".to_string(),
+        },
+    );
+    assert_eq!(
+        pair,
+        PairConfig {
+            enabled: true,
+            tax_rate: None
+        }
+    );
+    let old_pair: PairConfig = query(
+        &app,
+        &token,
+        &TokenQueryMsg::GetPair {
+            address: "old_pair".to_string(),
+        },
+    );
+    assert!(!old_pair.enabled);
+
+    let holders: u64 = query(&app, &token, &TokenQueryMsg::HolderCount {});
+    assert_eq!(holders, 3);
+    let power: VotingPowerResponse = query(
+        &app,
+        &token,
+        &TokenQueryMsg::VotingPowerAt {
+            address: "alice".to_string(),
+            height: app.block_info().height,
+        },
+    );
+    assert_eq!(power.power, Uint128::new(10_000_000));
+
+    // sells pay the 1.0.0 rate, wallet transfers stay untaxed
+    let sell = TokenExecuteMsg::Transfer {
+        recipient: "pair".to_string(),
+        amount: Uint128::new(1_000_000),
+    };
+    app.execute_contract(Addr::unchecked("alice"), token.clone(), &sell, &[])
+        .unwrap();
+    assert_eq!(balance(&app, &token, "pair"), Uint128::new(50_900_000));
+    assert_eq!(
+        balance(&app, &token, treasury.as_str()),
+        Uint128::new(100_000)
+    );
+
+    let transfer = TokenExecuteMsg::Transfer {
+        recipient: "bob".to_string(),
+        amount: Uint128::new(1_000_000),
+    };
+    app.execute_contract(Addr::unchecked("alice"), token.clone(), &transfer, &[])
+        .unwrap();
+    assert_eq!(balance(&app, &token, "bob"), Uint128::new(1_000_000));
+    assert_eq!(balance(&app, &token, "alice"), Uint128::new(8_000_000));
+}
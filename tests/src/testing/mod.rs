@@ -0,0 +1,413 @@
+use anyhow::Result as AnyResult;
+use cosmwasm_std::{coin, Addr, Decimal, Uint128};
+use cw20::{BalanceResponse, TokenInfoResponse};
+use dojoswap::asset::AssetInfo;
+use qtum_reflection_token::contract as token_contract;
+use qtum_reflection_token::msg::{
+    ExecuteMsg as TokenExecuteMsg, InitialBalance, InstantiateMsg as TokenInstantiateMsg,
+    InstantiateTreasuryInfo, QueryMsg as TokenQueryMsg, TreasuryResponse,
+};
+use qtum_treasury::contract as treasury_contract;
+use qtum_treasury::msg::{
+    ExecuteMsg as TreasuryExecuteMsg, InstantiateMsg as TreasuryInstantiateMsg,
+    QueryMsg as TreasuryQueryMsg, TreasuryStats,
+};
+use serde::de::DeserializeOwned;
+
+use crate::app::{App, AppResponse, ContractWrapper};
+use crate::treasury::{LiquifyCallsResponse, QueryMsg as MockQueryMsg};
+use crate::{pair, router, treasury as mock_treasury};
+
+mod denom;
+mod liquify;
+mod migrate;
+mod token;
+mod treasury;
+
+pub const QUOTE_DENOM: &str = "inj";
+const REFLECTION_DENOM: &str = "udojo";
+
+fn store_token(app: &mut App) -> u64 {
+    app.store_code(Box::new(
+        ContractWrapper::new(
+            token_contract::execute,
+            token_contract::instantiate,
+            token_contract::query,
+        )
+        .with_reply(token_contract::reply)
+        .with_migrate(token_contract::migrate),
+    ))
+}
+
+/// Token with 100 BABY for `admin` and 10 BABY for alice, all rates at zero
+fn token_msg(admin: &Addr, treasury: Option<InstantiateTreasuryInfo>) -> TokenInstantiateMsg {
+    TokenInstantiateMsg {
+        name: "Baby Token".to_string(),
+        symbol: "BABY".to_string(),
+        decimals: 6,
+        initial_balances: vec![
+            InitialBalance {
+                address: admin.to_string(),
+                amount: Uint128::new(100_000_000),
+                vesting: None,
+            },
+            InitialBalance {
+                address: "alice".to_string(),
+                amount: Uint128::new(10_000_000),
+                vesting: None,
+            },
+        ],
+        admin: admin.to_string(),
+        mint: None,
+        marketing: None,
+        treasury,
+        launch_protection: None,
+        enable_clawback: None,
+        max_total_tax: None,
+        tax_decay: None,
+        rebase: None,
+    }
+}
+
+struct Suite {
+    app: App,
+    admin: Addr,
+    token: Addr,
+    treasury: Addr,
+    liquidity_pair: Addr,
+}
+
+impl Suite {
+    /// Token and treasury wired to a babyTOKEN/INJ liquidity pair and a DOJO/INJ reflection pair,
+    /// with a 5% transfer tax split 40% reflection, 10% burn and 50% liquidity
+    fn new() -> Self {
+        let mut app = App::default();
+        let admin = Addr::unchecked("admin");
+
+        let token_code = store_token(&mut app);
+        let treasury_code = app.store_code(Box::new(
+            ContractWrapper::new(
+                treasury_contract::execute,
+                treasury_contract::instantiate,
+                treasury_contract::query,
+            )
+            .with_reply(treasury_contract::reply),
+        ));
+        let pair_code = app.store_code(pair::contract());
+        let router_code = app.store_code(router::contract());
+
+        let token = app
+            .instantiate_contract(token_code, admin.clone(), &token_msg(&admin, None), &[])
+            .unwrap();
+
+        let liquidity_assets = [
+            AssetInfo::Token {
+                contract_addr: token.to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: QUOTE_DENOM.to_string(),
+            },
+        ];
+        let reflection_assets = [
+            AssetInfo::NativeToken {
+                denom: REFLECTION_DENOM.to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: QUOTE_DENOM.to_string(),
+            },
+        ];
+        let liquidity_pair = app
+            .instantiate_contract(
+                pair_code,
+                admin.clone(),
+                &pair::InstantiateMsg {
+                    asset_infos: liquidity_assets.clone(),
+                },
+                &[],
+            )
+            .unwrap();
+        let reflection_pair = app
+            .instantiate_contract(
+                pair_code,
+                admin.clone(),
+                &pair::InstantiateMsg {
+                    asset_infos: reflection_assets.clone(),
+                },
+                &[],
+            )
+            .unwrap();
+        let router = app
+            .instantiate_contract(
+                router_code,
+                admin.clone(),
+                &router::InstantiateMsg {
+                    pairs: vec![liquidity_pair.to_string(), reflection_pair.to_string()],
+                },
+                &[],
+            )
+            .unwrap();
+        let treasury = app
+            .instantiate_contract(
+                treasury_code,
+                admin.clone(),
+                &TreasuryInstantiateMsg {
+                    admin: admin.to_string(),
+                    router: router.to_string(),
+                    token: token.clone(),
+                    lp_withdrawal_delay: None,
+                    emergency_withdrawal_delay: None,
+                },
+                &[],
+            )
+            .unwrap();
+
+        // seeds the pools, 1 babyTOKEN = 0.1 INJ = 1 DOJO
+        app.execute_contract(
+            admin.clone(),
+            token.clone(),
+            &TokenExecuteMsg::Transfer {
+                recipient: liquidity_pair.to_string(),
+                amount: Uint128::new(50_000_000),
+            },
+            &[],
+        )
+        .unwrap();
+        app.init_balance(&liquidity_pair, vec![coin(5_000_000, QUOTE_DENOM)]);
+        app.init_balance(
+            &reflection_pair,
+            vec![
+                coin(50_000_000, REFLECTION_DENOM),
+                coin(5_000_000, QUOTE_DENOM),
+            ],
+        );
+
+        let token_setup = vec![
+            TokenExecuteMsg::SetTreasury {
+                contract: treasury.to_string(),
+            },
+            TokenExecuteMsg::SetTaxExempt {
+                address: treasury.to_string(),
+                exempt: true,
+            },
+            TokenExecuteMsg::SetTaxExempt {
+                address: router.to_string(),
+                exempt: true,
+            },
+            TokenExecuteMsg::SetPair {
+                contract: liquidity_pair.to_string(),
+                enable: true,
+                tax_rate: None,
+            },
+            TokenExecuteMsg::SetTaxRate {
+                global_rate: Decimal::percent(10),
+                reflection_rate: Decimal::percent(40),
+                burn_rate: Decimal::percent(10),
+            },
+            TokenExecuteMsg::SetTaxRates {
+                buy_rate: Decimal::zero(),
+                sell_rate: Decimal::percent(10),
+                transfer_rate: Decimal::percent(5),
+            },
+            TokenExecuteMsg::SetBuyBack { enable: true },
+            TokenExecuteMsg::EnableTrading {},
+        ];
+        for msg in token_setup {
+            app.execute_contract(admin.clone(), token.clone(), &msg, &[])
+                .unwrap();
+        }
+
+        let treasury_setup = vec![
+            TreasuryExecuteMsg::SetLiquidityPair {
+                asset_infos: liquidity_assets,
+                pair_contract: liquidity_pair.to_string(),
+                dex: None,
+            },
+            TreasuryExecuteMsg::SetReflectionPair {
+                asset_infos: reflection_assets,
+                pair_contract: reflection_pair.to_string(),
+                dex: None,
+            },
+            TreasuryExecuteMsg::SetFeeCollector {
+                address: Some("collector".to_string()),
+            },
+        ];
+        for msg in treasury_setup {
+            app.execute_contract(admin.clone(), treasury.clone(), &msg, &[])
+                .unwrap();
+        }
+
+        Suite {
+            app,
+            admin,
+            token,
+            treasury,
+            liquidity_pair,
+        }
+    }
+
+    fn transfer(&mut self, sender: &str, recipient: &str, amount: u128) -> AppResponse {
+        self.app
+            .execute_contract(
+                Addr::unchecked(sender),
+                self.token.clone(),
+                &TokenExecuteMsg::Transfer {
+                    recipient: recipient.to_string(),
+                    amount: Uint128::new(amount),
+                },
+                &[],
+            )
+            .unwrap()
+    }
+
+    fn balance(&self, address: &Addr) -> Uint128 {
+        let res: BalanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                self.token.clone(),
+                &TokenQueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+        res.balance
+    }
+
+    fn total_supply(&self) -> Uint128 {
+        let res: TokenInfoResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(self.token.clone(), &TokenQueryMsg::TokenInfo {})
+            .unwrap();
+        res.total_supply
+    }
+
+    fn stats(&self) -> TreasuryStats {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.treasury.clone(), &TreasuryQueryMsg::Stats {})
+            .unwrap()
+    }
+
+    fn treasury_execute(&mut self, msg: TreasuryExecuteMsg) -> AnyResult<AppResponse> {
+        self.app
+            .execute_contract(self.admin.clone(), self.treasury.clone(), &msg, &[])
+    }
+
+    /// LP tokens of the liquidity pair held by `address`
+    fn lp_balance(&self, address: &Addr) -> Uint128 {
+        let res: BalanceResponse = self
+            .app
+            .wrap()
+            .query_wasm_smart(
+                self.liquidity_pair.clone(),
+                &pair::QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+        res.balance
+    }
+}
+
+/// Token instantiating the mock treasury, with a 5% transfer tax and buyback enabled
+struct MockSuite {
+    app: App,
+    admin: Addr,
+    token: Addr,
+    treasury: Addr,
+}
+
+impl MockSuite {
+    fn new() -> Self {
+        let admin = Addr::unchecked("admin");
+        Self::with_msg(token_msg(&admin, None))
+    }
+
+    /// Instantiates the token with `msg`, its treasury being the mock treasury
+    fn with_msg(mut msg: TokenInstantiateMsg) -> Self {
+        let mut app = App::default();
+        let admin = Addr::unchecked(&msg.admin);
+        let token_code = store_token(&mut app);
+        let treasury_code = app.store_code(mock_treasury::contract());
+
+        msg.treasury = Some(InstantiateTreasuryInfo {
+            code_id: treasury_code,
+            router: "router".to_string(),
+            admin: None,
+            label: "treasury".to_string(),
+            lp_withdrawal_delay: None,
+            emergency_withdrawal_delay: None,
+            deterministic: None,
+        });
+        let token = app
+            .instantiate_contract(token_code, admin.clone(), &msg, &[])
+            .unwrap();
+        let res: TreasuryResponse = app
+            .wrap()
+            .query_wasm_smart(token.clone(), &TokenQueryMsg::GetTreasury {})
+            .unwrap();
+        let treasury = Addr::unchecked(res.treasury);
+
+        let mut suite = MockSuite {
+            app,
+            admin,
+            token,
+            treasury,
+        };
+        let token_setup = vec![
+            TokenExecuteMsg::SetTaxRates {
+                buy_rate: Decimal::zero(),
+                sell_rate: Decimal::zero(),
+                transfer_rate: Decimal::percent(5),
+            },
+            TokenExecuteMsg::SetBuyBack { enable: true },
+            TokenExecuteMsg::EnableTrading {},
+        ];
+        for msg in token_setup {
+            suite.admin_execute(msg).unwrap();
+        }
+        suite
+    }
+
+    fn execute(&mut self, sender: &str, msg: TokenExecuteMsg) -> AnyResult<AppResponse> {
+        self.app
+            .execute_contract(Addr::unchecked(sender), self.token.clone(), &msg, &[])
+    }
+
+    fn admin_execute(&mut self, msg: TokenExecuteMsg) -> AnyResult<AppResponse> {
+        let admin = self.admin.to_string();
+        self.execute(&admin, msg)
+    }
+
+    fn transfer(&mut self, sender: &str, recipient: &str, amount: u128) -> AnyResult<AppResponse> {
+        self.execute(
+            sender,
+            TokenExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: Uint128::new(amount),
+            },
+        )
+    }
+
+    fn query<T: DeserializeOwned>(&self, msg: &TokenQueryMsg) -> T {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.token.clone(), msg)
+            .unwrap()
+    }
+
+    fn balance(&self, address: &str) -> Uint128 {
+        let res: BalanceResponse = self.query(&TokenQueryMsg::Balance {
+            address: address.to_string(),
+        });
+        res.balance
+    }
+
+    fn liquify_calls(&self) -> LiquifyCallsResponse {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.treasury.clone(), &MockQueryMsg::LiquifyCalls {})
+            .unwrap()
+    }
+}
@@ -0,0 +1,945 @@
+use cosmwasm_std::{to_json_vec, Addr, Binary, Decimal, Uint128};
+use cw20::{
+    AllAccountsResponse, AllowanceResponse, BalanceResponse, Expiration, TokenInfoResponse,
+};
+use dojoswap::asset::AssetInfo;
+use dojoswap::pair::PoolResponse;
+use k256::ecdsa::signature::hazmat::PrehashSigner;
+use k256::ecdsa::{Signature, SigningKey};
+use qtum_reflection_token::msg::{
+    DynamicTax, ExecuteMsg as TokenExecuteMsg, ExportStateResponse, PairConfig,
+    PendingReflectionResponse, PendingTaxChange, PendingTaxChangesResponse, PermitPayload,
+    PriceImpactTax, QueryMsg as TokenQueryMsg, QueryTaxResponse, RatesResponse, TaxBucket,
+    TaxChange, TaxDecay, TaxMode, TaxRatesResponse, TaxSplitResponse, VotingPowerResponse,
+};
+use sha2::{Digest, Sha256};
+
+use crate::app::AppResponse;
+use crate::pair;
+use crate::testing::{token_msg, MockSuite, QUOTE_DENOM};
+
+#[test]
+fn exclusive_transfer_from_is_capped_by_allowance() {
+    let mut suite = MockSuite::new();
+    suite
+        .admin_execute(TokenExecuteMsg::SetTaxMode {
+            mode: TaxMode::Exclusive,
+        })
+        .unwrap();
+    suite
+        .execute(
+            "alice",
+            TokenExecuteMsg::IncreaseAllowance {
+                spender: "spender".to_string(),
+                amount: Uint128::new(1_000_000),
+                expires: None,
+            },
+        )
+        .unwrap();
+
+    // alice would be debited 1_050_000 with the 5% tax on top
+    let transfer_from = |amount: u128| TokenExecuteMsg::TransferFrom {
+        owner: "alice".to_string(),
+        recipient: "bob".to_string(),
+        amount: Uint128::new(amount),
+    };
+    let err = suite
+        .execute("spender", transfer_from(1_000_000))
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Cannot Sub with 1000000 and 1050000"));
+    assert_eq!(suite.balance("alice"), Uint128::new(10_000_000));
+
+    // the tax is charged on the allowance too
+    suite.execute("spender", transfer_from(500_000)).unwrap();
+    assert_eq!(suite.balance("bob"), Uint128::new(500_000));
+    assert_eq!(suite.balance("alice"), Uint128::new(9_475_000));
+    let res: AllowanceResponse = suite.query(&TokenQueryMsg::Allowance {
+        owner: "alice".to_string(),
+        spender: "spender".to_string(),
+    });
+    assert_eq!(res.allowance, Uint128::new(475_000));
+    assert_eq!(res.expires, Expiration::Never {});
+
+    // the whole remaining allowance cannot cover its own tax either
+    let err = suite
+        .execute(
+            "spender",
+            TokenExecuteMsg::SendFrom {
+                owner: "alice".to_string(),
+                contract: suite.treasury.to_string(),
+                amount: Uint128::new(475_000),
+                msg: Default::default(),
+            },
+        )
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Cannot Sub with 475000 and 498750"));
+    assert_eq!(suite.balance("alice"), Uint128::new(9_475_000));
+}
+
+#[test]
+fn tax_setting_changes_wait_for_the_tax_rate_delay() {
+    let mut suite = MockSuite::new();
+    suite
+        .admin_execute(TokenExecuteMsg::SetTaxRateDelay { seconds: 3600 })
+        .unwrap();
+    let effective_at = suite.app.block_info().time.seconds() + 3600;
+
+    suite
+        .admin_execute(TokenExecuteMsg::SetPair {
+            contract: "pair".to_string(),
+            enable: true,
+            tax_rate: Some(Decimal::percent(20)),
+        })
+        .unwrap();
+    suite
+        .admin_execute(TokenExecuteMsg::SetTaxMode {
+            mode: TaxMode::Exclusive,
+        })
+        .unwrap();
+    let pending: PendingTaxChangesResponse = suite.query(&TokenQueryMsg::PendingTaxChanges {});
+    assert_eq!(
+        pending.changes,
+        vec![
+            PendingTaxChange {
+                change: TaxChange::Pair {
+                    contract: "pair".to_string(),
+                    config: PairConfig {
+                        enabled: true,
+                        tax_rate: Some(Decimal::percent(20)),
+                    },
+                },
+                effective_at,
+            },
+            PendingTaxChange {
+                change: TaxChange::TaxMode {
+                    mode: TaxMode::Exclusive,
+                },
+                effective_at,
+            },
+        ]
+    );
+
+    // until the delay passes, "pair" is a wallet and taxes stay inclusive
+    suite.transfer("alice", "pair", 1_000_000).unwrap();
+    assert_eq!(suite.balance("pair"), Uint128::new(950_000));
+    assert_eq!(suite.balance("alice"), Uint128::new(9_000_000));
+
+    suite.app.update_block(|block| {
+        block.time = block.time.plus_seconds(3600);
+    });
+    suite.transfer("alice", "pair", 1_000_000).unwrap();
+    assert_eq!(suite.balance("pair"), Uint128::new(1_950_000));
+    assert_eq!(suite.balance("alice"), Uint128::new(7_800_000));
+    let pending: PendingTaxChangesResponse = suite.query(&TokenQueryMsg::PendingTaxChanges {});
+    assert!(pending.changes.is_empty());
+}
+
+#[test]
+fn due_rate_changes_are_queried_before_the_next_execute() {
+    let mut suite = MockSuite::new();
+    suite
+        .admin_execute(TokenExecuteMsg::SetTaxRateDelay { seconds: 3600 })
+        .unwrap();
+    let stored: TaxRatesResponse = suite.query(&TokenQueryMsg::QueryTaxRates {});
+    suite
+        .admin_execute(TokenExecuteMsg::SetTaxRates {
+            buy_rate: Decimal::percent(2),
+            sell_rate: Decimal::percent(10),
+            transfer_rate: Decimal::percent(1),
+        })
+        .unwrap();
+    suite
+        .admin_execute(TokenExecuteMsg::SetPair {
+            contract: "pair".to_string(),
+            enable: true,
+            tax_rate: Some(Decimal::percent(20)),
+        })
+        .unwrap();
+    let rates: TaxRatesResponse = suite.query(&TokenQueryMsg::QueryTaxRates {});
+    assert_eq!(rates, stored);
+
+    // no execute runs after the delay, the queries apply the due changes themselves
+    suite.app.update_block(|block| {
+        block.time = block.time.plus_seconds(3600);
+    });
+    let rates: TaxRatesResponse = suite.query(&TokenQueryMsg::QueryTaxRates {});
+    assert_eq!(
+        (rates.buy_rate, rates.sell_rate, rates.transfer_rate),
+        (
+            Decimal::percent(2),
+            Decimal::percent(10),
+            Decimal::percent(1)
+        )
+    );
+    let rates: RatesResponse = suite.query(&TokenQueryMsg::QueryRates {});
+    assert_eq!(rates.tax_rate, Decimal::percent(10));
+    let tax: QueryTaxResponse = suite.query(&TokenQueryMsg::QueryTax {
+        amount: Uint128::new(1_000_000),
+        pair: Some("pair".to_string()),
+        sender: None,
+        recipient: None,
+    });
+    assert_eq!(tax.taxed_amount, Uint128::new(200_000));
+    let split: TaxSplitResponse = suite.query(&TokenQueryMsg::TaxSplit {
+        amount: Uint128::new(1_000_000),
+        pair: Some("pair".to_string()),
+    });
+    assert_eq!(split.taxed_amount, Uint128::new(200_000));
+    let pending: PendingTaxChangesResponse = suite.query(&TokenQueryMsg::PendingTaxChanges {});
+    assert!(pending.changes.is_empty());
+}
+
+#[test]
+fn pending_tax_changes_can_be_cancelled() {
+    let mut suite = MockSuite::new();
+    suite
+        .admin_execute(TokenExecuteMsg::SetTaxRateDelay { seconds: 3600 })
+        .unwrap();
+    suite
+        .admin_execute(TokenExecuteMsg::SetMintTax {
+            rate: Decimal::percent(50),
+        })
+        .unwrap();
+
+    suite
+        .execute("alice", TokenExecuteMsg::CancelPendingTaxChanges {})
+        .unwrap_err();
+    suite
+        .admin_execute(TokenExecuteMsg::CancelPendingTaxChanges {})
+        .unwrap();
+
+    suite.app.update_block(|block| {
+        block.time = block.time.plus_seconds(3600);
+    });
+    suite.transfer("alice", "bob", 1_000_000).unwrap();
+    let pending: PendingTaxChangesResponse = suite.query(&TokenQueryMsg::PendingTaxChanges {});
+    assert!(pending.changes.is_empty());
+    let rates: TaxRatesResponse = suite.query(&TokenQueryMsg::QueryTaxRates {});
+    assert_eq!(rates.mint_tax_rate, Decimal::zero());
+}
+
+#[test]
+fn launch_tax_applies_to_trades_without_a_base_rate() {
+    let admin = Addr::unchecked("admin");
+    let mut msg = token_msg(&admin, None);
+    msg.tax_decay = Some(TaxDecay {
+        start_rate: Decimal::percent(20),
+        end_rate: Decimal::percent(20),
+        start_time: 0,
+        duration: 4_000_000_000,
+    });
+    let mut suite = MockSuite::with_msg(msg);
+    suite
+        .admin_execute(TokenExecuteMsg::SetTaxRates {
+            buy_rate: Decimal::zero(),
+            sell_rate: Decimal::zero(),
+            transfer_rate: Decimal::zero(),
+        })
+        .unwrap();
+    suite
+        .admin_execute(TokenExecuteMsg::SetPair {
+            contract: "pair".to_string(),
+            enable: true,
+            tax_rate: None,
+        })
+        .unwrap();
+
+    // sells and buys pay the launch tax
+    suite.transfer("alice", "pair", 1_000_000).unwrap();
+    assert_eq!(suite.balance("pair"), Uint128::new(800_000));
+    suite.transfer("pair", "bob", 500_000).unwrap();
+    assert_eq!(suite.balance("bob"), Uint128::new(400_000));
+
+    // untaxed wallet transfers stay untaxed
+    suite.transfer("alice", "carol", 1_000_000).unwrap();
+    assert_eq!(suite.balance("carol"), Uint128::new(1_000_000));
+
+    // so do exempt traders
+    suite
+        .admin_execute(TokenExecuteMsg::SetTaxExempt {
+            address: "carol".to_string(),
+            exempt: true,
+        })
+        .unwrap();
+    suite.transfer("carol", "pair", 1_000_000).unwrap();
+    assert_eq!(suite.balance("pair"), Uint128::new(1_300_000));
+}
+
+#[test]
+fn clawback_moves_compounded_balances_and_their_history() {
+    let admin = Addr::unchecked("admin");
+    let mut msg = token_msg(&admin, None);
+    msg.enable_clawback = Some(true);
+    msg.rebase = Some(true);
+    let mut suite = MockSuite::with_msg(msg);
+    suite
+        .admin_execute(TokenExecuteMsg::SetHolderReflectionRate {
+            rate: Decimal::percent(50),
+        })
+        .unwrap();
+    // alice accrues a share of the holder reflections of this taxed transfer
+    suite.transfer("admin", "bob", 10_000_000).unwrap();
+    let res: BalanceResponse = suite.query(&TokenQueryMsg::Balance {
+        address: "alice".to_string(),
+    });
+    let compounded = res.balance;
+    assert!(compounded > Uint128::new(10_000_000));
+    let holders: u64 = suite.query(&TokenQueryMsg::HolderCount {});
+
+    suite.app.next_block();
+    let res = suite
+        .admin_execute(TokenExecuteMsg::Clawback {
+            from: "alice".to_string(),
+            to: "carol".to_string(),
+            amount: compounded,
+        })
+        .unwrap();
+    let event = res.event("wasm-clawback").unwrap();
+    let attribute = |key: &str| {
+        event
+            .attributes
+            .iter()
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.as_str())
+    };
+    assert_eq!(attribute("from"), Some("alice"));
+    assert_eq!(attribute("to"), Some("carol"));
+    assert_eq!(attribute("amount"), Some(compounded.to_string().as_str()));
+
+    assert_eq!(suite.balance("alice"), Uint128::zero());
+    assert_eq!(suite.balance("carol"), compounded);
+    // alice is no longer a holder, carol is a new one
+    let count: u64 = suite.query(&TokenQueryMsg::HolderCount {});
+    assert_eq!(count, holders);
+
+    suite.app.next_block();
+    let height = suite.app.block_info().height;
+    let voting_power = |suite: &MockSuite, address: &str| {
+        let res: VotingPowerResponse = suite.query(&TokenQueryMsg::VotingPowerAt {
+            address: address.to_string(),
+            height,
+        });
+        res.power
+    };
+    assert_eq!(voting_power(&suite, "alice"), Uint128::zero());
+    assert_eq!(voting_power(&suite, "carol"), compounded);
+    let res: BalanceResponse = suite.query(&TokenQueryMsg::BalanceAt {
+        address: "carol".to_string(),
+        height,
+    });
+    assert_eq!(res.balance, compounded);
+}
+
+#[test]
+fn holder_reflections_accrue_pro_rata_and_are_claimed() {
+    let mut suite = MockSuite::new();
+    let setup = vec![
+        TokenExecuteMsg::SetHolderReflectionRate {
+            rate: Decimal::percent(50),
+        },
+        TokenExecuteMsg::SetTaxExempt {
+            address: "admin".to_string(),
+            exempt: true,
+        },
+    ];
+    for msg in setup {
+        suite.admin_execute(msg).unwrap();
+    }
+    let pending = |suite: &MockSuite, address: &str| {
+        let res: PendingReflectionResponse = suite.query(&TokenQueryMsg::PendingReflection {
+            address: address.to_string(),
+        });
+        res
+    };
+
+    // half of the 5% tax is distributed to the holders, less the rounding dust
+    suite.transfer("alice", "bob", 2_000_000).unwrap();
+    let distributed: Uint128 = ["admin", "alice", "bob"]
+        .iter()
+        .map(|address| pending(&suite, address).pending)
+        .sum();
+    assert!(distributed <= Uint128::new(50_000) && distributed > Uint128::new(49_997));
+    assert_eq!(suite.balance(suite.token.as_str()), Uint128::new(50_000));
+
+    // reflections keep accruing across transfers
+    let admin_pending = pending(&suite, "admin").pending;
+    suite.transfer("bob", "alice", 1_000_000).unwrap();
+    assert!(pending(&suite, "admin").pending > admin_pending);
+
+    // a holder joining after a distribution gets none of it
+    suite.transfer("admin", "dave", 5_000_000).unwrap();
+    assert_eq!(pending(&suite, "dave").pending, Uint128::zero());
+    let admin_pending = pending(&suite, "admin").pending;
+    suite.transfer("alice", "bob", 1_000_000).unwrap();
+    let dave_pending = pending(&suite, "dave").pending;
+    assert!(!dave_pending.is_zero());
+    // dave holds 5_000_000 to the 95_000_000 left to the admin, and earns in proportion
+    let admin_earned = pending(&suite, "admin").pending - admin_pending;
+    assert!(admin_earned.abs_diff(dave_pending * Uint128::new(19)) < Uint128::new(19));
+
+    let alice = pending(&suite, "alice");
+    let balance = suite.balance("alice");
+    let pool = suite.balance(suite.token.as_str());
+    suite
+        .execute("alice", TokenExecuteMsg::ClaimReflection {})
+        .unwrap();
+    assert_eq!(suite.balance("alice"), balance + alice.pending);
+    assert_eq!(suite.balance(suite.token.as_str()), pool - alice.pending);
+    let claimed = pending(&suite, "alice");
+    assert_eq!(claimed.pending, Uint128::zero());
+    assert_eq!(claimed.claimed, alice.pending);
+    let err = suite
+        .execute("alice", TokenExecuteMsg::ClaimReflection {})
+        .unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("No reflections to claim"));
+}
+
+#[test]
+fn rebased_balances_sum_to_the_total_supply() {
+    let admin = Addr::unchecked("admin");
+    let mut msg = token_msg(&admin, None);
+    msg.rebase = Some(true);
+    let mut suite = MockSuite::with_msg(msg);
+    suite
+        .admin_execute(TokenExecuteMsg::SetHolderReflectionRate {
+            rate: Decimal::percent(50),
+        })
+        .unwrap();
+    suite.transfer("alice", "bob", 2_000_000).unwrap();
+    suite.transfer("admin", "carol", 10_000_000).unwrap();
+    suite.transfer("bob", "alice", 1_000_000).unwrap();
+    let height = suite.app.block_info().height;
+    suite.app.next_block();
+
+    let accounts: AllAccountsResponse = suite.query(&TokenQueryMsg::AllAccounts {
+        start_after: None,
+        limit: None,
+    });
+    let info: TokenInfoResponse = suite.query(&TokenQueryMsg::TokenInfo {});
+    let sum = |balance: &dyn Fn(&str) -> Uint128| {
+        accounts
+            .accounts
+            .iter()
+            .map(|address| balance(address))
+            .sum::<Uint128>()
+    };
+    // rounding leaves less than a token per holder in the reflection pool
+    let dust = Uint128::new(accounts.accounts.len() as u128);
+    let balances = sum(&|address| suite.balance(address));
+    assert!(balances <= info.total_supply && info.total_supply - balances < dust);
+    // the reflections held for the holders are only counted in their balances
+    assert_eq!(suite.balance(suite.token.as_str()), Uint128::zero());
+    let pending: PendingReflectionResponse = suite.query(&TokenQueryMsg::PendingReflection {
+        address: "alice".to_string(),
+    });
+    assert!(!pending.pending.is_zero());
+
+    let balance_at = |address: &str| {
+        let res: BalanceResponse = suite.query(&TokenQueryMsg::BalanceAt {
+            address: address.to_string(),
+            height: height + 1,
+        });
+        res.balance
+    };
+    assert_eq!(sum(&balance_at), balances);
+    assert_eq!(balance_at("alice"), suite.balance("alice"));
+
+    let export: ExportStateResponse = suite.query(&TokenQueryMsg::ExportState {
+        start_after: None,
+        limit: None,
+    });
+    let exported: Uint128 = export.balances.iter().map(|coin| coin.amount).sum();
+    assert_eq!(exported, balances);
+}
+
+#[test]
+fn reflection_pool_balance_is_checkpointed() {
+    let mut suite = MockSuite::new();
+    suite
+        .admin_execute(TokenExecuteMsg::SetHolderReflectionRate {
+            rate: Decimal::percent(50),
+        })
+        .unwrap();
+    let token = suite.token.to_string();
+    let balance_at = |suite: &MockSuite, height: u64| {
+        let res: BalanceResponse = suite.query(&TokenQueryMsg::BalanceAt {
+            address: token.clone(),
+            height,
+        });
+        res.balance
+    };
+
+    // half of the 5% tax is held by the contract for the holders
+    suite.transfer("admin", "bob", 10_000_000).unwrap();
+    suite.app.next_block();
+    let pool = suite.balance(&token);
+    assert_eq!(pool, Uint128::new(250_000));
+    assert_eq!(balance_at(&suite, suite.app.block_info().height), pool);
+
+    let pending: PendingReflectionResponse = suite.query(&TokenQueryMsg::PendingReflection {
+        address: "alice".to_string(),
+    });
+    suite
+        .execute("alice", TokenExecuteMsg::ClaimReflection {})
+        .unwrap();
+    suite.app.next_block();
+    assert_eq!(
+        balance_at(&suite, suite.app.block_info().height),
+        pool - pending.pending
+    );
+}
+
+#[test]
+fn holder_reflections_are_a_share_of_the_tax_split() {
+    let mut suite = MockSuite::new();
+    let setup = vec![
+        TokenExecuteMsg::SetTaxRate {
+            global_rate: Decimal::zero(),
+            reflection_rate: Decimal::percent(40),
+            burn_rate: Decimal::percent(10),
+        },
+        TokenExecuteMsg::SetPair {
+            contract: "pair".to_string(),
+            enable: true,
+            tax_rate: Some(Decimal::percent(10)),
+        },
+        TokenExecuteMsg::SetHolderReflectionRate {
+            rate: Decimal::percent(50),
+        },
+        TokenExecuteMsg::SetDustBucket {
+            bucket: TaxBucket::HolderReflection,
+        },
+        TokenExecuteMsg::SetInstantBurn { enable: true },
+    ];
+    for msg in setup {
+        suite.admin_execute(msg).unwrap();
+    }
+
+    // 50_001 to holders and 50_002 split 40/10/50, the unit of dust goes to the holders
+    let split: TaxSplitResponse = suite.query(&TokenQueryMsg::TaxSplit {
+        amount: Uint128::new(1_000_030),
+        pair: Some("pair".to_string()),
+    });
+    assert_eq!(split.taxed_amount, Uint128::new(100_003));
+    assert_eq!(split.holder_reflection_amount, Uint128::new(50_002));
+    assert_eq!(split.reflection_amount, Uint128::new(20_000));
+    assert_eq!(split.burn_amount, Uint128::new(5_000));
+    assert_eq!(split.liquidity_amount, Uint128::new(25_001));
+
+    // the 5% tax of this transfer burns 10% of the half left after the holder share
+    let supply = |suite: &MockSuite| {
+        let info: TokenInfoResponse = suite.query(&TokenQueryMsg::TokenInfo {});
+        info.total_supply
+    };
+    let supply_before = supply(&suite);
+    suite.transfer("alice", "bob", 1_000_000).unwrap();
+    assert_eq!(supply_before - supply(&suite), Uint128::new(2_500));
+    assert_eq!(suite.balance(suite.treasury.as_str()), Uint128::new(22_500));
+    assert_eq!(suite.balance(suite.token.as_str()), Uint128::new(25_000));
+}
+
+#[test]
+fn pair_overrides_take_precedence_over_the_dynamic_tax() {
+    let mut suite = MockSuite::new();
+    let setup = vec![
+        TokenExecuteMsg::SetDynamicTax {
+            config: Some(DynamicTax {
+                min_rate: Decimal::percent(15),
+                max_rate: Decimal::percent(30),
+                epoch_seconds: 3600,
+                volume_threshold: Uint128::new(100_000_000),
+            }),
+        },
+        TokenExecuteMsg::SetPair {
+            contract: "pair".to_string(),
+            enable: true,
+            tax_rate: Some(Decimal::percent(2)),
+        },
+        TokenExecuteMsg::SetPair {
+            contract: "other_pair".to_string(),
+            enable: true,
+            tax_rate: None,
+        },
+    ];
+    for msg in setup {
+        suite.admin_execute(msg).unwrap();
+    }
+
+    let sell_tax = |suite: &MockSuite, pair: &str| {
+        let taxes: QueryTaxResponse = suite.query(&TokenQueryMsg::QueryTax {
+            amount: Uint128::new(1_000_000),
+            pair: Some(pair.to_string()),
+            sender: None,
+            recipient: None,
+        });
+        taxes.taxed_amount
+    };
+    let transfer_tax = |suite: &MockSuite, pair: &str| {
+        let taxes: QueryTaxResponse = suite.query(&TokenQueryMsg::QueryTax {
+            amount: Uint128::new(1_000_000),
+            pair: None,
+            sender: Some("alice".to_string()),
+            recipient: Some(pair.to_string()),
+        });
+        taxes.taxed_amount
+    };
+    assert_eq!(sell_tax(&suite, "pair"), Uint128::new(20_000));
+    assert_eq!(transfer_tax(&suite, "pair"), Uint128::new(20_000));
+    assert_eq!(sell_tax(&suite, "other_pair"), Uint128::new(150_000));
+    assert_eq!(transfer_tax(&suite, "other_pair"), Uint128::new(150_000));
+
+    suite.transfer("alice", "other_pair", 1_000_000).unwrap();
+    assert_eq!(suite.balance("other_pair"), Uint128::new(850_000));
+    // the sell raised the dynamic tax, the override stays
+    assert!(sell_tax(&suite, "other_pair") > Uint128::new(150_000));
+    suite.transfer("alice", "pair", 1_000_000).unwrap();
+    assert_eq!(suite.balance("pair"), Uint128::new(980_000));
+}
+
+#[test]
+fn price_impact_surtax_uses_the_pool_reserve() {
+    let admin = Addr::unchecked("admin");
+    let mut msg = token_msg(&admin, None);
+    msg.rebase = Some(true);
+    let mut suite = MockSuite::with_msg(msg);
+    let pair_code = suite.app.store_code(pair::contract());
+    let pair = suite
+        .app
+        .instantiate_contract(
+            pair_code,
+            admin.clone(),
+            &pair::InstantiateMsg {
+                asset_infos: [
+                    AssetInfo::Token {
+                        contract_addr: suite.token.to_string(),
+                    },
+                    AssetInfo::NativeToken {
+                        denom: QUOTE_DENOM.to_string(),
+                    },
+                ],
+            },
+            &[],
+        )
+        .unwrap();
+    suite.transfer("admin", pair.as_str(), 1_000_000).unwrap();
+
+    let setup = vec![
+        TokenExecuteMsg::SetPair {
+            contract: pair.to_string(),
+            enable: true,
+            tax_rate: None,
+        },
+        TokenExecuteMsg::SetPriceImpactTax {
+            config: Some(PriceImpactTax {
+                multiplier: Decimal::one(),
+                max_rate: Decimal::percent(50),
+            }),
+        },
+        TokenExecuteMsg::SetHolderReflectionRate {
+            rate: Decimal::percent(50),
+        },
+    ];
+    for msg in setup {
+        suite.admin_execute(msg).unwrap();
+    }
+    // the pair accrues holder reflections, compounded into the balance it reports
+    suite.transfer("alice", "bob", 1_000_000).unwrap();
+
+    let pool: PoolResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(pair.clone(), &pair::QueryMsg::Pool {})
+        .unwrap();
+    let reserve = pool.assets[0].amount;
+    assert!(reserve > Uint128::new(950_000));
+
+    let amount = Uint128::new(100_000);
+    let taxes: QueryTaxResponse = suite.query(&TokenQueryMsg::QueryTax {
+        amount,
+        pair: Some(pair.to_string()),
+        sender: None,
+        recipient: None,
+    });
+    assert_eq!(
+        taxes.taxed_amount,
+        amount * Decimal::from_ratio(amount, reserve + amount)
+    );
+}
+
+#[test]
+fn failing_tax_strategy_is_reported() {
+    let mut suite = MockSuite::new();
+    suite
+        .admin_execute(TokenExecuteMsg::SetTaxStrategy {
+            contract: Some("strategy".to_string()),
+        })
+        .unwrap();
+
+    // the strategy is not a contract, the 5% base rate is charged
+    let res = suite.transfer("alice", "bob", 1_000_000).unwrap();
+    assert_eq!(suite.balance("bob"), Uint128::new(950_000));
+    let event = res.event("wasm-tax_strategy_failed").unwrap();
+    let attribute = |key: &str| {
+        event
+            .attributes
+            .iter()
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.as_str())
+    };
+    assert_eq!(attribute("from"), Some("alice"));
+    assert_eq!(attribute("to"), Some("bob"));
+    assert!(attribute("error").unwrap().contains("strategy"));
+
+    // nothing is reported without a strategy
+    suite
+        .admin_execute(TokenExecuteMsg::SetTaxStrategy { contract: None })
+        .unwrap();
+    let res = suite.transfer("alice", "bob", 1_000_000).unwrap();
+    assert!(res.event("wasm-tax_strategy_failed").is_none());
+}
+
+#[test]
+fn set_pair_reports_the_pair_config() {
+    let mut suite = MockSuite::new();
+    let attributes = |res: &AppResponse| -> Vec<(String, String)> {
+        res.event("wasm")
+            .unwrap()
+            .attributes
+            .iter()
+            .filter(|attr| attr.key != "_contract_address")
+            .map(|attr| (attr.key.clone(), attr.value.clone()))
+            .collect()
+    };
+    let expected = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    };
+    let effective_at = suite.app.block_info().time.seconds().to_string();
+
+    let res = suite
+        .admin_execute(TokenExecuteMsg::SetPair {
+            contract: "pair".to_string(),
+            enable: true,
+            tax_rate: Some(Decimal::percent(3)),
+        })
+        .unwrap();
+    assert_eq!(
+        attributes(&res),
+        expected(&[
+            ("action", "set_pairlist"),
+            ("pair", "pair"),
+            ("enabled", "true"),
+            ("tax_rate", "0.03"),
+            ("effective_at", &effective_at),
+        ])
+    );
+
+    let res = suite
+        .admin_execute(TokenExecuteMsg::SetPair {
+            contract: "pair".to_string(),
+            enable: false,
+            tax_rate: None,
+        })
+        .unwrap();
+    assert_eq!(
+        attributes(&res),
+        expected(&[
+            ("action", "set_pairlist"),
+            ("pair", "pair"),
+            ("enabled", "false"),
+            ("tax_rate", "none"),
+            ("effective_at", &effective_at),
+        ])
+    );
+}
+
+#[test]
+fn paused_token_rejects_every_balance_change() {
+    let mut suite = MockSuite::new();
+    suite.admin_execute(TokenExecuteMsg::Pause {}).unwrap();
+
+    let amount = Uint128::new(1_000);
+    let messages = vec![
+        TokenExecuteMsg::IncreaseAllowance {
+            spender: "bob".to_string(),
+            amount,
+            expires: None,
+        },
+        TokenExecuteMsg::DecreaseAllowance {
+            spender: "bob".to_string(),
+            amount,
+            expires: None,
+        },
+        TokenExecuteMsg::Permit {
+            owner: "alice".to_string(),
+            spender: "bob".to_string(),
+            amount,
+            expires: None,
+            nonce: 0,
+            deadline: u64::MAX,
+            signature: Binary::default(),
+        },
+        TokenExecuteMsg::RegisterMerkleRoot {
+            merkle_root: "00".repeat(32),
+            total_amount: amount,
+            expiration: None,
+        },
+        TokenExecuteMsg::Claim {
+            stage: 1,
+            index: 0,
+            amount,
+            proof: vec![],
+        },
+        TokenExecuteMsg::ClawbackAirdrop {
+            stage: 1,
+            recipient: None,
+        },
+        TokenExecuteMsg::LockTokens {
+            beneficiary: "bob".to_string(),
+            amount,
+            unlock_time: u64::MAX,
+        },
+        TokenExecuteMsg::ClaimVested {},
+        TokenExecuteMsg::ClaimReflection {},
+        TokenExecuteMsg::Clawback {
+            from: "alice".to_string(),
+            to: "bob".to_string(),
+            amount,
+        },
+    ];
+    for msg in messages {
+        let err = suite.admin_execute(msg.clone()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Generic error: Contract is paused",
+            "{:?}",
+            msg
+        );
+    }
+
+    // the state import stays open to the admin
+    let err = suite
+        .admin_execute(TokenExecuteMsg::ImportState {
+            balances: vec![],
+            allowances: vec![],
+            finish: true,
+        })
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Generic error: State import is closed");
+}
+
+/// Signs a permit of `owner` with `key`, the way a wallet signs it off-chain
+fn sign_permit(suite: &MockSuite, key: &SigningKey, permit: &TokenExecuteMsg) -> Binary {
+    let payload = match permit.clone() {
+        TokenExecuteMsg::Permit {
+            owner,
+            spender,
+            amount,
+            expires,
+            nonce,
+            deadline,
+            ..
+        } => PermitPayload {
+            chain_id: suite.app.block_info().chain_id,
+            contract: suite.token.to_string(),
+            owner,
+            spender,
+            amount,
+            expires,
+            nonce,
+            deadline,
+        },
+        _ => unreachable!(),
+    };
+    let hash = Sha256::digest(to_json_vec(&payload).unwrap());
+    let signature: Signature = key.sign_prehash(&hash).unwrap();
+    Binary::from(signature.to_bytes().as_slice())
+}
+
+#[test]
+fn permits_need_a_fresh_valid_signature() {
+    let mut suite = MockSuite::new();
+    let key = SigningKey::from_bytes(&[7u8; 32].into()).unwrap();
+    let other_key = SigningKey::from_bytes(&[9u8; 32].into()).unwrap();
+    let pubkey = key.verifying_key().to_encoded_point(true);
+    suite
+        .execute(
+            "alice",
+            TokenExecuteMsg::SetPermitKey {
+                pubkey: Some(Binary::from(pubkey.as_bytes())),
+            },
+        )
+        .unwrap();
+
+    let now = suite.app.block_info().time.seconds();
+    let permit = |nonce: u64, deadline: u64, expires: Option<Expiration>| TokenExecuteMsg::Permit {
+        owner: "alice".to_string(),
+        spender: "carol".to_string(),
+        amount: Uint128::new(1_000),
+        expires,
+        nonce,
+        deadline,
+        signature: Binary::default(),
+    };
+    let signed = |suite: &MockSuite, key: &SigningKey, mut msg: TokenExecuteMsg| {
+        let signature = sign_permit(suite, key, &msg);
+        if let TokenExecuteMsg::Permit { signature: sig, .. } = &mut msg {
+            *sig = signature;
+        }
+        msg
+    };
+    let allowance = |suite: &MockSuite| {
+        let res: AllowanceResponse = suite.query(&TokenQueryMsg::Allowance {
+            owner: "alice".to_string(),
+            spender: "carol".to_string(),
+        });
+        res.allowance
+    };
+
+    // anyone can relay a permit signed by the owner
+    let valid = signed(&suite, &key, permit(0, now + 100, None));
+    suite.execute("relayer", valid.clone()).unwrap();
+    assert_eq!(allowance(&suite), Uint128::new(1_000));
+
+    let err = suite.execute("relayer", valid).unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Invalid permit nonce, expected 1"));
+
+    let msg = signed(&suite, &other_key, permit(1, now + 100, None));
+    let err = suite.execute("relayer", msg).unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Invalid permit signature"));
+
+    let msg = signed(&suite, &key, permit(1, now - 1, None));
+    let err = suite.execute("relayer", msg).unwrap_err();
+    assert!(err.root_cause().to_string().contains("Permit expired"));
+
+    let height = suite.app.block_info().height;
+    let msg = signed(
+        &suite,
+        &key,
+        permit(1, now + 100, Some(Expiration::AtHeight(height))),
+    );
+    let err = suite.execute("relayer", msg).unwrap_err();
+    assert!(err
+        .root_cause()
+        .to_string()
+        .contains("Permit allowance already expired"));
+
+    // the rejected permits did not use the nonce
+    let msg = signed(&suite, &key, permit(1, now + 100, None));
+    suite.execute("relayer", msg).unwrap();
+}
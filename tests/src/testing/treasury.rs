@@ -0,0 +1,319 @@
+use cosmwasm_std::{coin, Addr, Attribute, Uint128};
+use dojoswap::asset::{Asset, AssetInfo};
+use dojoswap::pair::ExecuteMsg as PairExecuteMsg;
+use qtum_reflection_token::msg::{
+    AuditLogResponse as TokenAuditLogResponse, ExecuteMsg as TokenExecuteMsg,
+    QueryMsg as TokenQueryMsg,
+};
+use qtum_treasury::contract::DEFAULT_LP_WITHDRAWAL_DELAY;
+use qtum_treasury::msg::{
+    AuditLogResponse, ExecuteMsg as TreasuryExecuteMsg, KeeperBounty, KeeperBountyLimits,
+    KeeperBountyLimitsResponse, LpDestination, PendingLpDestination, QueryMsg as TreasuryQueryMsg,
+};
+
+use crate::testing::{Suite, QUOTE_DENOM};
+
+fn lp_burn_address(suite: &Suite) -> Option<Addr> {
+    suite
+        .app
+        .wrap()
+        .query_wasm_smart(suite.treasury.clone(), &TreasuryQueryMsg::LpBurnAddress {})
+        .unwrap()
+}
+
+#[test]
+fn lp_destination_changes_wait_for_the_delay() {
+    let mut suite = Suite::new();
+
+    suite
+        .treasury_execute(TreasuryExecuteMsg::SetLpBurnAddress {
+            address: Some("burn".to_string()),
+        })
+        .unwrap();
+    assert_eq!(lp_burn_address(&suite), None);
+    let pending: Option<PendingLpDestination> = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            suite.treasury.clone(),
+            &TreasuryQueryMsg::PendingLpDestination {},
+        )
+        .unwrap();
+    let executable_at = suite.app.block_info().time.seconds() + DEFAULT_LP_WITHDRAWAL_DELAY;
+    assert_eq!(
+        pending,
+        Some(PendingLpDestination {
+            destination: LpDestination::BurnAddress(Some(Addr::unchecked("burn"))),
+            executable_at,
+        })
+    );
+
+    let err = suite
+        .treasury_execute(TreasuryExecuteMsg::ExecuteLpDestination {})
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Generic error: LP destination change is executable at {}",
+            executable_at
+        )
+    );
+
+    suite.app.update_block(|block| {
+        block.time = block.time.plus_seconds(DEFAULT_LP_WITHDRAWAL_DELAY);
+    });
+    suite
+        .app
+        .execute_contract(
+            Addr::unchecked("alice"),
+            suite.treasury.clone(),
+            &TreasuryExecuteMsg::ExecuteLpDestination {},
+            &[],
+        )
+        .unwrap_err();
+    suite
+        .treasury_execute(TreasuryExecuteMsg::ExecuteLpDestination {})
+        .unwrap();
+    assert_eq!(lp_burn_address(&suite), Some(Addr::unchecked("burn")));
+
+    // unsetting the destination waits for the delay too
+    suite
+        .treasury_execute(TreasuryExecuteMsg::SetLpBurnAddress { address: None })
+        .unwrap();
+    suite
+        .treasury_execute(TreasuryExecuteMsg::ExecuteLpDestination {})
+        .unwrap_err();
+    suite
+        .treasury_execute(TreasuryExecuteMsg::CancelLpDestination {})
+        .unwrap();
+    assert_eq!(lp_burn_address(&suite), Some(Addr::unchecked("burn")));
+}
+
+#[test]
+fn liquify_burns_only_the_lp_tokens_it_minted() {
+    let mut suite = Suite::new();
+    let burn = Addr::unchecked("burn");
+
+    // the treasury already holds LP tokens, e.g. from a previous liquify
+    suite
+        .app
+        .init_balance(&suite.admin, vec![coin(100_000, QUOTE_DENOM)]);
+    suite
+        .app
+        .execute_contract(
+            suite.admin.clone(),
+            suite.token.clone(),
+            &TokenExecuteMsg::IncreaseAllowance {
+                spender: suite.liquidity_pair.to_string(),
+                amount: Uint128::new(1_000_000),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+    suite
+        .app
+        .execute_contract(
+            suite.admin.clone(),
+            suite.liquidity_pair.clone(),
+            &PairExecuteMsg::ProvideLiquidity {
+                assets: [
+                    Asset {
+                        info: AssetInfo::Token {
+                            contract_addr: suite.token.to_string(),
+                        },
+                        amount: Uint128::new(1_000_000),
+                    },
+                    Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: QUOTE_DENOM.to_string(),
+                        },
+                        amount: Uint128::new(100_000),
+                    },
+                ],
+                receiver: Some(suite.treasury.to_string()),
+                deadline: None,
+                slippage_tolerance: None,
+            },
+            &[coin(100_000, QUOTE_DENOM)],
+        )
+        .unwrap();
+    let held = suite.lp_balance(&suite.treasury);
+    assert!(!held.is_zero());
+
+    suite
+        .treasury_execute(TreasuryExecuteMsg::SetLpBurnAddress {
+            address: Some(burn.to_string()),
+        })
+        .unwrap();
+    suite.app.update_block(|block| {
+        block.time = block.time.plus_seconds(DEFAULT_LP_WITHDRAWAL_DELAY);
+    });
+    suite
+        .treasury_execute(TreasuryExecuteMsg::ExecuteLpDestination {})
+        .unwrap();
+
+    let minted_before = suite.stats().total_lp_minted;
+    suite.transfer("alice", "bob", 1_000_000);
+    let minted = suite.stats().total_lp_minted - minted_before;
+    assert!(!minted.is_zero());
+    assert_eq!(suite.lp_balance(&burn), minted);
+    assert_eq!(suite.lp_balance(&suite.treasury), held);
+}
+
+#[test]
+fn keeper_bounty_needs_enough_work_and_the_cooldown() {
+    let mut suite = Suite::new();
+    let keeper = Addr::unchecked("keeper");
+    let liquify = |suite: &mut Suite| {
+        suite
+            .app
+            .execute_contract(
+                keeper.clone(),
+                suite.treasury.clone(),
+                &TreasuryExecuteMsg::Liquify {},
+                &[],
+            )
+            .unwrap();
+        suite
+            .app
+            .wrap()
+            .query_balance(keeper.clone(), "ubounty")
+            .unwrap()
+            .amount
+            .u128()
+    };
+    // fills the treasury without the liquify triggered by the token
+    let fill = |suite: &mut Suite, amount: u128| {
+        let admin = suite.admin.to_string();
+        let treasury = suite.treasury.to_string();
+        suite.transfer(&admin, &treasury, amount);
+    };
+
+    let treasury = suite.treasury.clone();
+    suite
+        .app
+        .init_balance(&treasury, vec![coin(1_000, "ubounty")]);
+    suite
+        .app
+        .execute_contract(
+            suite.admin.clone(),
+            suite.token.clone(),
+            &TokenExecuteMsg::SetBuyBack { enable: false },
+            &[],
+        )
+        .unwrap();
+    suite
+        .treasury_execute(TreasuryExecuteMsg::SetKeeperBounty {
+            bounty: Some(KeeperBounty::Native {
+                denom: "ubounty".to_string(),
+                amount: Uint128::new(10),
+            }),
+            limits: KeeperBountyLimits {
+                min_amount: Uint128::new(40_000),
+                cooldown: 3_600,
+            },
+        })
+        .unwrap();
+
+    // too little babyTOKEN processed
+    fill(&mut suite, 30_000);
+    assert_eq!(liquify(&mut suite), 0);
+    // an empty treasury is not paid either
+    assert_eq!(liquify(&mut suite), 0);
+
+    fill(&mut suite, 50_000);
+    assert_eq!(liquify(&mut suite), 10);
+    let paid_at = suite.app.block_info().time.seconds();
+
+    // repeated calls wait for the cooldown
+    fill(&mut suite, 50_000);
+    assert_eq!(liquify(&mut suite), 10);
+    suite.app.update_block(|block| {
+        block.time = block.time.plus_seconds(3_600);
+    });
+    fill(&mut suite, 50_000);
+    assert_eq!(liquify(&mut suite), 20);
+
+    let limits: KeeperBountyLimitsResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            suite.treasury.clone(),
+            &TreasuryQueryMsg::KeeperBountyLimits {},
+        )
+        .unwrap();
+    assert_eq!(limits.last_paid_at, Some(paid_at + 3_600));
+}
+
+#[test]
+fn every_audited_action_is_named() {
+    let mut suite = Suite::new();
+    suite
+        .treasury_execute(TreasuryExecuteMsg::SetMinLiquify {
+            min_liquify_amt: Uint128::new(1_000),
+        })
+        .unwrap();
+    suite
+        .app
+        .execute_contract(
+            suite.admin.clone(),
+            suite.token.clone(),
+            &TokenExecuteMsg::SetBuyBack { enable: false },
+            &[],
+        )
+        .unwrap();
+
+    let res: AuditLogResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            suite.treasury.clone(),
+            &TreasuryQueryMsg::AuditLog {
+                start_after: None,
+                limit: Some(30),
+            },
+        )
+        .unwrap();
+    let treasury_actions: Vec<_> = res
+        .entries
+        .iter()
+        .map(|entry| entry.entry.attributes.first().cloned().unwrap())
+        .collect();
+    let res: TokenAuditLogResponse = suite
+        .app
+        .wrap()
+        .query_wasm_smart(
+            suite.token.clone(),
+            &TokenQueryMsg::AuditLog {
+                start_after: None,
+                limit: Some(30),
+            },
+        )
+        .unwrap();
+    let token_actions: Vec<_> = res
+        .entries
+        .iter()
+        .map(|entry| entry.entry.attributes.first().cloned().unwrap())
+        .collect();
+
+    for action in treasury_actions.iter().chain(token_actions.iter()) {
+        assert_eq!(action.key, "action");
+        assert!(!action.value.is_empty());
+    }
+    let names = |actions: &[Attribute]| -> Vec<String> {
+        actions.iter().map(|action| action.value.clone()).collect()
+    };
+    assert_eq!(
+        names(&treasury_actions),
+        [
+            "set_liquidity_pair",
+            "set_reflection_pair",
+            "set_fee_collector",
+            "set_min_liquify_amt"
+        ]
+    );
+    let token_names = names(&token_actions);
+    assert!(token_names.contains(&"set_pairlist".to_string()));
+    assert_eq!(token_names.last().unwrap(), "set_buyback");
+}
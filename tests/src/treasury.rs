@@ -0,0 +1,112 @@
+//! Treasury answering the token without any DEX behind it. Every `Liquify` is recorded with the
+//! babyTOKEN balance the treasury held at the time, and can be made to fail to test the token
+//! reaction to a failed liquify
+
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+    Uint128,
+};
+use cw20::{BalanceResponse, Cw20QueryMsg};
+use cw_storage_plus::Item;
+use qtum_reflection_token::msg::TreasuryInstantiateMsg;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::app::ContractWrapper;
+
+pub const TOKEN: Item<Addr> = Item::new("token");
+pub const LIQUIFY_CALLS: Item<Vec<LiquifyCall>> = Item::new("liquify_calls");
+pub const FAILING: Item<bool> = Item::new("failing");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Liquify {},
+    /// Makes every following `Liquify` fail
+    SetFailing {
+        failing: bool,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// babyTOKEN balance of the treasury, like the treasury `Balance` query
+    Balance {},
+    LiquifyCalls {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquifyCall {
+    pub sender: Addr,
+    pub height: u64,
+    /// babyTOKEN balance of the treasury when it was called
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LiquifyCallsResponse {
+    pub calls: Vec<LiquifyCall>,
+}
+
+pub fn contract() -> Box<ContractWrapper> {
+    Box::new(ContractWrapper::new(execute, instantiate, query))
+}
+
+/// Takes the message the token instantiates the treasury with
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: TreasuryInstantiateMsg,
+) -> StdResult<Response> {
+    TOKEN.save(deps.storage, &msg.token)?;
+    LIQUIFY_CALLS.save(deps.storage, &vec![])?;
+    FAILING.save(deps.storage, &false)?;
+    Ok(Response::default())
+}
+
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::Liquify {} => {
+            if FAILING.load(deps.storage)? {
+                return Err(StdError::generic_err("Liquify failed"));
+            }
+
+            let balance = query_balance(deps.as_ref(), &env)?;
+            let mut calls = LIQUIFY_CALLS.load(deps.storage)?;
+            calls.push(LiquifyCall {
+                sender: info.sender,
+                height: env.block.height,
+                balance,
+            });
+            LIQUIFY_CALLS.save(deps.storage, &calls)?;
+            Ok(Response::new()
+                .add_attribute("action", "liquify")
+                .add_attribute("balance", balance))
+        }
+        ExecuteMsg::SetFailing { failing } => {
+            FAILING.save(deps.storage, &failing)?;
+            Ok(Response::default())
+        }
+    }
+}
+
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Balance {} => to_json_binary(&query_balance(deps, &env)?),
+        QueryMsg::LiquifyCalls {} => to_json_binary(&LiquifyCallsResponse {
+            calls: LIQUIFY_CALLS.load(deps.storage)?,
+        }),
+    }
+}
+
+fn query_balance(deps: Deps, env: &Env) -> StdResult<Uint128> {
+    let res: BalanceResponse = deps.querier.query_wasm_smart(
+        TOKEN.load(deps.storage)?,
+        &Cw20QueryMsg::Balance {
+            address: env.contract.address.to_string(),
+        },
+    )?;
+    Ok(res.balance)
+}